@@ -5,12 +5,20 @@ pub mod jit {
     pub use burn_jit::{BoolElement, FloatElement, IntElement, JitBackend, JitRuntime};
 }
 
+/// Types and traits of NdArray backend.
+pub mod ndarray {
+    pub use burn::backend::ndarray::{
+        FloatNdArrayElement, IntNdArrayElement, NdArray, NdArrayDevice, QuantElement,
+    };
+}
+
 pub use burn::backend::wgpu::{self, WgpuDevice, WgpuRuntime};
 pub use burn::{
     backend::autodiff,
     tensor::backend::{AutodiffBackend, Backend},
 };
 pub use jit::JitBackend;
+pub use ndarray::NdArray;
 
 /// The backend marker to enable autodiff.
 pub type Autodiff<B> = autodiff::Autodiff<B>;
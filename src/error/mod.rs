@@ -2,14 +2,65 @@
 
 pub use gausplat_loader::source::polygon;
 
-use crate::spherical_harmonics::SH_DEGREE_MAX;
+use crate::{
+    render::gaussian_3d::jit::CONTRIBUTOR_COUNT_MAX, spherical_harmonics::SH_DEGREE_MAX,
+};
 
 /// Error variants.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// Error from invalid view ID of the appearance.
+    #[error(
+        "Invalid view ID of the appearance: {0}. \
+        It should be less than the view count {1}."
+    )]
+    InvalidAppearanceViewId(u32, usize),
+    /// Error from invalid count of the contributors.
+    #[error(
+        "Invalid count of the contributors: {0}. \
+        It should be no more than {CONTRIBUTOR_COUNT_MAX}."
+    )]
+    InvalidContributorCount(u32),
+    /// Error from invalid depth range.
+    #[error(
+        "Invalid depth range: [{0}, {1}). \
+        It should be finite and satisfy `0 < near < far`."
+    )]
+    InvalidDepthRange(f32, f32),
+    /// Error from invalid extent of the scene.
+    #[error("Invalid extent of the scene: {0}. It should be finite and positive.")]
+    InvalidExtent(f64),
+    /// Error from invalid factor of the radius.
+    #[error("Invalid factor of the radius: {0}. It should be finite and positive.")]
+    InvalidFactorRadius(f32),
+    /// Error from invalid scale of the 3D filter.
+    #[error(
+        "Invalid scale of the 3D filter: {0}. It should be finite and non-negative."
+    )]
+    InvalidFilter3dScale(f32),
+    /// Error from invalid variance of the low-pass filter.
+    #[error(
+        "Invalid variance of the low-pass filter: {0}. \
+        It should be finite and non-negative."
+    )]
+    InvalidFilterLowPass(f32),
     /// Error from invalid pixel count.
     #[error("Invalid pixel count: {0}. It should not be zero or excessively large.")]
     InvalidPixelCount(usize),
+    /// Error from invalid region.
+    #[error(
+        "Invalid region: {0:?}. \
+        It should be non-empty and within the image size {1:?}."
+    )]
+    InvalidRegion([u32; 4], [u32; 2]),
+    /// Error from invalid count of the split points.
+    #[error("Invalid count of the split points: {0}. It should be positive.")]
+    InvalidSplitCount(u32),
+    /// Error from invalid factor of the split scalings.
+    #[error(
+        "Invalid factor of the split scalings: {0}. It should be finite and positive."
+    )]
+    InvalidSplitScalingFactor(f64),
     /// Error from I/O.
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
@@ -31,6 +82,15 @@ pub enum Error {
     /// Error from mismatched tensor shape.
     #[error("Mismatched tensor shape: {0:?}. It should be {1:?}.")]
     MismatchedTensorShape(Vec<usize>, Vec<usize>),
+    /// Error from missing sums of the absolute 2D position gradient norms.
+    #[error(
+        "Missing sums of the absolute 2D position gradient norms. \
+        The renders should be accumulated with `positions_2d_grad_abs` enabled."
+    )]
+    MissingPositions2dGradAbsNormsSum,
+    /// Error from unsupported sort key bit count.
+    #[error("Unsupported sort key bit count: {0}. It should be 32 or 64.")]
+    UnsupportedSortKeyBitCount(u32),
     /// Error from unsupported spherical harmonics degree.
     #[error(
         "Unsupported spherical harmonics degree: {0}. \
@@ -5,6 +5,10 @@ pub use super::*;
 /// Rendering inputs (backward).
 #[derive(Clone, Debug)]
 pub struct RenderInput<B: Backend> {
+    /// `C_rgb_bg`
+    pub background_rgb: [f32; 3],
+    /// `(0 ~ 4)`
+    pub camera_model: u32,
     /// The shape is `[P, 3]`
     pub colors_rgb_3d: B::FloatTensorPrimitive,
     /// The shape is `[P, M * 3]` <- `[P, M, 3]`
@@ -15,6 +19,18 @@ pub struct RenderInput<B: Backend> {
     pub conics: B::FloatTensorPrimitive,
     /// The shape is `[P]`
     pub depths: B::FloatTensorPrimitive,
+    /// `[k1, k2, k3, k4, p1, p2]`
+    pub distortion: [f32; 6],
+    /// The shape is `[P, D]`
+    pub features: Option<B::FloatTensorPrimitive>,
+    /// `C_f`
+    pub filter_2d: f32,
+    /// `C_3d`
+    pub filter_3d_scale: f32,
+    /// `(0: Low-pass filter, 1: Mip filter)`
+    pub filter_mip_2d: u32,
+    /// The shape is `[P, 1]`
+    pub filters_3d: B::FloatTensorPrimitive,
     /// `f_x <- I_x / tan(Fov_x / 2) / 2`
     pub focal_length_x: f32,
     /// `f_y <- I_y / tan(Fov_y / 2) / 2`
@@ -30,39 +46,55 @@ pub struct RenderInput<B: Backend> {
     /// The shape is `[P, 3]`
     pub is_colors_rgb_3d_not_clamped: B::FloatTensorPrimitive,
     /// The shape is `[P, 1]`
+    pub opacities: B::FloatTensorPrimitive,
+    /// The shape is `[P]`
     pub opacities_3d: B::FloatTensorPrimitive,
     /// `P`
     pub point_count: u32,
     /// The shape is `[T]`
     pub point_indices: B::IntTensorPrimitive,
-    /// The shape is `[I_y, I_x]`
+    /// The shape is `[I_y', I_x']`
     pub point_rendered_counts: B::IntTensorPrimitive,
     /// The shape is `[P, 2]`
     pub positions_2d: B::FloatTensorPrimitive,
+    /// `(0: Disabled, 1: Enabled)`
+    pub positions_2d_grad_abs: u32,
     /// The shape is `[P, 3]`
     pub positions_3d: B::FloatTensorPrimitive,
     /// The shape is `[P, 2]`
     pub positions_3d_in_normalized: B::FloatTensorPrimitive,
     /// The shape is `[P]`
     pub radii: B::IntTensorPrimitive,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
     /// The shape is `[P, 4]`
     pub rotations: B::FloatTensorPrimitive,
     /// The shape is `[P, 3, 3]`
     pub rotations_matrix: B::FloatTensorPrimitive,
     /// The shape is `[P, 3]`
     pub scalings: B::FloatTensorPrimitive,
-    /// `I_x / T_x`
+    /// `I_x' / T_x`
     pub tile_count_x: u32,
-    /// `I_y / T_y`
+    /// `I_y' / T_y`
     pub tile_count_y: u32,
-    /// The shape is `[I_y / T_y, I_x / T_x, 2]`
+    /// The shape is `[I_y' / T_y, I_x' / T_x, 2]`
     pub tile_point_ranges: B::IntTensorPrimitive,
-    /// The shape is `[I_y, I_x]`
+    /// The shape is `[I_y', I_x']`
     pub transmittances: B::FloatTensorPrimitive,
-    /// `tan(Fov_x / 2) * (C_f + 1)`
-    pub view_bound_x: f32,
-    /// `tan(Fov_y / 2) * (C_f + 1)`
-    pub view_bound_y: f32,
+    /// `(I_x - C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_max: f32,
+    /// `-(C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_min: f32,
+    /// `(I_y - C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_max: f32,
+    /// `-(C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_min: f32,
     /// `[3]`
     pub view_position: [f32; 3],
     /// `[3 (+ 1), 3 + 1]`
@@ -76,12 +108,25 @@ pub struct RenderOutput<B: Backend> {
     ///
     /// It is the gradient of `colors_rgb_2d` with respect to `colors_sh`.
     pub colors_sh_grad: B::FloatTensorPrimitive,
+    /// The shape is `[P, D]`
+    ///
+    /// It is the gradient of `features_2d` with respect to `features`.
+    pub features_grad: Option<B::FloatTensorPrimitive>,
+    /// The shape is `[2]`
+    ///
+    /// It is the gradient of `colors_rgb_2d` with respect to `[f_x, f_y]`.
+    pub focal_lengths_grad: B::FloatTensorPrimitive,
     /// The shape is `[P, 1]`
     ///
     /// It is the gradient of `colors_rgb_2d` with respect to `colors_sh_degree_max`.
     pub opacities_grad: B::FloatTensorPrimitive,
     /// The shape is `[P]`
     ///
+    /// It is the norm of the absolute gradients of the 2D positions,
+    /// which is `None` unless they are accumulated.
+    pub positions_2d_grad_abs_norm: Option<B::FloatTensorPrimitive>,
+    /// The shape is `[P]`
+    ///
     /// It is the gradient norm of the 2D positions.
     pub positions_2d_grad_norm: B::FloatTensorPrimitive,
     /// The shape is `[P, 3]`
@@ -96,4 +141,9 @@ pub struct RenderOutput<B: Backend> {
     ///
     /// It is the gradient of `colors_rgb_2d` with respect to `scalings`.
     pub scalings_grad: B::FloatTensorPrimitive,
+    /// The shape is `[6]`
+    ///
+    /// It is the gradient of `colors_rgb_2d` with respect to `view_transform`
+    /// in tangent space, i.e., `[ρ[3], φ[3]]` of `exp(ξ) * Tv`.
+    pub view_transform_grad: B::FloatTensorPrimitive,
 }
@@ -0,0 +1,168 @@
+//! CPU kernels.
+//!
+//! They mirror the [JIT kernels](super::super::jit::kernel) step by step.
+
+pub mod rank;
+pub mod rasterize;
+pub mod rasterize_backward;
+pub mod rasterize_features;
+pub mod rasterize_features_backward;
+pub mod rasterize_labels;
+pub mod segment;
+pub mod transform;
+pub mod transform_backward;
+
+pub use super::super::jit::{
+    CONTRIBUTOR_COUNT_MAX, FACTOR_RADIUS, FILTER_LOW_PASS, FILTER_MIP_2D, TILE_COUNT_MAX,
+    TILE_COUNT_MAX_32_BIT, TILE_SIZE_X, TILE_SIZE_Y, VIEW_BOUND_MARGIN,
+};
+pub use crate::spherical_harmonics::{SH_COEF, SH_COUNT_MAX};
+
+/// A 2D vector.
+pub type Vec2 = [f32; 2];
+/// A 3D vector.
+pub type Vec3 = [f32; 3];
+/// A 4D vector.
+pub type Vec4 = [f32; 4];
+/// A 3x3 matrix in **column-major order**, i.e., `M[col][row]`.
+pub type Mat3 = [[f32; 3]; 3];
+/// A 2x3 matrix in **row-major order**, i.e., `M[row][col]`.
+pub type Mat2x3 = [[f32; 3]; 2];
+
+/// `α'_max`
+pub const OPACITY_2D_MAX: f32 = 252.0 / 255.0;
+/// `α'_min`
+pub const OPACITY_2D_MIN: f32 = 1.0 / 255.0;
+/// `t_med`
+pub const TRANSMITTANCE_MEDIAN: f32 = 0.5;
+/// `(1 - α'_max)^2`
+pub const TRANSMITTANCE_MIN: f32 = (1.0 - OPACITY_2D_MAX) * (1.0 - OPACITY_2D_MAX);
+
+/// `A[3] × B[3]`
+#[inline]
+pub(crate) fn cross_3(
+    a: &Vec3,
+    b: &Vec3,
+) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[inline]
+pub(crate) fn dot_3(
+    a: &Vec3,
+    b: &Vec3,
+) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline]
+pub(crate) fn mat3_mul(
+    a: &Mat3,
+    b: &Mat3,
+) -> Mat3 {
+    let mut m = Mat3::default();
+    for c in 0..3 {
+        for r in 0..3 {
+            m[c][r] = a[0][r] * b[c][0] + a[1][r] * b[c][1] + a[2][r] * b[c][2];
+        }
+    }
+    m
+}
+
+#[inline]
+pub(crate) fn mat3_mul_vec3(
+    m: &Mat3,
+    v: &Vec3,
+) -> Vec3 {
+    [
+        m[0][0] * v[0] + m[1][0] * v[1] + m[2][0] * v[2],
+        m[0][1] * v[0] + m[1][1] * v[1] + m[2][1] * v[2],
+        m[0][2] * v[0] + m[1][2] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+#[inline]
+pub(crate) fn mat3_transpose(m: &Mat3) -> Mat3 {
+    [
+        [m[0][0], m[1][0], m[2][0]],
+        [m[0][1], m[1][1], m[2][1]],
+        [m[0][2], m[1][2], m[2][2]],
+    ]
+}
+
+/// `V^t[1, 2] * M[2, 3]`
+#[inline]
+pub(crate) fn vec2_mul_mat2x3(
+    v: &Vec2,
+    m: &Mat2x3,
+) -> Vec3 {
+    [0, 1, 2].map(|col| v[0] * m[0][col] + v[1] * m[1][col])
+}
+
+/// `V^t[1, 3] * M[3, 3]`
+#[inline]
+pub(crate) fn vec3_mul_mat3(
+    v: &Vec3,
+    m: &Mat3,
+) -> Vec3 {
+    [dot_3(v, &m[0]), dot_3(v, &m[1]), dot_3(v, &m[2])]
+}
+
+/// `M[2, 3] * R[3, 3]`
+#[inline]
+pub(crate) fn mat2x3_mul_mat3(
+    m: &Mat2x3,
+    r: &Mat3,
+) -> Mat2x3 {
+    let mut t = Mat2x3::default();
+    for row in 0..2 {
+        for col in 0..3 {
+            t[row][col] =
+                m[row][0] * r[col][0] + m[row][1] * r[col][1] + m[row][2] * r[col][2];
+        }
+    }
+    t
+}
+
+/// Return the rotation `R_v` and translation `T_v` of the view transform.
+#[inline]
+pub(crate) fn view_rotation_translation(view_transform: &[[f32; 4]; 4]) -> (Mat3, Vec3) {
+    let m = view_transform;
+    (
+        [
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]],
+        ],
+        [m[3][0], m[3][1], m[3][2]],
+    )
+}
+
+/// `y = e^x / (1 + e^x)`
+#[inline]
+pub(crate) fn sigmoid(x: f32) -> f32 {
+    let x_exp = x.exp();
+    x_exp / (1.0 + x_exp)
+}
+
+/// Return `M[3, 3]` from the flattened columns.
+#[inline]
+pub(crate) fn mat3_from_slice(a: &[f32]) -> Mat3 {
+    [[a[0], a[1], a[2]], [a[3], a[4], a[5]], [a[6], a[7], a[8]]]
+}
+
+#[inline]
+pub(crate) fn vec3_from_slice(a: &[f32]) -> Vec3 {
+    [a[0], a[1], a[2]]
+}
+
+/// The normalized vector.
+#[inline]
+pub(crate) fn normalize_3(x: &Vec3) -> Vec3 {
+    let norm = dot_3(x, x).sqrt();
+    [x[0] / norm, x[1] / norm, x[2] / norm]
+}
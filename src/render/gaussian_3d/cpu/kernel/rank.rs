@@ -0,0 +1,169 @@
+//! Ranking and sorting the points.
+//!
+//! It mirrors [`jit::kernel::rank`](super::super::super::jit::kernel::rank)
+//! and [`jit::kernel::sort`](super::super::super::jit::kernel::sort).
+
+pub use super::*;
+
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `D_far`
+    pub depth_far: f32,
+    /// `D_near`
+    pub depth_near: f32,
+    /// `P`
+    pub point_count: u32,
+    /// `(1: 32-bit, 2: 64-bit)`
+    pub point_order_word_count: u32,
+    /// `I_x / T_x`
+    pub tile_count_x: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[P]`
+    pub depths: &'a [f32],
+    /// `[P, 4]`
+    pub point_tile_bounds: &'a [u32],
+    /// `[P]`
+    pub radii: &'a [u32],
+    /// `[P]`
+    pub tile_touched_counts: &'a [u32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[T]`
+    ///
+    /// They are sorted by the point orders.
+    pub point_indices: Vec<u32>,
+    /// `[T]`
+    ///
+    /// They are sorted.
+    /// The 32-bit point orders are zero-extended.
+    pub point_orders: Vec<u64>,
+}
+
+/// The maximum of depth order (16 bits).
+pub const DEPTH_ORDER_MAX: u32 = (1 << 16) - 1;
+
+/// Rank the points by its tile index and depth, and then sort them stably.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // P
+    let point_count = arguments.point_count as usize;
+    // T
+    let tile_point_count = inputs.tile_touched_counts[..point_count]
+        .iter()
+        .map(|&count| count as usize)
+        .sum::<usize>();
+
+    // Computing the orders and indices of point
+
+    let mut items = Vec::with_capacity(tile_point_count);
+    for index in 0..point_count {
+        if inputs.radii[index] == 0 {
+            continue;
+        }
+
+        let bound = &inputs.point_tile_bounds[index * 4..index * 4 + 4];
+        let depth = inputs.depths[index];
+        for tile_y in bound[3]..bound[2] {
+            for tile_x in bound[1]..bound[0] {
+                let tile_index = tile_y * arguments.tile_count_x + tile_x;
+                let point_order = match arguments.point_order_word_count {
+                    2 => make_point_order_64(tile_index, depth),
+                    _ => make_point_order(
+                        tile_index,
+                        depth,
+                        arguments.depth_near,
+                        arguments.depth_far,
+                    ) as u64,
+                };
+                items.push((point_order, index as u32));
+            }
+        }
+    }
+
+    // Sorting the points by its tile index and depth
+
+    items.par_sort_by_key(|item| item.0);
+
+    let (point_orders, point_indices) = items.into_iter().unzip();
+
+    Outputs {
+        point_indices,
+        point_orders,
+    }
+}
+
+/// Computing the point order.
+///
+/// It is the same as `make_point_order` in the JIT kernel.
+///
+/// The depth order is normalized from the bits of depth within `[D_near, D_far)`.
+#[inline]
+pub fn make_point_order(
+    tile_index: u32,
+    depth: f32,
+    depth_near: f32,
+    depth_far: f32,
+) -> u32 {
+    let depth_bits_near = depth_near.to_bits();
+    let depth_bits_range = depth_far.to_bits().wrapping_sub(depth_bits_near);
+    let depth_order = (depth.to_bits().wrapping_sub(depth_bits_near) as f32
+        / depth_bits_range as f32
+        * (DEPTH_ORDER_MAX + 1) as f32) as u32;
+    (tile_index << 16) | depth_order.min(DEPTH_ORDER_MAX)
+}
+
+/// Computing the point order (64-bit).
+///
+/// It is the same as `make_point_order_64` in the JIT kernel.
+///
+/// The tile index is in the high order word,
+/// and the bits of depth are in the low order word.
+#[inline]
+pub fn make_point_order_64(
+    tile_index: u32,
+    depth: f32,
+) -> u64 {
+    ((tile_index as u64) << 32) | depth.to_bits() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn sort_key_bit_count_render_ndarray() {
+        let scene = scene_sixstars::<NdArray>(&Default::default());
+        let target = scene
+            .render(&VIEW, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .into_data();
+        let output = scene
+            .render(
+                &VIEW,
+                &Gaussian3dRenderOptions::default().with_sort_key_bit_count(64),
+            )
+            .unwrap()
+            .colors_rgb_2d
+            .into_data();
+        output.assert_approx_eq(&target, 3);
+
+        let output = scene.render(
+            &VIEW,
+            &Gaussian3dRenderOptions::default().with_sort_key_bit_count(48),
+        );
+        assert!(output.is_err());
+    }
+}
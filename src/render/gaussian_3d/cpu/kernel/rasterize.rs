@@ -0,0 +1,434 @@
+//! Rasterizing the point to the image.
+//!
+//! It mirrors [`jit::kernel::rasterize`](super::super::super::jit::kernel::rasterize).
+
+pub use super::*;
+
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `C_rgb_bg`
+    pub background_rgb: Vec3,
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `K` (0 ~ K_max)
+    pub contributor_count: u32,
+    /// `I_x`
+    pub image_size_x: u32,
+    /// `I_y`
+    pub image_size_y: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[P, 3]`
+    pub colors_rgb_3d: &'a [f32],
+    /// `[P, 3]`
+    pub conics: &'a [f32],
+    /// `[P]`
+    pub depths: &'a [f32],
+    /// `[P]` (Outer)
+    pub opacities_3d: &'a [f32],
+    /// `[T]`
+    pub point_indices: &'a [u32],
+    /// `[P, 2]`
+    pub positions_2d: &'a [f32],
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
+    pub tile_point_ranges: &'a [u32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[I_y', I_x', 3]`
+    pub colors_rgb_2d: Vec<f32>,
+    /// `[I_y', I_x', K]` (-1 ~ P)
+    pub contributor_indices_2d: Vec<i32>,
+    /// `[I_y', I_x', K]`
+    pub contributor_weights_2d: Vec<f32>,
+    /// `[I_y', I_x']`
+    pub depths_2d: Vec<f32>,
+    /// `[I_y', I_x']`
+    pub depths_2d_median: Vec<f32>,
+    /// `[I_y', I_x']`
+    pub point_rendered_counts: Vec<u32>,
+    /// `[I_y', I_x']`
+    pub transmittances: Vec<f32>,
+}
+
+/// `i_none`
+///
+/// The index of the absent contributors.
+pub const CONTRIBUTOR_INDEX_NONE: i32 = -1;
+
+/// The outputs of a pixel.
+#[derive(Clone, Copy, Debug, Default)]
+struct Output {
+    color_rgb_2d: Vec3,
+    contributor_indices: [i32; CONTRIBUTOR_COUNT_MAX as usize],
+    contributor_weights: [f32; CONTRIBUTOR_COUNT_MAX as usize],
+    depth_2d: f32,
+    depth_2d_median: f32,
+    point_rendered_count: u32,
+    transmittance: f32,
+}
+
+/// Rasterize the point to the image.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // K
+    let contributor_count = arguments.contributor_count as usize;
+    // I_x'
+    let region_size_x = arguments.region_size_x as usize;
+    // I_y'
+    let region_size_y = arguments.region_size_y as usize;
+
+    let pixels = (0..region_size_y * region_size_x)
+        .into_par_iter()
+        .map(|pixel_index| {
+            rasterize(
+                arguments,
+                inputs,
+                (pixel_index % region_size_x) as u32,
+                (pixel_index / region_size_x) as u32,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut outputs = Outputs {
+        colors_rgb_2d: Vec::with_capacity(pixels.len() * 3),
+        contributor_indices_2d: Vec::with_capacity(pixels.len() * contributor_count),
+        contributor_weights_2d: Vec::with_capacity(pixels.len() * contributor_count),
+        depths_2d: Vec::with_capacity(pixels.len()),
+        depths_2d_median: Vec::with_capacity(pixels.len()),
+        point_rendered_counts: Vec::with_capacity(pixels.len()),
+        transmittances: Vec::with_capacity(pixels.len()),
+    };
+    for pixel in pixels {
+        outputs.colors_rgb_2d.extend(pixel.color_rgb_2d);
+        outputs
+            .contributor_indices_2d
+            .extend(&pixel.contributor_indices[..contributor_count]);
+        outputs
+            .contributor_weights_2d
+            .extend(&pixel.contributor_weights[..contributor_count]);
+        outputs.depths_2d.push(pixel.depth_2d);
+        outputs.depths_2d_median.push(pixel.depth_2d_median);
+        outputs
+            .point_rendered_counts
+            .push(pixel.point_rendered_count);
+        outputs.transmittances.push(pixel.transmittance);
+    }
+
+    outputs
+}
+
+/// Rasterizing the pixel at `(x, y)` in the region.
+fn rasterize(
+    arguments: &Arguments,
+    inputs: &Inputs,
+    x: u32,
+    y: u32,
+) -> Output {
+    // Specifying the parameters
+
+    // (0 ~ I_x, 0 ~ I_y)
+    let pixel = [x + arguments.region_offset_x, y + arguments.region_offset_y];
+    // The tiles are aligned to the image rather than the region
+    let tile_index = ((pixel[1] / TILE_SIZE_Y - arguments.region_offset_y / TILE_SIZE_Y)
+        * arguments.tile_count_x
+        + pixel[0] / TILE_SIZE_X
+        - arguments.region_offset_x / TILE_SIZE_X) as usize;
+    let point_range = &inputs.tile_point_ranges[tile_index * 2..tile_index * 2 + 2];
+    let position_pixel = [pixel[0] as f32, pixel[1] as f32];
+    let image_size_x = arguments.image_size_x as f32;
+    let is_equirectangular =
+        arguments.camera_model == transform::CAMERA_MODEL_EQUIRECTANGULAR;
+    let mut color_rgb_2d = Vec3::default();
+    // K
+    let contributor_count = arguments.contributor_count as usize;
+    // [K] (Sorted by the weights in descending order)
+    let mut contributor_indices =
+        [CONTRIBUTOR_INDEX_NONE; CONTRIBUTOR_COUNT_MAX as usize];
+    // [K]
+    let mut contributor_weights = [0.0; CONTRIBUTOR_COUNT_MAX as usize];
+    let mut depth_2d = 0.0;
+    let mut depth_2d_median = 0.0;
+    let mut point_rendered_count = 0;
+    let mut transmittance_state = 1.0;
+
+    // Processing the points of the tile
+
+    for (point_rendered_state, &point_index) in inputs.point_indices
+        [point_range[0] as usize..point_range[1].max(point_range[0]) as usize]
+        .iter()
+        .enumerate()
+    {
+        let point_index = point_index as usize;
+
+        // Computing the density of the point in the pixel
+        // D[2, 1] = Pv'[2, 1] - Px[2, 1]
+        // D.x is wrapped around the seam for the equirectangular camera model
+        // σ[n] = e^(-0.5 * D^t[1, 2] * Σ'^-1[2, 2] * D[2, 1])[n]
+
+        let conic = &inputs.conics[point_index * 3..point_index * 3 + 3];
+        let mut position_offset = [
+            inputs.positions_2d[point_index * 2] - position_pixel[0],
+            inputs.positions_2d[point_index * 2 + 1] - position_pixel[1],
+        ];
+        if is_equirectangular {
+            position_offset[0] -=
+                image_size_x * (position_offset[0] / image_size_x).round();
+        }
+        let density = density(conic, &position_offset);
+
+        // Skipping if the density is greater than one
+
+        if density > 1.0 {
+            continue;
+        }
+
+        // Computing the 2D opacity of the point in the pixel
+        // α'[n] = α[n] * σ[n]
+
+        let opacity_3d = inputs.opacities_3d[point_index];
+        let opacity_2d = (opacity_3d * density).min(OPACITY_2D_MAX);
+
+        // Skipping if the 2D opacity is too low
+
+        if opacity_2d < OPACITY_2D_MIN {
+            continue;
+        }
+
+        // Computing the next transmittance
+        // t[n + 1] = t[n] * (1 - α'[n])
+
+        let transmittance = transmittance_state * (1.0 - opacity_2d);
+
+        // Leaving before the transmittance is too low
+
+        if transmittance < TRANSMITTANCE_MIN {
+            break;
+        }
+
+        // Blending the 3D colors of the pixel into the 2D color in RGB space
+        // C_rgb'[n + 1] = C_rgb'[n] + C_rgb[n] * α'[n] * t[n]
+
+        let color_rgb_3d = &inputs.colors_rgb_3d[point_index * 3..point_index * 3 + 3];
+        for channel in 0..3 {
+            color_rgb_2d[channel] +=
+                color_rgb_3d[channel] * opacity_2d * transmittance_state;
+        }
+
+        // Blending the depths of the pixel into the 2D depth
+        // D'[n + 1] = D'[n] + D[n] * α'[n] * t[n]
+
+        let depth = inputs.depths[point_index];
+        depth_2d += depth * opacity_2d * transmittance_state;
+
+        // Finding the median depth where the transmittance crosses the half
+        // t[n] >= 0.5 > t[n + 1]
+
+        if transmittance_state >= TRANSMITTANCE_MEDIAN
+            && transmittance < TRANSMITTANCE_MEDIAN
+        {
+            depth_2d_median = depth;
+        }
+
+        // Recording the point if it is one of the top contributors of the pixel
+        // w[n] = α'[n] * t[n]
+        //
+        // The lighter contributors are shifted down by insertion.
+
+        let weight = opacity_2d * transmittance_state;
+        if contributor_count != 0 && weight > contributor_weights[contributor_count - 1] {
+            let mut rank = contributor_count - 1;
+            while rank > 0 && contributor_weights[rank - 1] < weight {
+                contributor_indices[rank] = contributor_indices[rank - 1];
+                contributor_weights[rank] = contributor_weights[rank - 1];
+                rank -= 1;
+            }
+            contributor_indices[rank] = point_index as i32;
+            contributor_weights[rank] = weight;
+        }
+
+        // Updating the states of the pixel
+
+        point_rendered_count = point_rendered_state as u32 + 1;
+        transmittance_state = transmittance;
+    }
+
+    // Blending the background color of the pixel into the 2D color in RGB space
+    // C_rgb' = C_rgb'[N] + C_rgb_bg * t[N]
+
+    for channel in 0..3 {
+        color_rgb_2d[channel] += arguments.background_rgb[channel] * transmittance_state;
+    }
+
+    Output {
+        color_rgb_2d,
+        contributor_indices,
+        contributor_weights,
+        depth_2d,
+        depth_2d_median,
+        point_rendered_count,
+        transmittance: transmittance_state,
+    }
+}
+
+/// `σ = e^(-0.5 * D^t[1, 2] * Σ'^-1[2, 2] * D[2, 1])`
+#[inline]
+pub(crate) fn density(
+    conic: &[f32],
+    position_offset: &Vec2,
+) -> f32 {
+    let [d_x, d_y] = *position_offset;
+    (-0.5 * (conic[0] * d_x * d_x + 2.0 * conic[1] * d_x * d_y + conic[2] * d_y * d_y))
+        .exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn background_rgb_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[
+                ([0.0, 0.5, 1.0], [0.1, -0.1, 4.0], 0.5, 0.3),
+                ([1.0, 1.0, 0.0], [-0.4, 0.2, 3.0], 0.3, 0.2),
+            ],
+            &device,
+        );
+        let background_rgb = [1.0, 0.25, 0.0];
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_background_rgb(background_rgb),
+            )
+            .unwrap()
+            .colors_rgb_2d;
+
+        let target = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .composite_background(
+                Tensor::<NdArray, 1>::from_floats(background_rgb, &device)
+                    .reshape([1, 1, 3]),
+            );
+
+        output.into_data().assert_approx_eq(&target.into_data(), 5);
+    }
+
+    #[test]
+    fn contributors_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.0, 0.0], [-0.5, 0.0, 4.0], 0.1, 0.2),
+                ([0.0, 1.0, 0.0], [0.5, 0.0, 4.0], 0.1, 0.2),
+                ([0.0, 0.0, 1.0], [0.0, 0.5, 4.0], 0.1, 0.2),
+            ],
+            &device,
+        );
+
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        assert!(output.contributor_indices_2d.is_none());
+        assert!(output.contributor_weights_2d.is_none());
+
+        let options = Gaussian3dRenderOptions::default().with_contributor_count(2);
+        let output = scene.render(&VIEW_IDENTITY, &options).unwrap();
+        let contributor_indices_2d = output.contributor_indices_2d.unwrap();
+        let contributor_weights_2d = output.contributor_weights_2d.unwrap();
+        assert_eq!(contributor_indices_2d.dims(), [32, 32, 2]);
+        assert_eq!(contributor_weights_2d.dims(), [32, 32, 2]);
+
+        // The weights are sorted in descending order.
+        let weights_first = contributor_weights_2d
+            .to_owned()
+            .slice([0..32, 0..32, 0..1]);
+        let weights_second = contributor_weights_2d.slice([0..32, 0..32, 1..2]);
+        assert!(weights_second.sub(weights_first).max().into_scalar() <= 0.0);
+
+        // The contributors are absent at the empty pixel.
+        assert_eq!(
+            contributor_indices_2d
+                .slice([0..1, 0..1, 0..2])
+                .into_data()
+                .convert::<i64>()
+                .into_vec::<i64>()
+                .unwrap(),
+            [-1, -1]
+        );
+
+        let contributors = scene
+            .pick(&VIEW_IDENTITY, [12, 16], &Default::default())
+            .unwrap();
+        assert_eq!(contributors[0].0, 0);
+        assert!(contributors[0].1 > 0.0);
+        assert!(contributors.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let contributors = scene
+            .pick(&VIEW_IDENTITY, [0, 0], &Default::default())
+            .unwrap();
+        assert!(contributors.is_empty());
+
+        let output = scene.render(
+            &VIEW_IDENTITY,
+            &Gaussian3dRenderOptions::default().with_contributor_count(17),
+        );
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn depths_2d_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[([1.0, 1.0, 1.0], [0.0, 0.0, 4.0], 0.99, 1.0)],
+            &device,
+        );
+
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        assert_eq!(output.depths_2d.dims(), [32, 32]);
+        assert_eq!(output.depths_2d_median.dims(), [32, 32]);
+
+        let output_depth_2d = output
+            .depths_2d
+            .to_owned()
+            .slice([15..16, 15..16])
+            .into_scalar();
+        assert!(
+            output_depth_2d > 3.9 && output_depth_2d < 4.0,
+            "{output_depth_2d}"
+        );
+
+        let target = 4.0;
+        let output_depth_2d_median = output
+            .depths_2d_median
+            .slice([15..16, 15..16])
+            .into_scalar();
+        assert!(
+            (output_depth_2d_median - target).abs() < 1e-3,
+            "{output_depth_2d_median}"
+        );
+    }
+}
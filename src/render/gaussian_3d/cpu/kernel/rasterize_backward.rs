@@ -0,0 +1,450 @@
+//! Rasterizing the point to the image (backward).
+//!
+//! It mirrors [`jit::kernel::rasterize_backward`](super::super::super::jit::kernel::rasterize_backward).
+
+pub use super::*;
+
+use rasterize::density;
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `C_rgb_bg`
+    pub background_rgb: Vec3,
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `I_x`
+    pub image_size_x: u32,
+    /// `I_y`
+    pub image_size_y: u32,
+    /// `P`
+    pub point_count: u32,
+    /// `(0: Disabled, 1: Enabled)`
+    pub positions_2d_grad_abs: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[I_y', I_x']`
+    pub alphas_2d_grad: &'a [f32],
+    /// `[I_y', I_x', 3]`
+    pub colors_rgb_2d_grad: &'a [f32],
+    /// `[P, 3]`
+    pub colors_rgb_3d: &'a [f32],
+    /// `[P, 3]`
+    pub conics: &'a [f32],
+    /// `[P]`
+    pub depths: &'a [f32],
+    /// `[I_y', I_x']`
+    pub depths_2d_grad: &'a [f32],
+    /// `[P]` (Outer)
+    pub opacities_3d: &'a [f32],
+    /// `[T]`
+    pub point_indices: &'a [u32],
+    /// `[I_y', I_x']`
+    pub point_rendered_counts: &'a [u32],
+    /// `[P, 2]`
+    pub positions_2d: &'a [f32],
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
+    pub tile_point_ranges: &'a [u32],
+    /// `[I_y', I_x']`
+    pub transmittances: &'a [f32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[P, 3]`
+    pub colors_rgb_3d_grad: Vec<f32>,
+    /// `[P, 3]`
+    pub conics_grad: Vec<f32>,
+    /// `[P]`
+    pub depths_grad: Vec<f32>,
+    /// `[P]` (Outer)
+    pub opacities_3d_grad: Vec<f32>,
+    /// `[P, 2]`
+    pub positions_2d_grad: Vec<f32>,
+    /// `[P, 2]`
+    pub positions_2d_grad_abs: Vec<f32>,
+}
+
+impl Outputs {
+    /// Zeroed gradients of `point_count` points.
+    fn zeros(point_count: usize) -> Self {
+        Self {
+            colors_rgb_3d_grad: vec![0.0; point_count * 3],
+            conics_grad: vec![0.0; point_count * 3],
+            depths_grad: vec![0.0; point_count],
+            opacities_3d_grad: vec![0.0; point_count],
+            positions_2d_grad: vec![0.0; point_count * 2],
+            positions_2d_grad_abs: vec![0.0; point_count * 2],
+        }
+    }
+
+    /// Accumulating the gradients of `other`.
+    fn add(
+        mut self,
+        other: Self,
+    ) -> Self {
+        [
+            (&mut self.colors_rgb_3d_grad, other.colors_rgb_3d_grad),
+            (&mut self.conics_grad, other.conics_grad),
+            (&mut self.depths_grad, other.depths_grad),
+            (&mut self.opacities_3d_grad, other.opacities_3d_grad),
+            (&mut self.positions_2d_grad, other.positions_2d_grad),
+            (&mut self.positions_2d_grad_abs, other.positions_2d_grad_abs),
+        ]
+        .into_iter()
+        .for_each(|(target, source)| {
+            target.iter_mut().zip(source).for_each(|(t, s)| *t += s);
+        });
+        self
+    }
+}
+
+/// Compute the gradient of the rasterization.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // I_x'
+    let region_size_x = arguments.region_size_x;
+    // P
+    let point_count = arguments.point_count as usize;
+
+    (0..arguments.region_size_y)
+        .into_par_iter()
+        .fold(
+            || Outputs::zeros(point_count),
+            |mut outputs, y| {
+                for x in 0..region_size_x {
+                    rasterize_backward(arguments, inputs, &mut outputs, x, y);
+                }
+                outputs
+            },
+        )
+        .reduce(|| Outputs::zeros(point_count), Outputs::add)
+}
+
+/// Computing the gradients of the pixel at `(x, y)` in the region.
+fn rasterize_backward(
+    arguments: &Arguments,
+    inputs: &Inputs,
+    outputs: &mut Outputs,
+    x: u32,
+    y: u32,
+) {
+    // Specifying the parameters
+
+    let pixel_index = (y * arguments.region_size_x + x) as usize;
+    // (0 ~ I_x, 0 ~ I_y)
+    let pixel = [x + arguments.region_offset_x, y + arguments.region_offset_y];
+    // The tiles are aligned to the image rather than the region
+    let tile_index = ((pixel[1] / TILE_SIZE_Y - arguments.region_offset_y / TILE_SIZE_Y)
+        * arguments.tile_count_x
+        + pixel[0] / TILE_SIZE_X
+        - arguments.region_offset_x / TILE_SIZE_X) as usize;
+    let point_range_start = inputs.tile_point_ranges[tile_index * 2] as usize;
+    let point_rendered_count = inputs.point_rendered_counts[pixel_index] as usize;
+    let position_pixel = [pixel[0] as f32, pixel[1] as f32];
+    let image_size_x = arguments.image_size_x as f32;
+    let is_equirectangular =
+        arguments.camera_model == transform::CAMERA_MODEL_EQUIRECTANGULAR;
+    let color_rgb_2d_grad =
+        vec3_from_slice(&inputs.colors_rgb_2d_grad[pixel_index * 3..]);
+    let alpha_2d_grad = inputs.alphas_2d_grad[pixel_index];
+    let depth_2d_grad = inputs.depths_2d_grad[pixel_index];
+    // C_rgb'[N] = C_rgb_bg
+    let mut color_rgb_2d_state = arguments.background_rgb;
+    let mut color_rgb_3d_state = Vec3::default();
+    let mut depth_2d_state = 0.0;
+    let mut depth_3d_state = 0.0;
+    let mut opacity_2d_state = 0.0;
+    let transmittance_last = inputs.transmittances[pixel_index];
+    let mut transmittance_state = transmittance_last;
+
+    // Processing the rendered points of the tile in reverse order
+
+    for &point_index in inputs.point_indices
+        [point_range_start..point_range_start + point_rendered_count]
+        .iter()
+        .rev()
+    {
+        let point_index = point_index as usize;
+
+        // Computing the density of the point in the pixel
+        //
+        // D.x is wrapped around the seam for the equirectangular camera model
+
+        let conic = &inputs.conics[point_index * 3..point_index * 3 + 3];
+        let mut position_offset = [
+            inputs.positions_2d[point_index * 2] - position_pixel[0],
+            inputs.positions_2d[point_index * 2 + 1] - position_pixel[1],
+        ];
+        if is_equirectangular {
+            position_offset[0] -=
+                image_size_x * (position_offset[0] / image_size_x).round();
+        }
+        let density = density(conic, &position_offset);
+
+        // Skipping if the density is greater than one
+
+        if density > 1.0 {
+            continue;
+        }
+
+        // Computing the 2D opacity of the point in the pixel
+
+        let opacity_3d = inputs.opacities_3d[point_index];
+        let opacity_2d = (opacity_3d * density).min(OPACITY_2D_MAX);
+
+        // Skipping if the 2D opacity is too low
+
+        if opacity_2d < OPACITY_2D_MIN {
+            continue;
+        }
+
+        // Updating the states of the pixel
+        //
+        // C_rgb'[n] = C_rgb[n + 1] * α'[n + 1] +
+        //             C_rgb'[n + 1] * (1 - α'[n + 1])
+        // D'[n]     = D[n + 1] * α'[n + 1] +
+        //             D'[n + 1] * (1 - α'[n + 1])
+        // t[n]      = t[n + 1] / (1 - α'[n])
+
+        for channel in 0..3 {
+            color_rgb_2d_state[channel] = color_rgb_3d_state[channel] * opacity_2d_state
+                + color_rgb_2d_state[channel] * (1.0 - opacity_2d_state);
+        }
+        color_rgb_3d_state = vec3_from_slice(&inputs.colors_rgb_3d[point_index * 3..]);
+        depth_2d_state =
+            depth_3d_state * opacity_2d_state + depth_2d_state * (1.0 - opacity_2d_state);
+        depth_3d_state = inputs.depths[point_index];
+        opacity_2d_state = opacity_2d;
+        transmittance_state /= 1.0 - opacity_2d;
+
+        // Computing the gradients of the point
+        //
+        // ∂L/∂C_rgb[n] = ∂L/∂C_rgb' * α'[n] * t[n]
+        // ∂L/∂α'[n]    =〈∂L/∂C_rgb', t[n] * (C_rgb[n] - C_rgb'[n])〉
+
+        let color_rgb_3d_grad =
+            color_rgb_2d_grad.map(|g| opacity_2d * transmittance_state * g);
+        let mut opacity_2d_grad = transmittance_state
+            * (0..3)
+                .map(|channel| {
+                    (color_rgb_3d_state[channel] - color_rgb_2d_state[channel])
+                        * color_rgb_2d_grad[channel]
+                })
+                .sum::<f32>();
+
+        // Computing the gradients of the point
+        //
+        // ∂L/∂D[n]  = ∂L/∂D' * α'[n] * t[n]
+        // ∂L/∂α'[n] += ∂L/∂D' * t[n] * (D[n] - D'[n])
+
+        let depth_grad = opacity_2d * transmittance_state * depth_2d_grad;
+        opacity_2d_grad +=
+            transmittance_state * (depth_3d_state - depth_2d_state) * depth_2d_grad;
+
+        // Computing the gradients of the point
+        //
+        // A' = 1 - t[N]
+        // t[N] = t[n] * (1 - α'[n]) * ... * (1 - α'[N - 1])
+        //
+        // ∂L/∂α'[n] += ∂L/∂A' * t[N] / (1 - α'[n])
+
+        opacity_2d_grad += alpha_2d_grad * transmittance_last / (1.0 - opacity_2d);
+
+        // Computing the gradients of the point
+        //
+        // ∂L/∂α[n] = ∂L/∂α'[n] * σ[n]
+        // ∂L/∂σ[n] = ∂L/∂α'[n] * α[n]
+
+        let opacity_3d_grad = density * opacity_2d_grad;
+        let density_grad = opacity_3d * opacity_2d_grad;
+
+        // Computing the gradients of the point
+        //
+        // ∂L/∂Σ'^-1[2, 2] = (∂L/∂σ * -σ) * D[2, 1] * D^t[1, 2] * 0.5
+        // ∂L/∂P[2, 1]     = (∂L/∂σ * -σ) * Σ'^-1[2, 2] * D[2, 1]
+
+        let density_density_grad_n = -density * density_grad;
+        let [d_x, d_y] = position_offset;
+        let conic_grad = [
+            0.5 * density_density_grad_n * d_x * d_x,
+            0.5 * density_density_grad_n * d_x * d_y,
+            0.5 * density_density_grad_n * d_y * d_y,
+        ];
+        let position_2d_grad = [
+            density_density_grad_n * (conic[0] * d_x + conic[1] * d_y),
+            density_density_grad_n * (conic[1] * d_x + conic[2] * d_y),
+        ];
+
+        // Updating the gradients of the point
+
+        for channel in 0..3 {
+            outputs.colors_rgb_3d_grad[point_index * 3 + channel] +=
+                color_rgb_3d_grad[channel];
+            outputs.conics_grad[point_index * 3 + channel] += conic_grad[channel];
+        }
+        outputs.depths_grad[point_index] += depth_grad;
+        outputs.opacities_3d_grad[point_index] += opacity_3d_grad;
+        outputs.positions_2d_grad[point_index * 2] += position_2d_grad[0];
+        outputs.positions_2d_grad[point_index * 2 + 1] += position_2d_grad[1];
+
+        // Accumulating the absolute gradients of the point if enabled
+        //
+        // |∂L/∂P|[2] = sum(|∂L/∂P[2]| of each pixel)
+
+        if arguments.positions_2d_grad_abs != 0 {
+            outputs.positions_2d_grad_abs[point_index * 2] += position_2d_grad[0].abs();
+            outputs.positions_2d_grad_abs[point_index * 2 + 1] +=
+                position_2d_grad[1].abs();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn alphas_2d_render_ndarray_autodiff() {
+        let device = Default::default();
+        let scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([1.0, 1.0, 1.0], [0.1, -0.1, 4.0], 0.6, 0.2),
+                ([1.0, 1.0, 1.0], [-0.4, 0.3, 5.0], 0.3, 0.3),
+                ([1.0, 1.0, 1.0], [0.5, 0.4, 3.0], 0.8, 0.1),
+            ],
+            &device,
+        );
+
+        let alphas_2d = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .alphas_2d;
+        assert_eq!(alphas_2d.dims(), [32, 32]);
+        assert!(alphas_2d.to_owned().min().into_scalar() >= 0.0);
+        assert!(alphas_2d.to_owned().max().into_scalar() <= 1.0);
+
+        let mut grads = alphas_2d.sum().backward();
+
+        // Each point covers more alpha with a higher opacity.
+        let opacities_grad = scene.opacities.grad_remove(&mut grads).unwrap();
+        assert_eq!(opacities_grad.dims(), [3, 1]);
+        assert!(opacities_grad.min().into_scalar() > 0.0);
+    }
+
+    #[test]
+    fn background_rgb_render_ndarray_autodiff() {
+        let device = Default::default();
+        let scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([0.0, 0.5, 1.0], [0.2, 0.1, 4.0], 0.9, 0.2),
+                ([0.5, 0.0, 0.5], [-0.2, -0.3, 6.0], 0.4, 0.5),
+            ],
+            &device,
+        );
+        let background_rgb_2d =
+            Tensor::<Autodiff<NdArray>, 3>::ones([1, 1, 3], &device).require_grad();
+
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        let mut grads = output
+            .composite_background(background_rgb_2d.to_owned())
+            .sum()
+            .backward();
+
+        // The gradient of the background is the total transmittance.
+        let target = output
+            .alphas_2d
+            .inner()
+            .neg()
+            .add_scalar(1.0)
+            .sum()
+            .repeat_dim(0, 3)
+            .reshape([1, 1, 3]);
+        let output = background_rgb_2d.grad_remove(&mut grads).unwrap();
+        output.into_data().assert_approx_eq(&target.into_data(), 3);
+    }
+
+    #[test]
+    fn depths_2d_render_ndarray_autodiff() {
+        let device = Default::default();
+        let scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([1.0, 1.0, 1.0], [0.1, -0.1, 4.0], 0.6, 0.3),
+                ([1.0, 1.0, 1.0], [-0.4, 0.3, 6.0], 0.5, 0.2),
+            ],
+            &device,
+        );
+
+        let mut grads = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .depths_2d
+            .sum()
+            .backward();
+
+        // The depths of both points contribute.
+        let positions_grad = scene.positions.grad_remove(&mut grads).unwrap();
+        assert_eq!(positions_grad.dims(), [2, 3]);
+        assert!(positions_grad.abs().sum_dim(1).min().into_scalar() > 0.0);
+
+        let colors_sh_grad = scene.colors_sh.grad_remove(&mut grads).unwrap();
+        assert_eq!(colors_sh_grad.abs().sum().into_scalar(), 0.0);
+    }
+
+    #[test]
+    fn region_render_ndarray_autodiff() {
+        let device = Default::default();
+        let scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 0.3),
+                ([0.0, 0.5, 1.0], [-0.6, 0.4, 3.0], 0.8, 0.2),
+            ],
+            &device,
+        );
+
+        let target = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .slice([7..18, 5..18, 0..3]);
+        let mut grads = target.to_owned().sum().backward();
+        let target_grad = scene.positions.grad_remove(&mut grads).unwrap();
+
+        let options =
+            Gaussian3dRenderOptions::default().with_region(Some([5, 7, 13, 11]));
+        let output = scene
+            .render(&VIEW_IDENTITY, &options)
+            .unwrap()
+            .colors_rgb_2d;
+        assert_eq!(output.dims(), [11, 13, 3]);
+        output
+            .to_owned()
+            .into_data()
+            .assert_eq(&target.into_data(), true);
+        let mut grads = output.sum().backward();
+        let output_grad = scene.positions.grad_remove(&mut grads).unwrap();
+
+        output_grad
+            .into_data()
+            .assert_approx_eq(&target_grad.into_data(), 3);
+    }
+}
@@ -0,0 +1,239 @@
+//! Rasterizing the point features to the image.
+//!
+//! It mirrors [`jit::kernel::rasterize_features`](super::super::super::jit::kernel::rasterize_features).
+
+pub use super::*;
+
+use rasterize::density;
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `D`
+    pub feature_count: u32,
+    /// `I_x`
+    pub image_size_x: u32,
+    /// `I_y`
+    pub image_size_y: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[P, 3]`
+    pub conics: &'a [f32],
+    /// `[P, D]`
+    pub features: &'a [f32],
+    /// `[P]` (Outer)
+    pub opacities_3d: &'a [f32],
+    /// `[T]`
+    pub point_indices: &'a [u32],
+    /// `[I_y', I_x']`
+    pub point_rendered_counts: &'a [u32],
+    /// `[P, 2]`
+    pub positions_2d: &'a [f32],
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
+    pub tile_point_ranges: &'a [u32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[I_y', I_x', D]`
+    pub features_2d: Vec<f32>,
+}
+
+/// Rasterize the point features to the image.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // I_x'
+    let region_size_x = arguments.region_size_x as usize;
+    // I_y'
+    let region_size_y = arguments.region_size_y as usize;
+    // D
+    let feature_count = arguments.feature_count as usize;
+
+    let mut features_2d = vec![0.0; region_size_y * region_size_x * feature_count];
+    features_2d
+        .par_chunks_mut(feature_count)
+        .enumerate()
+        .for_each(|(pixel_index, feature_2d)| {
+            rasterize_features(
+                arguments,
+                inputs,
+                feature_2d,
+                (pixel_index % region_size_x) as u32,
+                (pixel_index / region_size_x) as u32,
+            )
+        });
+
+    Outputs { features_2d }
+}
+
+/// Rasterizing the features of the pixel at `(x, y)` in the region.
+fn rasterize_features(
+    arguments: &Arguments,
+    inputs: &Inputs,
+    feature_2d: &mut [f32],
+    x: u32,
+    y: u32,
+) {
+    // Specifying the parameters
+
+    let pixel_index = (y * arguments.region_size_x + x) as usize;
+    // (0 ~ I_x, 0 ~ I_y)
+    let pixel = [x + arguments.region_offset_x, y + arguments.region_offset_y];
+    // The tiles are aligned to the image rather than the region
+    let tile_index = ((pixel[1] / TILE_SIZE_Y - arguments.region_offset_y / TILE_SIZE_Y)
+        * arguments.tile_count_x
+        + pixel[0] / TILE_SIZE_X
+        - arguments.region_offset_x / TILE_SIZE_X) as usize;
+    let point_range_start = inputs.tile_point_ranges[tile_index * 2] as usize;
+    let point_rendered_count = inputs.point_rendered_counts[pixel_index] as usize;
+    let position_pixel = [pixel[0] as f32, pixel[1] as f32];
+    let image_size_x = arguments.image_size_x as f32;
+    let is_equirectangular =
+        arguments.camera_model == transform::CAMERA_MODEL_EQUIRECTANGULAR;
+    // D
+    let feature_count = arguments.feature_count as usize;
+    let mut transmittance_state = 1.0;
+
+    // Processing the rendered points of the tile
+
+    for &point_index in inputs.point_indices
+        [point_range_start..point_range_start + point_rendered_count]
+        .iter()
+    {
+        let point_index = point_index as usize;
+
+        // Computing the density of the point in the pixel
+        //
+        // D.x is wrapped around the seam for the equirectangular camera model
+
+        let conic = &inputs.conics[point_index * 3..point_index * 3 + 3];
+        let mut position_offset = [
+            inputs.positions_2d[point_index * 2] - position_pixel[0],
+            inputs.positions_2d[point_index * 2 + 1] - position_pixel[1],
+        ];
+        if is_equirectangular {
+            position_offset[0] -=
+                image_size_x * (position_offset[0] / image_size_x).round();
+        }
+        let density = density(conic, &position_offset);
+
+        // Skipping if the density is greater than one
+
+        if density > 1.0 {
+            continue;
+        }
+
+        // Computing the 2D opacity of the point in the pixel
+
+        let opacity_3d = inputs.opacities_3d[point_index];
+        let opacity_2d = (opacity_3d * density).min(OPACITY_2D_MAX);
+
+        // Skipping if the 2D opacity is too low
+
+        if opacity_2d < OPACITY_2D_MIN {
+            continue;
+        }
+
+        // Blending the features of the point into the 2D features
+        // F'[n + 1] = F'[n] + F[n] * α'[n] * t[n]
+
+        let feature = &inputs.features
+            [point_index * feature_count..point_index * feature_count + feature_count];
+        feature_2d
+            .iter_mut()
+            .zip(feature)
+            .for_each(|(f_2d, f)| *f_2d += f * opacity_2d * transmittance_state);
+
+        // Updating the states of the pixel
+        // t[n + 1] = t[n] * (1 - α'[n])
+
+        transmittance_state *= 1.0 - opacity_2d;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn features_render_ndarray() {
+        let device = Default::default();
+        let mut scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 0.2),
+                ([0.0, 0.25, 1.0], [-0.3, 0.2, 5.0], 0.8, 0.4),
+            ],
+            &device,
+        );
+
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        assert!(output.features_2d.is_none());
+
+        scene.init_features(3);
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        let features_2d = output.features_2d.unwrap();
+        assert_eq!(features_2d.dims(), [32, 32, 3]);
+        assert_eq!(features_2d.abs().sum().into_scalar(), 0.0);
+
+        // The features are the colors, one and zero.
+        scene.set_inner_features(Tensor::from_data(
+            [[1.0, 0.5, 0.0, 1.0, 0.0], [0.0, 0.25, 1.0, 1.0, 0.0]],
+            &device,
+        ));
+
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        let features_2d = output.features_2d.unwrap();
+        assert_eq!(features_2d.dims(), [32, 32, 5]);
+        features_2d
+            .to_owned()
+            .slice([0..32, 0..32, 0..3])
+            .into_data()
+            .assert_approx_eq(&output.colors_rgb_2d.into_data(), 5);
+        features_2d
+            .to_owned()
+            .slice([0..32, 0..32, 3..4])
+            .into_data()
+            .assert_approx_eq(&output.alphas_2d.unsqueeze_dim::<3>(2).into_data(), 5);
+        assert_eq!(
+            features_2d
+                .slice([0..32, 0..32, 4..5])
+                .abs()
+                .sum()
+                .into_scalar(),
+            0.0
+        );
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_region(Some([5, 3, 20, 10])),
+            )
+            .unwrap();
+        assert_eq!(output.features_2d.unwrap().dims(), [10, 20, 5]);
+
+        scene.set_inner_features(Tensor::zeros([3, 5], &device));
+        let output = scene.render(&VIEW_IDENTITY, &Default::default());
+        assert!(output.is_err());
+    }
+}
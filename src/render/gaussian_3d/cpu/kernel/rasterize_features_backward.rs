@@ -0,0 +1,337 @@
+//! Rasterizing the point features to the image (backward).
+//!
+//! It mirrors [`jit::kernel::rasterize_features_backward`](super::super::super::jit::kernel::rasterize_features_backward).
+
+pub use super::*;
+
+use rasterize::density;
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `D`
+    pub feature_count: u32,
+    /// `I_x`
+    pub image_size_x: u32,
+    /// `I_y`
+    pub image_size_y: u32,
+    /// `P`
+    pub point_count: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[P, 3]`
+    pub conics: &'a [f32],
+    /// `[P, D]`
+    pub features: &'a [f32],
+    /// `[I_y', I_x', D]`
+    pub features_2d_grad: &'a [f32],
+    /// `[P]` (Outer)
+    pub opacities_3d: &'a [f32],
+    /// `[T]`
+    pub point_indices: &'a [u32],
+    /// `[I_y', I_x']`
+    pub point_rendered_counts: &'a [u32],
+    /// `[P, 2]`
+    pub positions_2d: &'a [f32],
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
+    pub tile_point_ranges: &'a [u32],
+    /// `[I_y', I_x']`
+    pub transmittances: &'a [f32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[P, 3]`
+    pub conics_grad: Vec<f32>,
+    /// `[P, D]`
+    pub features_grad: Vec<f32>,
+    /// `[P]` (Outer)
+    pub opacities_3d_grad: Vec<f32>,
+    /// `[P, 2]`
+    pub positions_2d_grad: Vec<f32>,
+}
+
+impl Outputs {
+    /// Zeroed gradients of `point_count` points with `feature_count` features.
+    fn zeros(
+        point_count: usize,
+        feature_count: usize,
+    ) -> Self {
+        Self {
+            conics_grad: vec![0.0; point_count * 3],
+            features_grad: vec![0.0; point_count * feature_count],
+            opacities_3d_grad: vec![0.0; point_count],
+            positions_2d_grad: vec![0.0; point_count * 2],
+        }
+    }
+
+    /// Accumulating the gradients of `other`.
+    fn add(
+        mut self,
+        other: Self,
+    ) -> Self {
+        [
+            (&mut self.conics_grad, other.conics_grad),
+            (&mut self.features_grad, other.features_grad),
+            (&mut self.opacities_3d_grad, other.opacities_3d_grad),
+            (&mut self.positions_2d_grad, other.positions_2d_grad),
+        ]
+        .into_iter()
+        .for_each(|(target, source)| {
+            target.iter_mut().zip(source).for_each(|(t, s)| *t += s);
+        });
+        self
+    }
+}
+
+/// Compute the gradient of the rasterization of the features.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // I_x'
+    let region_size_x = arguments.region_size_x;
+    // P
+    let point_count = arguments.point_count as usize;
+    // D
+    let feature_count = arguments.feature_count as usize;
+
+    (0..arguments.region_size_y)
+        .into_par_iter()
+        .fold(
+            || Outputs::zeros(point_count, feature_count),
+            |mut outputs, y| {
+                for x in 0..region_size_x {
+                    rasterize_features_backward(arguments, inputs, &mut outputs, x, y);
+                }
+                outputs
+            },
+        )
+        .reduce(|| Outputs::zeros(point_count, feature_count), Outputs::add)
+}
+
+/// Computing the gradients of the features of the pixel at `(x, y)` in the region.
+fn rasterize_features_backward(
+    arguments: &Arguments,
+    inputs: &Inputs,
+    outputs: &mut Outputs,
+    x: u32,
+    y: u32,
+) {
+    // Specifying the parameters
+
+    let pixel_index = (y * arguments.region_size_x + x) as usize;
+    // (0 ~ I_x, 0 ~ I_y)
+    let pixel = [x + arguments.region_offset_x, y + arguments.region_offset_y];
+    // The tiles are aligned to the image rather than the region
+    let tile_index = ((pixel[1] / TILE_SIZE_Y - arguments.region_offset_y / TILE_SIZE_Y)
+        * arguments.tile_count_x
+        + pixel[0] / TILE_SIZE_X
+        - arguments.region_offset_x / TILE_SIZE_X) as usize;
+    let point_range_start = inputs.tile_point_ranges[tile_index * 2] as usize;
+    let point_rendered_count = inputs.point_rendered_counts[pixel_index] as usize;
+    let position_pixel = [pixel[0] as f32, pixel[1] as f32];
+    let image_size_x = arguments.image_size_x as f32;
+    let is_equirectangular =
+        arguments.camera_model == transform::CAMERA_MODEL_EQUIRECTANGULAR;
+    // D
+    let feature_count = arguments.feature_count as usize;
+    let feature_2d_grad = &inputs.features_2d_grad
+        [pixel_index * feature_count..pixel_index * feature_count + feature_count];
+    // F'[N] = 0
+    let mut feature_2d_state = vec![0.0; feature_count];
+    let mut feature_3d_state = vec![0.0; feature_count];
+    let mut opacity_2d_state = 0.0;
+    let mut transmittance_state = inputs.transmittances[pixel_index];
+
+    // Processing the rendered points of the tile in reverse order
+
+    for &point_index in inputs.point_indices
+        [point_range_start..point_range_start + point_rendered_count]
+        .iter()
+        .rev()
+    {
+        let point_index = point_index as usize;
+
+        // Computing the density of the point in the pixel
+        //
+        // D.x is wrapped around the seam for the equirectangular camera model
+
+        let conic = &inputs.conics[point_index * 3..point_index * 3 + 3];
+        let mut position_offset = [
+            inputs.positions_2d[point_index * 2] - position_pixel[0],
+            inputs.positions_2d[point_index * 2 + 1] - position_pixel[1],
+        ];
+        if is_equirectangular {
+            position_offset[0] -=
+                image_size_x * (position_offset[0] / image_size_x).round();
+        }
+        let density = density(conic, &position_offset);
+
+        // Skipping if the density is greater than one
+
+        if density > 1.0 {
+            continue;
+        }
+
+        // Computing the 2D opacity of the point in the pixel
+
+        let opacity_3d = inputs.opacities_3d[point_index];
+        let opacity_2d = (opacity_3d * density).min(OPACITY_2D_MAX);
+
+        // Skipping if the 2D opacity is too low
+
+        if opacity_2d < OPACITY_2D_MIN {
+            continue;
+        }
+
+        // Updating the states of the pixel
+        //
+        // F'[n] = F[n + 1] * α'[n + 1] +
+        //         F'[n + 1] * (1 - α'[n + 1])
+        // t[n]  = t[n + 1] / (1 - α'[n])
+
+        let feature = &inputs.features
+            [point_index * feature_count..point_index * feature_count + feature_count];
+        feature_2d_state
+            .iter_mut()
+            .zip(feature_3d_state.iter_mut())
+            .zip(feature)
+            .for_each(|((f_2d, f_3d), f)| {
+                *f_2d = *f_3d * opacity_2d_state + *f_2d * (1.0 - opacity_2d_state);
+                *f_3d = *f;
+            });
+        opacity_2d_state = opacity_2d;
+        transmittance_state /= 1.0 - opacity_2d;
+
+        // Computing the gradients of the point
+        //
+        // ∂L/∂F[n]  = ∂L/∂F' * α'[n] * t[n]
+        // ∂L/∂α'[n] =〈∂L/∂F', t[n] * (F[n] - F'[n])〉
+
+        let opacity_2d_grad = transmittance_state
+            * feature_3d_state
+                .iter()
+                .zip(&feature_2d_state)
+                .zip(feature_2d_grad)
+                .map(|((f_3d, f_2d), g)| (f_3d - f_2d) * g)
+                .sum::<f32>();
+
+        // Computing the gradients of the point
+        //
+        // ∂L/∂α[n] = ∂L/∂α'[n] * σ[n]
+        // ∂L/∂σ[n] = ∂L/∂α'[n] * α[n]
+
+        let opacity_3d_grad = density * opacity_2d_grad;
+        let density_grad = opacity_3d * opacity_2d_grad;
+
+        // Computing the gradients of the point
+        //
+        // ∂L/∂Σ'^-1[2, 2] = (∂L/∂σ * -σ) * D[2, 1] * D^t[1, 2] * 0.5
+        // ∂L/∂P[2, 1]     = (∂L/∂σ * -σ) * Σ'^-1[2, 2] * D[2, 1]
+
+        let density_density_grad_n = -density * density_grad;
+        let [d_x, d_y] = position_offset;
+        let conic_grad = [
+            0.5 * density_density_grad_n * d_x * d_x,
+            0.5 * density_density_grad_n * d_x * d_y,
+            0.5 * density_density_grad_n * d_y * d_y,
+        ];
+        let position_2d_grad = [
+            density_density_grad_n * (conic[0] * d_x + conic[1] * d_y),
+            density_density_grad_n * (conic[1] * d_x + conic[2] * d_y),
+        ];
+
+        // Updating the gradients of the point
+
+        outputs.features_grad
+            [point_index * feature_count..point_index * feature_count + feature_count]
+            .iter_mut()
+            .zip(feature_2d_grad)
+            .for_each(|(f, g)| *f += opacity_2d * transmittance_state * g);
+        for channel in 0..3 {
+            outputs.conics_grad[point_index * 3 + channel] += conic_grad[channel];
+        }
+        outputs.opacities_3d_grad[point_index] += opacity_3d_grad;
+        outputs.positions_2d_grad[point_index * 2] += position_2d_grad[0];
+        outputs.positions_2d_grad[point_index * 2 + 1] += position_2d_grad[1];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn features_render_ndarray_autodiff() {
+        let device = Default::default();
+        let mut scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([1.0, 1.0, 1.0], [0.1, -0.1, 4.0], 0.6, 0.2),
+                ([1.0, 1.0, 1.0], [-0.2, 0.3, 5.0], 0.5, 0.3),
+            ],
+            &device,
+        );
+        scene.set_inner_features(Tensor::ones([2, 5], &device).set_require_grad(true));
+
+        // The 2D features of ones are the 2D alphas.
+        let mut grads = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .features_2d
+            .unwrap()
+            .slice([0..32, 0..32, 0..1])
+            .sum()
+            .backward();
+
+        let features_grad = scene
+            .features
+            .as_ref()
+            .unwrap()
+            .grad_remove(&mut grads)
+            .unwrap();
+        assert_eq!(features_grad.dims(), [2, 5]);
+        assert!(
+            features_grad
+                .to_owned()
+                .slice([0..2, 0..1])
+                .min()
+                .into_scalar()
+                > 0.0
+        );
+        assert_eq!(
+            features_grad.slice([0..2, 1..5]).abs().sum().into_scalar(),
+            0.0
+        );
+        let output = to_vec(scene.opacities.grad_remove(&mut grads).unwrap());
+
+        let mut grads = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .alphas_2d
+            .sum()
+            .backward();
+
+        let target = to_vec(scene.opacities.grad_remove(&mut grads).unwrap());
+        assert_relative_eq(&output, &target, 1e-3, "opacities_grad");
+    }
+}
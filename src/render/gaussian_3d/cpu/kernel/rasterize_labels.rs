@@ -0,0 +1,278 @@
+//! Rasterizing the point labels to the image.
+//!
+//! It mirrors [`jit::kernel::rasterize_labels`](super::super::super::jit::kernel::rasterize_labels).
+
+pub use super::*;
+
+use rasterize::density;
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `I_x`
+    pub image_size_x: u32,
+    /// `I_y`
+    pub image_size_y: u32,
+    /// `L`
+    pub label_count: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[P, 3]`
+    pub conics: &'a [f32],
+    /// `[P]`
+    pub labels: &'a [i32],
+    /// `[P]` (Outer)
+    pub opacities_3d: &'a [f32],
+    /// `[T]`
+    pub point_indices: &'a [u32],
+    /// `[I_y', I_x']`
+    pub point_rendered_counts: &'a [u32],
+    /// `[P, 2]`
+    pub positions_2d: &'a [f32],
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
+    pub tile_point_ranges: &'a [u32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[I_y', I_x', L]`
+    pub label_coverages_2d: Vec<f32>,
+    /// `[I_y', I_x']`
+    pub labels_2d: Vec<i32>,
+}
+
+/// `l'_none`
+///
+/// The label of the pixels without any rendered point.
+pub const LABEL_2D_NONE: i32 = -1;
+
+/// Rasterize the point labels to the image.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // I_x'
+    let region_size_x = arguments.region_size_x as usize;
+    // I_y'
+    let region_size_y = arguments.region_size_y as usize;
+    // L
+    let label_count = arguments.label_count as usize;
+
+    let mut label_coverages_2d = vec![0.0; region_size_y * region_size_x * label_count];
+    let mut labels_2d = vec![LABEL_2D_NONE; region_size_y * region_size_x];
+    label_coverages_2d
+        .par_chunks_mut(label_count)
+        .zip(labels_2d.par_iter_mut())
+        .enumerate()
+        .for_each(|(pixel_index, (label_coverage_2d, label_2d))| {
+            rasterize_labels(
+                arguments,
+                inputs,
+                label_coverage_2d,
+                label_2d,
+                (pixel_index % region_size_x) as u32,
+                (pixel_index / region_size_x) as u32,
+            )
+        });
+
+    Outputs {
+        label_coverages_2d,
+        labels_2d,
+    }
+}
+
+/// Rasterizing the labels of the pixel at `(x, y)` in the region.
+fn rasterize_labels(
+    arguments: &Arguments,
+    inputs: &Inputs,
+    label_coverage_2d: &mut [f32],
+    label_2d: &mut i32,
+    x: u32,
+    y: u32,
+) {
+    // Specifying the parameters
+
+    let pixel_index = (y * arguments.region_size_x + x) as usize;
+    // (0 ~ I_x, 0 ~ I_y)
+    let pixel = [x + arguments.region_offset_x, y + arguments.region_offset_y];
+    // The tiles are aligned to the image rather than the region
+    let tile_index = ((pixel[1] / TILE_SIZE_Y - arguments.region_offset_y / TILE_SIZE_Y)
+        * arguments.tile_count_x
+        + pixel[0] / TILE_SIZE_X
+        - arguments.region_offset_x / TILE_SIZE_X) as usize;
+    let point_range_start = inputs.tile_point_ranges[tile_index * 2] as usize;
+    let point_rendered_count = inputs.point_rendered_counts[pixel_index] as usize;
+    let position_pixel = [pixel[0] as f32, pixel[1] as f32];
+    let image_size_x = arguments.image_size_x as f32;
+    let is_equirectangular =
+        arguments.camera_model == transform::CAMERA_MODEL_EQUIRECTANGULAR;
+    let mut transmittance_state = 1.0;
+    let mut weight_max = 0.0;
+
+    // Processing the rendered points of the tile
+
+    for &point_index in inputs.point_indices
+        [point_range_start..point_range_start + point_rendered_count]
+        .iter()
+    {
+        let point_index = point_index as usize;
+
+        // Computing the density of the point in the pixel
+        //
+        // D.x is wrapped around the seam for the equirectangular camera model
+
+        let conic = &inputs.conics[point_index * 3..point_index * 3 + 3];
+        let mut position_offset = [
+            inputs.positions_2d[point_index * 2] - position_pixel[0],
+            inputs.positions_2d[point_index * 2 + 1] - position_pixel[1],
+        ];
+        if is_equirectangular {
+            position_offset[0] -=
+                image_size_x * (position_offset[0] / image_size_x).round();
+        }
+        let density = density(conic, &position_offset);
+
+        // Skipping if the density is greater than one
+
+        if density > 1.0 {
+            continue;
+        }
+
+        // Computing the 2D opacity of the point in the pixel
+
+        let opacity_3d = inputs.opacities_3d[point_index];
+        let opacity_2d = (opacity_3d * density).min(OPACITY_2D_MAX);
+
+        // Skipping if the 2D opacity is too low
+
+        if opacity_2d < OPACITY_2D_MIN {
+            continue;
+        }
+
+        // Accumulating the blending weight of the point into its label
+        // w[n] = α'[n] * t[n]
+        //
+        // The label of the point with the largest weight is dominant.
+
+        let label = inputs.labels[point_index];
+        let weight = opacity_2d * transmittance_state;
+        if weight > weight_max {
+            weight_max = weight;
+            *label_2d = label;
+        }
+        if label >= 0 && (label as usize) < label_coverage_2d.len() {
+            label_coverage_2d[label as usize] += weight;
+        }
+
+        // Updating the states of the pixel
+        // t[n + 1] = t[n] * (1 - α'[n])
+
+        transmittance_state *= 1.0 - opacity_2d;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn labels_render_ndarray() {
+        let device = Default::default();
+        let mut scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.0, 0.0], [-0.5, 0.0, 4.0], 0.1, 0.05),
+                ([0.0, 1.0, 0.0], [0.5, 0.0, 4.0], 0.1, 0.05),
+                ([0.0, 0.0, 1.0], [0.0, 0.5, 4.0], 0.1, 0.05),
+            ],
+            &device,
+        );
+
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        assert!(output.label_coverages_2d.is_none());
+        assert!(output.labels_2d.is_none());
+
+        // The last point is unlabeled.
+        scene.set_inner_labels(Tensor::<NdArray, 1, Int>::from_ints([0, 2, -1], &device));
+        assert_eq!(scene.label_count(), 3);
+
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        let label_coverages_2d = output.label_coverages_2d.unwrap();
+        let labels_2d = output.labels_2d.unwrap();
+        assert_eq!(label_coverages_2d.dims(), [32, 32, 3]);
+        assert_eq!(labels_2d.dims(), [32, 32]);
+
+        let label_at = |y: usize, x: usize| {
+            labels_2d
+                .to_owned()
+                .slice([y..y + 1, x..x + 1])
+                .into_scalar()
+        };
+        assert_eq!(label_at(16, 12), 0);
+        assert_eq!(label_at(16, 20), 2);
+        assert_eq!(label_at(20, 16), -1);
+        assert_eq!(label_at(0, 0), -1);
+
+        // The coverages of the labels never rendered are zero.
+        assert_eq!(
+            label_coverages_2d
+                .to_owned()
+                .slice([0..32, 0..32, 1..2])
+                .sum()
+                .into_scalar(),
+            0.0
+        );
+
+        // The coverages exclude the unlabeled point.
+        let coverages_2d = label_coverages_2d.sum_dim(2).squeeze::<2>(2);
+        let alphas_2d = output.alphas_2d;
+        assert!(
+            coverages_2d
+                .to_owned()
+                .sub(alphas_2d.to_owned())
+                .max()
+                .into_scalar()
+                <= 1e-6
+        );
+        assert_eq!(
+            coverages_2d
+                .to_owned()
+                .slice([20..21, 16..17])
+                .into_scalar(),
+            0.0
+        );
+        assert!(alphas_2d.slice([20..21, 16..17]).into_scalar() > 0.0);
+        assert!(coverages_2d.slice([16..17, 12..13]).into_scalar() > 0.0);
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_region(Some([5, 3, 20, 10])),
+            )
+            .unwrap();
+        assert_eq!(output.label_coverages_2d.unwrap().dims(), [10, 20, 3]);
+        assert_eq!(output.labels_2d.unwrap().dims(), [10, 20]);
+
+        scene.set_inner_labels(Tensor::<NdArray, 1, Int>::from_ints([0, 1], &device));
+        let output = scene.render(&VIEW_IDENTITY, &Default::default());
+        assert!(output.is_err());
+    }
+}
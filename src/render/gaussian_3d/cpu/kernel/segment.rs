@@ -0,0 +1,61 @@
+//! Segmenting the points into tiles.
+//!
+//! It mirrors [`jit::kernel::segment`](super::super::super::jit::kernel::segment).
+
+pub use super::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `(1: 32-bit, 2: 64-bit)`
+    pub point_order_word_count: u32,
+    /// `I_x / T_x`
+    pub tile_count_x: u32,
+    /// `I_y / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[T]`
+    pub point_orders: &'a [u64],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[I_y / T_y, I_x / T_x, 2]`
+    pub tile_point_ranges: Vec<u32>,
+}
+
+/// Segment the points into tiles.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // (I_y / T_y) * (I_x / T_x)
+    let tile_count = (arguments.tile_count_x * arguments.tile_count_y) as usize;
+    // [I_y / T_y, I_x / T_x, 2]
+    let mut tile_point_ranges = vec![0; tile_count * 2];
+    // The shift of tile order in the point order
+    let tile_order_shift = match arguments.point_order_word_count {
+        2 => 32,
+        _ => 16,
+    };
+
+    // Finding the ranges of each point tile
+
+    let mut start = 0;
+    for orders in inputs.point_orders.chunk_by(|previous, current| {
+        previous >> tile_order_shift == current >> tile_order_shift
+    }) {
+        let tile_index = (orders[0] >> tile_order_shift) as usize;
+        let end = start + orders.len();
+        tile_point_ranges[tile_index * 2] = start as u32;
+        tile_point_ranges[tile_index * 2 + 1] = end as u32;
+        start = end;
+    }
+
+    Outputs { tile_point_ranges }
+}
@@ -0,0 +1,1239 @@
+//! Transforming the points.
+//!
+//! It mirrors [`jit::kernel::transform`](super::super::super::jit::kernel::transform).
+
+pub use super::*;
+
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `(0 ~ 3)`
+    pub colors_sh_degree_max: u32,
+    /// `D_far`
+    pub depth_far: f32,
+    /// `D_near`
+    pub depth_near: f32,
+    /// `[k1, k2, k3, k4, p1, p2]`
+    pub distortion: [f32; 6],
+    /// `k`
+    pub factor_radius: f32,
+    /// `C_f`
+    pub filter_2d: f32,
+    /// `C_3d`
+    pub filter_3d_scale: f32,
+    /// `(0: Low-pass filter, 1: Mip filter)`
+    pub filter_mip_2d: u32,
+    /// `f_x <- I_x / tan(Fov_x / 2) / 2`
+    pub focal_length_x: f32,
+    /// `f_y <- I_y / tan(Fov_y / 2) / 2`
+    pub focal_length_y: f32,
+    /// `P`
+    pub point_count: u32,
+    /// `C_x <- I_x / 2 + O_x`
+    pub principal_point_x: f32,
+    /// `C_y <- I_y / 2 + O_y`
+    pub principal_point_y: f32,
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+    /// `I_x0 / T_x`
+    pub tile_offset_x: u32,
+    /// `I_y0 / T_y`
+    pub tile_offset_y: u32,
+    /// `(I_x - C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_max: f32,
+    /// `-(C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_min: f32,
+    /// `(I_y - C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_max: f32,
+    /// `-(C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_min: f32,
+    /// `[3]`
+    pub view_position: [f32; 3],
+    /// `[3 (+ 1), 3 + 1]`
+    pub view_transform: [[f32; 4]; 4],
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[P, M * 3]` <- `[P, M, 3]`
+    pub colors_sh: &'a [f32],
+    /// `[P, 1]`
+    pub filters_3d: &'a [f32],
+    /// `[P, 1]` (Inner)
+    pub opacities: &'a [f32],
+    /// `[P, 3]`
+    pub positions_3d: &'a [f32],
+    /// `[P, 4]`
+    pub rotations: &'a [f32],
+    /// `[P, 3]`
+    pub scalings: &'a [f32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[P, 3]`
+    pub colors_rgb_3d: Vec<f32>,
+    /// `[P, 3]`
+    pub conics: Vec<f32>,
+    /// `[P]`
+    pub depths: Vec<f32>,
+    /// `[P, 3]`
+    pub is_colors_rgb_3d_not_clamped: Vec<f32>,
+    /// `[P]` (Outer)
+    pub opacities_3d: Vec<f32>,
+    /// `[P, 4]`
+    pub point_tile_bounds: Vec<u32>,
+    /// `[P, 2]`
+    pub positions_2d: Vec<f32>,
+    /// `[P, 2]`
+    pub positions_3d_in_normalized: Vec<f32>,
+    /// `[P]`
+    pub radii: Vec<u32>,
+    /// `[P, 3, 3]`
+    pub rotations_matrix: Vec<f32>,
+    /// `[P]`
+    pub tile_touched_counts: Vec<u32>,
+}
+
+/// The outputs of a point.
+#[derive(Clone, Copy, Debug, Default)]
+struct Output {
+    color_rgb_3d: Vec3,
+    conic: Vec3,
+    depth: f32,
+    is_color_rgb_3d_not_clamped: Vec3,
+    opacity_3d: f32,
+    point_tile_bound: [u32; 4],
+    position_2d: Vec2,
+    position_3d_in_normalized: Vec2,
+    radius: u32,
+    rotation_matrix: Mat3,
+    tile_touched_count: u32,
+}
+
+/// The camera model with equirectangular projection.
+pub const CAMERA_MODEL_EQUIRECTANGULAR: u32 = 4;
+/// The camera model with fisheye distortion.
+pub const CAMERA_MODEL_FISHEYE: u32 = 2;
+/// The camera model with radial-tangential distortion.
+pub const CAMERA_MODEL_OPENCV: u32 = 1;
+/// The camera model with orthographic projection.
+pub const CAMERA_MODEL_ORTHOGRAPHIC: u32 = 3;
+/// The camera model without distortion.
+pub const CAMERA_MODEL_PINHOLE: u32 = 0;
+
+/// Transforming the points.
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // P
+    let point_count = arguments.point_count as usize;
+
+    let points = (0..point_count)
+        .into_par_iter()
+        .map(|index| transform(arguments, inputs, index).unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    let mut outputs = Outputs {
+        colors_rgb_3d: Vec::with_capacity(point_count * 3),
+        conics: Vec::with_capacity(point_count * 3),
+        depths: Vec::with_capacity(point_count),
+        is_colors_rgb_3d_not_clamped: Vec::with_capacity(point_count * 3),
+        opacities_3d: Vec::with_capacity(point_count),
+        point_tile_bounds: Vec::with_capacity(point_count * 4),
+        positions_2d: Vec::with_capacity(point_count * 2),
+        positions_3d_in_normalized: Vec::with_capacity(point_count * 2),
+        radii: Vec::with_capacity(point_count),
+        rotations_matrix: Vec::with_capacity(point_count * 9),
+        tile_touched_counts: Vec::with_capacity(point_count),
+    };
+    for point in points {
+        outputs.colors_rgb_3d.extend(point.color_rgb_3d);
+        outputs.conics.extend(point.conic);
+        outputs.depths.push(point.depth);
+        outputs
+            .is_colors_rgb_3d_not_clamped
+            .extend(point.is_color_rgb_3d_not_clamped);
+        outputs.opacities_3d.push(point.opacity_3d);
+        outputs.point_tile_bounds.extend(point.point_tile_bound);
+        outputs.positions_2d.extend(point.position_2d);
+        outputs
+            .positions_3d_in_normalized
+            .extend(point.position_3d_in_normalized);
+        outputs.radii.push(point.radius);
+        outputs
+            .rotations_matrix
+            .extend(point.rotation_matrix.into_iter().flatten());
+        outputs.tile_touched_counts.push(point.tile_touched_count);
+    }
+
+    outputs
+}
+
+/// Transforming the point at `index`.
+///
+/// It returns `None` if the point is invisible.
+fn transform(
+    arguments: &Arguments,
+    inputs: &Inputs,
+    index: usize,
+) -> Option<Output> {
+    // Transforming the 3D position from world space to view space
+    // Pv[3, 1] = Rv[3, 3] * Pw[3, 1] + Tv[3, 1]
+
+    let position_3d = vec3_from_slice(&inputs.positions_3d[index * 3..]);
+    let (view_rotation, view_translation) =
+        view_rotation_translation(&arguments.view_transform);
+    let position_3d_in_view = mat3_mul_vec3(&view_rotation, &position_3d);
+    let position_3d_in_view = [
+        position_3d_in_view[0] + view_translation[0],
+        position_3d_in_view[1] + view_translation[1],
+        position_3d_in_view[2] + view_translation[2],
+    ];
+    // D = Pv.z
+    //
+    // D = |Pv| for the equirectangular camera model
+
+    let is_equirectangular = arguments.camera_model == CAMERA_MODEL_EQUIRECTANGULAR;
+    let depth = if is_equirectangular {
+        dot_3(&position_3d_in_view, &position_3d_in_view).sqrt()
+    } else {
+        position_3d_in_view[2]
+    };
+
+    // Performing viewing-frustum culling
+    // D in [D_near, D_far)
+
+    if !(arguments.depth_near..arguments.depth_far).contains(&depth) {
+        return None;
+    }
+
+    // Converting the quaternion to rotation matrix
+
+    let rotation = &inputs.rotations[index * 4..index * 4 + 4];
+    if rotation.iter().all(|&q| q == 0.0) {
+        return None;
+    }
+    let rotation_norm = rotation.iter().map(|q| q * q).sum::<f32>().sqrt();
+    let [x, y, z, w] = [
+        rotation[0] / rotation_norm,
+        rotation[1] / rotation_norm,
+        rotation[2] / rotation_norm,
+        rotation[3] / rotation_norm,
+    ];
+    let rotation_matrix = [
+        [
+            2.0 * (-y * y - z * z + 0.5),
+            2.0 * (x * y + w * z),
+            2.0 * (x * z - w * y),
+        ],
+        [
+            2.0 * (x * y - w * z),
+            2.0 * (-x * x - z * z + 0.5),
+            2.0 * (y * z + w * x),
+        ],
+        [
+            2.0 * (x * z + w * y),
+            2.0 * (y * z - w * x),
+            2.0 * (-x * x - y * y + 0.5),
+        ],
+    ];
+
+    // Computing the 3D covariance matrix from rotation and scaling
+    // RS[3, 3] = R[3, 3] * S[3, 3]
+    // Σ[3, 3] (Symmetric) = RS[3, 3] * RS^t[3, 3] + C_3d * F_3d * I[3, 3]
+    // ρ_3d = √(|S^2[3, 3]| / |S^2[3, 3] + C_3d * F_3d * I[3, 3]|)
+
+    let scaling = vec3_from_slice(&inputs.scalings[index * 3..]).map(f32::exp);
+    let rotation_scaling = [
+        rotation_matrix[0].map(|r| r * scaling[0]),
+        rotation_matrix[1].map(|r| r * scaling[1]),
+        rotation_matrix[2].map(|r| r * scaling[2]),
+    ];
+    let mut covariance_3d =
+        mat3_mul(&rotation_scaling, &mat3_transpose(&rotation_scaling));
+    let filter_3d = arguments.filter_3d_scale * inputs.filters_3d[index];
+    let opacity_compensation_3d = filter_3d_compensation(&scaling, filter_3d);
+    for axis in 0..3 {
+        covariance_3d[axis][axis] += filter_3d;
+    }
+
+    // Transforming the 3D position to 2D position
+    // (view => normalized => distorted => clip => screen)
+    // Pn[2] = [Pv.x / Pv.z, Pv.y / Pv.z]
+    // Pn'[2] = Pn[2] * ρ(r^2) + Tn(Pn)
+    // Pv'[2] = F[2] * Pn'[2] + C[2] - 0.5
+    //
+    // Pn[2] = [Pv.x, Pv.y] for the orthographic camera model
+    // Pn[2] = [atan2(Pv.x, Pv.z), atan2(Pv.y, √(Pv.x^2 + Pv.z^2))]
+    // for the equirectangular camera model
+
+    let is_orthographic = arguments.camera_model == CAMERA_MODEL_ORTHOGRAPHIC;
+    let focal_length = [arguments.focal_length_x, arguments.focal_length_y];
+    let position_3d_in_normalized = if is_orthographic {
+        [position_3d_in_view[0], position_3d_in_view[1]]
+    } else if is_equirectangular {
+        let [x, y, z] = position_3d_in_view;
+        [x.atan2(z), y.atan2((x * x + z * z).sqrt())]
+    } else {
+        [
+            position_3d_in_view[0] / depth,
+            position_3d_in_view[1] / depth,
+        ]
+    };
+    let position_3d_in_normalized_clamped = [
+        position_3d_in_normalized[0]
+            .clamp(arguments.view_bound_x_min, arguments.view_bound_x_max),
+        position_3d_in_normalized[1]
+            .clamp(arguments.view_bound_y_min, arguments.view_bound_y_max),
+    ];
+
+    // Performing viewing-frustum culling for the distorted camera models
+
+    if (arguments.camera_model == CAMERA_MODEL_OPENCV
+        || arguments.camera_model == CAMERA_MODEL_FISHEYE)
+        && position_3d_in_normalized != position_3d_in_normalized_clamped
+    {
+        return None;
+    }
+
+    let distortion = Distortion::new(
+        arguments.camera_model,
+        &arguments.distortion,
+        &position_3d_in_normalized_clamped,
+    );
+    let position_3d_in_distorted = distortion.position(&position_3d_in_normalized);
+    let mut position_2d = [
+        position_3d_in_distorted[0] * focal_length[0] + arguments.principal_point_x - 0.5,
+        position_3d_in_distorted[1] * focal_length[1] + arguments.principal_point_y - 0.5,
+    ];
+
+    // Wrapping around the horizontal seam for the equirectangular camera model
+    // Pv'.x in [-0.5, I_x - 0.5)
+    //
+    // I_x = 2π * F_x
+
+    let image_period_x = std::f32::consts::TAU * focal_length[0];
+    if is_equirectangular {
+        position_2d[0] -=
+            image_period_x * ((position_2d[0] + 0.5) / image_period_x).floor();
+    }
+
+    // Projecting the 3D covariance matrix into 2D covariance matrix
+    // K[2, 2] = F[2, 2] * Jd[2, 2]
+    // J[2, 3] = K[2, 2] * [[1, 0, -Pn.x], [0, 1, -Pn.y]] / Pv.z
+    // T[2, 3] = J[2, 3] * Rv[3, 3]
+    // Σ'[2, 2] (Symmetric) = T[2, 3] * Σ[3, 3] * T^t[3, 2] + C_f * I[2, 2]
+    // ρ_2d = √max(|Σ' - C_f * I[2, 2]| / |Σ'|, 0) for the mip filter, 1 otherwise
+    //
+    // Pn.x and Pn.y are the clamped
+    //
+    // J[2, 3] = F[2, 2] * [[1, 0, 0], [0, 1, 0]] for the orthographic camera model
+    // J[2, 3] = F[2, 2] * ∂Pn/∂Pv[2, 3] for the equirectangular camera model
+
+    let projection_2d_left = if is_equirectangular {
+        let jacobian = equirectangular_jacobian(&position_3d_in_view)?;
+        [0, 1].map(|row| jacobian[row].map(|j| j * focal_length[row]))
+    } else {
+        let (depth_projected, position_3d_in_normalized_projected) = if is_orthographic {
+            (1.0, [0.0; 2])
+        } else {
+            (depth, position_3d_in_normalized_clamped)
+        };
+        let distortion_jacobian = distortion.jacobian(&position_3d_in_normalized_clamped);
+        [0, 1].map(|row| {
+            let k =
+                distortion_jacobian[row].map(|j| j * focal_length[row] / depth_projected);
+            [
+                k[0],
+                k[1],
+                -k[0] * position_3d_in_normalized_projected[0]
+                    - k[1] * position_3d_in_normalized_projected[1],
+            ]
+        })
+    };
+    let projection_2d = mat2x3_mul_mat3(&projection_2d_left, &view_rotation);
+    let projection_2d_covariance_3d = [
+        vec3_mul_mat3(&projection_2d[0], &covariance_3d),
+        vec3_mul_mat3(&projection_2d[1], &covariance_3d),
+    ];
+    let covariance_2d_unfiltered = [
+        dot_3(&projection_2d_covariance_3d[0], &projection_2d[0]),
+        dot_3(&projection_2d_covariance_3d[0], &projection_2d[1]),
+        dot_3(&projection_2d_covariance_3d[1], &projection_2d[1]),
+    ];
+    let covariance_2d = [
+        covariance_2d_unfiltered[0] + arguments.filter_2d,
+        covariance_2d_unfiltered[1],
+        covariance_2d_unfiltered[2] + arguments.filter_2d,
+    ];
+
+    // Computing the inverse of the 2D covariance matrix
+    // Σ'^-1[2, 2] (Symmetric) <- Σ'[2, 2]
+
+    let covariance_2d_det =
+        covariance_2d[0] * covariance_2d[2] - covariance_2d[1] * covariance_2d[1];
+    if covariance_2d_det == 0.0 {
+        return None;
+    }
+    let conic = [
+        covariance_2d[2] / covariance_2d_det,
+        -covariance_2d[1] / covariance_2d_det,
+        covariance_2d[0] / covariance_2d_det,
+    ];
+    let opacity_compensation_2d = if arguments.filter_mip_2d != 0 {
+        let covariance_2d_unfiltered_det = covariance_2d_unfiltered[0]
+            * covariance_2d_unfiltered[2]
+            - covariance_2d_unfiltered[1] * covariance_2d_unfiltered[1];
+        (covariance_2d_unfiltered_det / covariance_2d_det)
+            .max(0.0)
+            .sqrt()
+    } else {
+        1.0
+    };
+
+    // Computing the max radius using the 2D covariance matrix
+    // r <- Σ'[2, 2]
+
+    let covariance_2d_diag_mean = (covariance_2d[0] + covariance_2d[2]) / 2.0;
+    let eigenvalue_difference = (covariance_2d_diag_mean * covariance_2d_diag_mean
+        - covariance_2d_det)
+        .max(0.0)
+        .sqrt();
+    let eigenvalue_max = (covariance_2d_diag_mean + eigenvalue_difference)
+        .max(covariance_2d_diag_mean - eigenvalue_difference);
+    let radius = (eigenvalue_max.max(0.0).sqrt() * arguments.factor_radius).ceil();
+
+    // Checking the tiles touched in the region
+    // (x max, x min, y max, y min)
+
+    let tile_size_x = TILE_SIZE_X as f32;
+    let tile_size_y = TILE_SIZE_Y as f32;
+    let tile_count_x = arguments.tile_count_x as i32;
+    let tile_count_y = arguments.tile_count_y as i32;
+    let tile_offset_x = arguments.tile_offset_x as i32;
+    let tile_offset_y = arguments.tile_offset_y as i32;
+    let tile_bound_x = |x: f32| {
+        ((x / tile_size_x) as i32).clamp(tile_offset_x, tile_offset_x + tile_count_x)
+            as u32
+            - tile_offset_x as u32
+    };
+    let tile_bound_y = |y: f32| {
+        ((y / tile_size_y) as i32).clamp(tile_offset_y, tile_offset_y + tile_count_y)
+            as u32
+            - tile_offset_y as u32
+    };
+    let point_tile_bound = [
+        tile_bound_x(position_2d[0] + radius + tile_size_x - 1.0),
+        tile_bound_x(position_2d[0] - radius),
+        tile_bound_y(position_2d[1] + radius + tile_size_y - 1.0),
+        tile_bound_y(position_2d[1] - radius),
+    ];
+
+    // Touching all the tiles in the rows if the point wraps around the seam
+
+    let point_tile_bound = if is_equirectangular
+        && (position_2d[0] - radius < -0.5
+            || position_2d[0] + radius >= image_period_x - 0.5)
+    {
+        [
+            tile_count_x as u32,
+            0,
+            point_tile_bound[2],
+            point_tile_bound[3],
+        ]
+    } else {
+        point_tile_bound
+    };
+    let tile_touched_count = (point_tile_bound[0] - point_tile_bound[1])
+        * (point_tile_bound[2] - point_tile_bound[3]);
+
+    // Leaving if no tile is touched
+
+    if tile_touched_count == 0 {
+        return None;
+    }
+
+    // Computing the view direction in world space
+    // Ov[3] = Pw[3] - V[3]
+    // Dv[3] = Ov[3] / |Ov|
+    //
+    // Ov[3] = Rv^t[3, 3] * [0, 0, 1] for the orthographic camera model
+
+    let view_offset = if is_orthographic {
+        [
+            view_rotation[0][2],
+            view_rotation[1][2],
+            view_rotation[2][2],
+        ]
+    } else {
+        [
+            position_3d[0] - arguments.view_position[0],
+            position_3d[1] - arguments.view_position[1],
+            position_3d[2] - arguments.view_position[2],
+        ]
+    };
+    if view_offset == [0.0; 3] {
+        return None;
+    }
+    let vd = normalize_3(&view_offset);
+
+    // Computing the 3D color in RGB space from SH space
+    // D[M] <- Dv[3]
+    // C_rgb[3] = D[1, M] * C_sh[M, 3] + 0.5
+    //
+    // C_rgb is clamped
+
+    let color_sh = &inputs.colors_sh[index * SH_COUNT_MAX * 3..];
+    let color_sh_basis = sh_basis(arguments.colors_sh_degree_max, &vd);
+    let mut color_rgb_3d = [0.5; 3];
+    for (basis_index, basis) in color_sh_basis.into_iter().enumerate() {
+        for channel in 0..3 {
+            color_rgb_3d[channel] += basis * color_sh[basis_index * 3 + channel];
+        }
+    }
+
+    let is_color_rgb_3d_not_clamped =
+        color_rgb_3d.map(|c| if c >= 0.0 { 1.0 } else { 0.0 });
+    let color_rgb_3d = color_rgb_3d.map(|c| if c >= 0.0 { c } else { 0.0 });
+
+    // Computing the 3D opacity compensated by the filters
+    // α[1] = sigmoid(α_inner[1]) * ρ_3d * ρ_2d
+
+    let opacity_3d = sigmoid(inputs.opacities[index])
+        * opacity_compensation_3d
+        * opacity_compensation_2d;
+
+    Some(Output {
+        color_rgb_3d,
+        conic,
+        depth,
+        is_color_rgb_3d_not_clamped,
+        opacity_3d,
+        point_tile_bound,
+        position_2d,
+        position_3d_in_normalized,
+        radius: radius as u32,
+        rotation_matrix,
+        tile_touched_count,
+    })
+}
+
+/// Return the opacity compensation `ρ_3d` of the 3D smoothing filter.
+///
+/// `ρ_3d = √(Π(S^2) / Π(S^2 + C_3d * F_3d))`
+pub(crate) fn filter_3d_compensation(
+    scaling: &Vec3,
+    filter_3d: f32,
+) -> f32 {
+    if filter_3d == 0.0 {
+        return 1.0;
+    }
+    scaling
+        .iter()
+        .map(|s| {
+            let variance = s * s;
+            variance / (variance + filter_3d)
+        })
+        .product::<f32>()
+        .sqrt()
+}
+
+/// Return the SH basis `D[M]` of the view direction `Dv[3]`.
+///
+/// The basis above `colors_sh_degree_max` is zero.
+pub(crate) fn sh_basis(
+    colors_sh_degree_max: u32,
+    vd: &Vec3,
+) -> [f32; SH_COUNT_MAX] {
+    let (c_0, c_1, c_2, c_3) = &*SH_COEF;
+    let mut basis = [0.0; SH_COUNT_MAX];
+
+    basis[0] = c_0[0] as f32;
+
+    if colors_sh_degree_max >= 1 {
+        basis[1] = c_1[0] as f32 * vd[1];
+        basis[2] = c_1[1] as f32 * vd[2];
+        basis[3] = c_1[2] as f32 * vd[0];
+    }
+
+    if colors_sh_degree_max >= 2 {
+        let vd_xx = vd[0] * vd[0];
+        let vd_yy = vd[1] * vd[1];
+        let vd_zz = vd[2] * vd[2];
+
+        basis[4] = c_2[0] as f32 * (vd[0] * vd[1]);
+        basis[5] = c_2[1] as f32 * (vd[1] * vd[2]);
+        basis[6] = c_2[2] as f32 * (vd_zz * 3.0 - 1.0);
+        basis[7] = c_2[3] as f32 * (vd[0] * vd[2]);
+        basis[8] = c_2[4] as f32 * (vd_xx - vd_yy);
+
+        if colors_sh_degree_max >= 3 {
+            let vd_zz_5_1 = vd_zz * 5.0 - 1.0;
+
+            basis[9] = c_3[0] as f32 * (vd[1] * (vd_xx * 3.0 - vd_yy));
+            basis[10] = c_3[1] as f32 * (vd[2] * (vd[0] * vd[1]));
+            basis[11] = c_3[2] as f32 * (vd[1] * vd_zz_5_1);
+            basis[12] = c_3[3] as f32 * (vd[2] * (vd_zz_5_1 - 2.0));
+            basis[13] = c_3[4] as f32 * (vd[0] * vd_zz_5_1);
+            basis[14] = c_3[5] as f32 * (vd[2] * (vd_xx - vd_yy));
+            basis[15] = c_3[6] as f32 * (vd[0] * (vd_xx - vd_yy * 3.0));
+        }
+    }
+
+    basis
+}
+
+/// The lens distortion at a normalized position `Pn[2]`.
+///
+/// `Pn'[2] = Pn[2] * ρ(r^2) + Tn(Pn)`
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Distortion {
+    /// `ρ`
+    pub radial: f32,
+    /// `∂ρ/∂r^2`
+    pub radial_grad: f32,
+    /// `∂^2ρ/∂(r^2)^2`
+    pub radial_grad_2: f32,
+    /// `[p1, p2]`
+    pub tangential: Vec2,
+}
+
+impl Distortion {
+    /// Evaluate the distortion of `camera_model` at `Pn[2]`.
+    pub fn new(
+        camera_model: u32,
+        coefficients: &[f32; 6],
+        position: &Vec2,
+    ) -> Self {
+        let [k1, k2, k3, k4, p1, p2] = *coefficients;
+        let r2 = position[0] * position[0] + position[1] * position[1];
+
+        match camera_model {
+            // ρ = (1 + k1 * r^2 + k2 * r^4 + k3 * r^6) / (1 + k4 * r^2)
+            CAMERA_MODEL_OPENCV => {
+                let numerator = 1.0 + r2 * (k1 + r2 * (k2 + r2 * k3));
+                let numerator_grad = k1 + r2 * (2.0 * k2 + r2 * 3.0 * k3);
+                let numerator_grad_2 = 2.0 * k2 + r2 * 6.0 * k3;
+                let denominator = 1.0 + r2 * k4;
+                let radial = numerator / denominator;
+                let radial_grad = (numerator_grad - radial * k4) / denominator;
+                let radial_grad_2 =
+                    (numerator_grad_2 - 2.0 * radial_grad * k4) / denominator;
+                Self {
+                    radial,
+                    radial_grad,
+                    radial_grad_2,
+                    tangential: [p1, p2],
+                }
+            },
+            // ρ = θ_d(atan(r)) / r
+            //
+            // It is expanded into the series of r^2 around zero.
+            CAMERA_MODEL_FISHEYE => {
+                if r2 < FISHEYE_SERIES_BOUND {
+                    let c_1 = k1 - 1.0 / 3.0;
+                    let c_2 = 0.2 - k1 + k2;
+                    let c_3 = -1.0 / 7.0 + 14.0 / 15.0 * k1 - 5.0 / 3.0 * k2 + k3;
+                    return Self {
+                        radial: 1.0 + r2 * (c_1 + r2 * (c_2 + r2 * c_3)),
+                        radial_grad: c_1 + r2 * (2.0 * c_2 + r2 * 3.0 * c_3),
+                        radial_grad_2: 2.0 * c_2 + r2 * 6.0 * c_3,
+                        tangential: [0.0; 2],
+                    };
+                }
+                let r = r2.sqrt();
+                let angle = r.atan();
+                let angle2 = angle * angle;
+                let angle_distorted = angle
+                    * (1.0 + angle2 * (k1 + angle2 * (k2 + angle2 * (k3 + angle2 * k4))));
+                let angle_distorted_grad = 1.0
+                    + angle2
+                        * (3.0 * k1
+                            + angle2
+                                * (5.0 * k2 + angle2 * (7.0 * k3 + angle2 * 9.0 * k4)));
+                let angle_distorted_grad_2 = angle
+                    * (6.0 * k1
+                        + angle2
+                            * (20.0 * k2 + angle2 * (42.0 * k3 + angle2 * 72.0 * k4)));
+                // h = θ_d(atan(r)), h' = ∂h/∂r, h'' = ∂^2h/∂r^2
+                let angle_r = 1.0 / (1.0 + r2);
+                let h = angle_distorted;
+                let h_1 = angle_distorted_grad * angle_r;
+                let h_2 = (angle_distorted_grad_2 - 2.0 * r * angle_distorted_grad)
+                    * angle_r
+                    * angle_r;
+                // ρ = h / r, ∂ρ/∂r = (h' - ρ) / r
+                let radial = h / r;
+                let radial_grad_r = (h_1 - radial) / r;
+                Self {
+                    radial,
+                    radial_grad: radial_grad_r / (2.0 * r),
+                    radial_grad_2: (h_2 - 3.0 * radial_grad_r) / (4.0 * r2 * r),
+                    tangential: [0.0; 2],
+                }
+            },
+            _ => Self {
+                radial: 1.0,
+                radial_grad: 0.0,
+                radial_grad_2: 0.0,
+                tangential: [0.0; 2],
+            },
+        }
+    }
+
+    /// `Pn'[2] = Pn[2] * ρ + [2 * p1 * x * y + p2 * (r^2 + 2 * x^2),
+    ///                       p1 * (r^2 + 2 * y^2) + 2 * p2 * x * y]`
+    pub fn position(
+        &self,
+        position: &Vec2,
+    ) -> Vec2 {
+        let [x, y] = *position;
+        let [p1, p2] = self.tangential;
+        let r2 = x * x + y * y;
+        [
+            x * self.radial + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+            y * self.radial + p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+        ]
+    }
+
+    /// `Jd[2, 2] = ∂Pn'/∂Pn = ρ * I + 2 * ∂ρ/∂r^2 * Pn * Pn^t + ∂Tn/∂Pn`
+    ///
+    /// It is in **row-major order**, i.e., `Jd[row][col]`.
+    pub fn jacobian(
+        &self,
+        position: &Vec2,
+    ) -> [[f32; 2]; 2] {
+        let [x, y] = *position;
+        let [p1, p2] = self.tangential;
+        let xy = 2.0 * self.radial_grad * x * y + 2.0 * p1 * x + 2.0 * p2 * y;
+        [
+            [
+                self.radial
+                    + 2.0 * self.radial_grad * x * x
+                    + 2.0 * p1 * y
+                    + 6.0 * p2 * x,
+                xy,
+            ],
+            [
+                xy,
+                self.radial
+                    + 2.0 * self.radial_grad * y * y
+                    + 6.0 * p1 * y
+                    + 2.0 * p2 * x,
+            ],
+        ]
+    }
+
+    /// `∂L/∂Pn[2] = 〈∂L/∂Jd[2, 2], ∂Jd/∂Pn[2, 2, 2]〉`
+    ///
+    /// `jacobian_grad` is in **row-major order**, i.e., `∂L/∂Jd[row][col]`.
+    pub fn jacobian_grad(
+        &self,
+        position: &Vec2,
+        jacobian_grad: &[[f32; 2]; 2],
+    ) -> Vec2 {
+        let [x, y] = *position;
+        let [p1, p2] = self.tangential;
+        let m = jacobian_grad;
+        let trace = m[0][0] + m[1][1];
+        let m_p = [m[0][0] * x + m[0][1] * y, m[1][0] * x + m[1][1] * y];
+        let m_t_p = [m[0][0] * x + m[1][0] * y, m[0][1] * x + m[1][1] * y];
+        let p_t_m_p = x * m_p[0] + y * m_p[1];
+        let m_off = m[0][1] + m[1][0];
+        let radial_common =
+            2.0 * self.radial_grad * trace + 4.0 * self.radial_grad_2 * p_t_m_p;
+        [
+            radial_common * x
+                + 2.0 * self.radial_grad * (m_p[0] + m_t_p[0])
+                + 6.0 * p2 * m[0][0]
+                + 2.0 * p1 * m_off
+                + 2.0 * p2 * m[1][1],
+            radial_common * y
+                + 2.0 * self.radial_grad * (m_p[1] + m_t_p[1])
+                + 2.0 * p1 * m[0][0]
+                + 2.0 * p2 * m_off
+                + 6.0 * p1 * m[1][1],
+        ]
+    }
+}
+
+/// `∂Pn/∂Pv[2, 3]` of the equirectangular projection.
+///
+/// It returns `None` at the poles, i.e., `Pv.x = Pv.z = 0`.
+///
+/// ```plaintext
+/// ∂Pn/∂Pv[2, 3] = [[Pv.z / ρ^2,           0,          -Pv.x / ρ^2          ]
+///                  [-Pv.x * Pv.y / r^2 ρ, ρ / r^2,    -Pv.z * Pv.y / r^2 ρ ]]
+/// ρ = √(Pv.x^2 + Pv.z^2)
+/// r = |Pv|
+/// ```
+pub(crate) fn equirectangular_jacobian(position: &Vec3) -> Option<Mat2x3> {
+    let [x, y, z] = *position;
+    let rho2 = x * x + z * z;
+    if rho2 == 0.0 {
+        return None;
+    }
+    let rho = rho2.sqrt();
+    let r2 = rho2 + y * y;
+    let r2_rho = r2 * rho;
+    Some([
+        [z / rho2, 0.0, -x / rho2],
+        [-x * y / r2_rho, rho / r2, -z * y / r2_rho],
+    ])
+}
+
+/// `∂L/∂Pv[3] = 〈∂L/∂J[2, 3], ∂J/∂Pv[2, 3, 3]〉`
+/// of the equirectangular projection `J = ∂Pn/∂Pv`.
+pub(crate) fn equirectangular_jacobian_grad(
+    position: &Vec3,
+    jacobian_grad: &Mat2x3,
+) -> Vec3 {
+    let [x, y, z] = *position;
+    let [g_0, g_1] = jacobian_grad;
+    let rho2 = x * x + z * z;
+    let rho = rho2.sqrt();
+    let r2 = rho2 + y * y;
+
+    // s = (∂L/∂J.0,0 * Pv.z - ∂L/∂J.0,2 * Pv.x) / ρ^2
+    let s = (g_0[0] * z - g_0[2] * x) / rho2;
+    // t = (-∂L/∂J.1,0 * Pv.x * Pv.y + ∂L/∂J.1,1 * ρ^2 - ∂L/∂J.1,2 * Pv.z * Pv.y) / w
+    // w = r^2 * ρ
+    let w = r2 * rho;
+    let t = (-g_1[0] * x * y + g_1[1] * rho2 - g_1[2] * z * y) / w;
+    let w_grad = [
+        2.0 * x * rho + r2 * x / rho,
+        2.0 * y * rho,
+        2.0 * z * rho + r2 * z / rho,
+    ];
+    [
+        (-g_0[2] - 2.0 * x * s) / rho2
+            + (-g_1[0] * y + 2.0 * g_1[1] * x - t * w_grad[0]) / w,
+        (-g_1[0] * x - g_1[2] * z - t * w_grad[1]) / w,
+        (g_0[0] - 2.0 * z * s) / rho2
+            + (2.0 * g_1[1] * z - g_1[2] * y - t * w_grad[2]) / w,
+    ]
+}
+
+/// The bound of `r^2` to expand the fisheye distortion into series.
+const FISHEYE_SERIES_BOUND: f32 = 1e-2;
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn camera_model_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 0.2),
+                ([0.0, 1.0, 0.5], [-0.6, 0.5, 4.0], 0.5, 0.2),
+            ],
+            &device,
+        );
+
+        // The OpenCV camera model without distortion is the pinhole one.
+        let target = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .colors_rgb_2d;
+        let view = render::View {
+            camera_model: render::CameraModel::OpenCv {
+                k1: 0.0,
+                k2: 0.0,
+                k3: 0.0,
+                k4: 0.0,
+                p1: 0.0,
+                p2: 0.0,
+            },
+            ..VIEW_IDENTITY
+        };
+        let output = scene
+            .render(&view, &Default::default())
+            .unwrap()
+            .colors_rgb_2d;
+        output.into_data().assert_approx_eq(&target.into_data(), 5);
+
+        // The splat is centered at the distorted position.
+        let position = [0.9, -0.7, 2.0];
+        let scene = scene_from_points::<NdArray>(
+            &[([1.0, 1.0, 1.0], position, 0.5, 0.02)],
+            &device,
+        );
+        for camera_model in CAMERA_MODELS_DISTORTED {
+            let view = render::View {
+                camera_model,
+                image_height: 128,
+                image_width: 128,
+                ..VIEW_IDENTITY
+            };
+            let alphas_2d =
+                to_vec(scene.render(&view, &Default::default()).unwrap().alphas_2d);
+            let alpha_sum = alphas_2d.iter().sum::<f64>();
+            let output = [0, 1].map(|axis| {
+                alphas_2d
+                    .iter()
+                    .enumerate()
+                    .map(|(index, alpha)| [index % 128, index / 128][axis] as f64 * alpha)
+                    .sum::<f64>()
+                    / alpha_sum
+            });
+
+            let position_undistorted =
+                [position[0] / position[2], position[1] / position[2]];
+            let (position_distorted, _) = camera_model.distort(position_undistorted);
+            let project = |position: [f64; 2]| {
+                [
+                    position[0] * view.focal_length_x() + view.principal_point_x() - 0.5,
+                    position[1] * view.focal_length_y() + view.principal_point_y() - 0.5,
+                ]
+            };
+            let target = project(position_distorted);
+            let target_undistorted = project(position_undistorted);
+            for ((output, target), target_undistorted) in
+                output.into_iter().zip(target).zip(target_undistorted)
+            {
+                assert!(
+                    (output - target).abs() < 5e-2,
+                    "{camera_model:?}: {output}, {target}"
+                );
+                assert!((target - target_undistorted).abs() > 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn depth_range_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.0, 0.0, 0.1], 0.1, 1.0),
+                ([0.0, 0.5, 1.0], [0.02, -0.01, 0.12], 0.3, 0.5),
+            ],
+            &device,
+        );
+
+        let output = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .colors_rgb_2d;
+        assert_eq!(output.sum().into_scalar(), 0.0);
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_depth_near(0.01),
+            )
+            .unwrap()
+            .colors_rgb_2d;
+        assert!(output.sum().into_scalar() > 0.0);
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default()
+                    .with_depth_far(0.05)
+                    .with_depth_near(0.01),
+            )
+            .unwrap()
+            .colors_rgb_2d;
+        assert_eq!(output.sum().into_scalar(), 0.0);
+
+        let output = scene.render(
+            &VIEW_IDENTITY,
+            &Gaussian3dRenderOptions::default()
+                .with_depth_far(0.5)
+                .with_depth_near(1.0),
+        );
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn equirectangular_render_ndarray() {
+        let device = Default::default();
+        let view = render::View {
+            camera_model: render::CameraModel::Equirectangular,
+            image_height: 32,
+            image_width: 64,
+            ..VIEW_IDENTITY
+        };
+
+        // The splat is centered at the longitude and the latitude.
+        let [longitude, latitude, distance] = [0.5_f64, 0.3, 4.0];
+        let position = [
+            distance * latitude.cos() * longitude.sin(),
+            distance * latitude.sin(),
+            distance * latitude.cos() * longitude.cos(),
+        ];
+        let scene = scene_from_points::<NdArray>(
+            &[([1.0, 0.5, 0.0], position, 0.6, 0.3)],
+            &device,
+        );
+        let alphas_2d =
+            to_vec(scene.render(&view, &Default::default()).unwrap().alphas_2d);
+        let alpha_sum = alphas_2d.iter().sum::<f64>();
+        let output = [0, 1].map(|axis| {
+            alphas_2d
+                .iter()
+                .enumerate()
+                .map(|(index, alpha)| [index % 64, index / 64][axis] as f64 * alpha)
+                .sum::<f64>()
+                / alpha_sum
+        });
+        let target = [
+            longitude * view.focal_length_x() + view.principal_point_x() - 0.5,
+            latitude * view.focal_length_y() + view.principal_point_y() - 0.5,
+        ];
+        for (output, target) in output.into_iter().zip(target) {
+            assert!((output - target).abs() < 5e-2, "{output}, {target}");
+        }
+
+        // The splat behind the view is centered at the seam of ±180°,
+        // and covers both sides symmetrically.
+        let scene = scene_from_points::<NdArray>(
+            &[([1.0, 0.5, 0.0], [0.0, 0.0, -4.0], 0.6, 0.5)],
+            &device,
+        );
+        let alphas_2d =
+            to_vec(scene.render(&view, &Default::default()).unwrap().alphas_2d);
+        let alpha = |x: usize, y: usize| alphas_2d[y * 64 + x];
+        assert!(alpha(0, 15) > 0.3);
+        assert_eq!(alpha(32, 15), 0.0);
+        for y in 0..32 {
+            for x in 0..32 {
+                let output = alpha(x, y);
+                let target = alpha(63 - x, y);
+                assert!(
+                    (output - target).abs() < 1e-5,
+                    "({x}, {y}): {output}, {target}"
+                );
+                let target = alpha(x, 31 - y);
+                assert!(
+                    (output - target).abs() < 1e-5,
+                    "({x}, {y}): {output}, {target}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn factor_radius_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 0.2),
+                ([0.0, 1.0, 0.5], [-0.4, 0.3, 5.0], 0.4, 0.3),
+            ],
+            &device,
+        );
+
+        let target = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .alphas_2d
+            .greater_elem(0.0)
+            .int()
+            .sum()
+            .into_scalar();
+        assert!(target > 0);
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_factor_radius(1.0),
+            )
+            .unwrap()
+            .alphas_2d
+            .greater_elem(0.0)
+            .int()
+            .sum()
+            .into_scalar();
+        assert!(output < target);
+
+        let output = scene.render(
+            &VIEW_IDENTITY,
+            &Gaussian3dRenderOptions::default().with_factor_radius(0.0),
+        );
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn filter_low_pass_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 1e-3),
+                ([0.0, 0.5, 1.0], [-0.3, 0.2, 6.0], 0.8, 1e-3),
+            ],
+            &device,
+        );
+
+        let target = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .alphas_2d;
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default()
+                    .with_filter_low_pass(render::jit::FILTER_LOW_PASS as f32),
+            )
+            .unwrap()
+            .alphas_2d;
+        output
+            .into_data()
+            .assert_eq(&target.to_owned().into_data(), true);
+
+        let target = target.greater_elem(0.0).int().sum().into_scalar();
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_filter_low_pass(1.0),
+            )
+            .unwrap()
+            .alphas_2d
+            .greater_elem(0.0)
+            .int()
+            .sum()
+            .into_scalar();
+        assert!(output > target);
+
+        let output = scene.render(
+            &VIEW_IDENTITY,
+            &Gaussian3dRenderOptions::default().with_filter_low_pass(-0.1),
+        );
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn filter_render_ndarray() {
+        let device = Default::default();
+        let mut scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 1e-3),
+                ([0.0, 0.5, 1.0], [-0.3, 0.2, 6.0], 0.8, 1e-3),
+            ],
+            &device,
+        );
+
+        assert!(scene.filters_3d.is_none());
+        let target = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+
+        // F_3d = (D / F)^2
+        scene.update_filters_3d([&VIEW_IDENTITY], &Default::default());
+        let filters_3d = to_vec(scene.filters_3d.to_owned().unwrap());
+        let filters_3d_target =
+            [4.0, 6.0].map(|depth| (depth / VIEW_IDENTITY.focal_length_x()).powi(2));
+        assert_relative_eq(&filters_3d, &filters_3d_target, 1e-5, "filters_3d");
+
+        // The 3D filters are disabled by default.
+        let output = scene.render(&VIEW_IDENTITY, &Default::default()).unwrap();
+        output
+            .alphas_2d
+            .into_data()
+            .assert_eq(&target.alphas_2d.to_owned().into_data(), true);
+
+        let alpha_2d_max = target.alphas_2d.max().into_scalar();
+        assert!(alpha_2d_max > 0.0);
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_filter_3d_scale(0.2),
+            )
+            .unwrap()
+            .alphas_2d;
+        assert!(output.max().into_scalar() < alpha_2d_max);
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_filter_mip_2d(true),
+            )
+            .unwrap()
+            .alphas_2d;
+        assert!(output.max().into_scalar() < alpha_2d_max);
+
+        let output = scene.render(
+            &VIEW_IDENTITY,
+            &Gaussian3dRenderOptions::default().with_filter_3d_scale(-1.0),
+        );
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn orthographic_render_ndarray() {
+        let device = Default::default();
+        let view = render::View {
+            camera_model: render::CameraModel::Orthographic,
+            field_of_view_x: 2.0,
+            field_of_view_y: 2.0,
+            ..VIEW_IDENTITY
+        };
+        let scene_near = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.3, -0.2, 2.0], 0.6, 0.1),
+                ([0.0, 0.5, 1.0], [-0.4, 0.1, 3.0], 0.5, 0.2),
+            ],
+            &device,
+        );
+        // The points are farther along the view axis.
+        let scene_far = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.3, -0.2, 8.0], 0.6, 0.1),
+                ([0.0, 0.5, 1.0], [-0.4, 0.1, 9.0], 0.5, 0.2),
+            ],
+            &device,
+        );
+
+        let target = scene_near
+            .render(&view, &Default::default())
+            .unwrap()
+            .colors_rgb_2d;
+        let output = scene_far
+            .render(&view, &Default::default())
+            .unwrap()
+            .colors_rgb_2d;
+        assert!(target.to_owned().sum().into_scalar() > 0.0);
+        output.into_data().assert_approx_eq(&target.into_data(), 5);
+    }
+
+    #[test]
+    fn principal_point_render_ndarray() {
+        let device = Default::default();
+        let scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 0.2),
+                ([0.0, 0.5, 1.0], [-0.3, 0.25, 5.0], 0.7, 0.3),
+            ],
+            &device,
+        );
+        let view = render::View {
+            principal_point_offset_x: 4.0,
+            principal_point_offset_y: -2.0,
+            ..VIEW_IDENTITY
+        };
+
+        let target = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .slice([2..32, 0..28]);
+        let output = scene
+            .render(&view, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .slice([0..30, 4..32]);
+        output.into_data().assert_approx_eq(&target.into_data(), 4);
+    }
+}
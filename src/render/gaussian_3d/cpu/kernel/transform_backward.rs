@@ -0,0 +1,926 @@
+//! Transforming the points (backward).
+//!
+//! It mirrors [`jit::kernel::transform_backward`](super::super::super::jit::kernel::transform_backward).
+
+pub use super::*;
+
+use rayon::prelude::*;
+
+/// Arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `(0 ~ 3)`
+    pub colors_sh_degree_max: u32,
+    /// `[k1, k2, k3, k4, p1, p2]`
+    pub distortion: [f32; 6],
+    /// `C_f`
+    pub filter_2d: f32,
+    /// `C_3d`
+    pub filter_3d_scale: f32,
+    /// `(0: Low-pass filter, 1: Mip filter)`
+    pub filter_mip_2d: u32,
+    /// `f_x <- I_x / tan(Fov_x / 2) / 2`
+    pub focal_length_x: f32,
+    /// `f_y <- I_y / tan(Fov_y / 2) / 2`
+    pub focal_length_y: f32,
+    /// `I_x / 2`
+    pub image_size_half_x: f32,
+    /// `I_y / 2`
+    pub image_size_half_y: f32,
+    /// `P`
+    pub point_count: u32,
+    /// `(I_x - C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_max: f32,
+    /// `-(C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_min: f32,
+    /// `(I_y - C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_max: f32,
+    /// `-(C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_min: f32,
+    /// `[3]`
+    pub view_position: [f32; 3],
+    /// `[3 (+ 1), 3 + 1]`
+    pub view_transform: [[f32; 4]; 4],
+}
+
+/// Inputs.
+#[derive(Clone, Copy, Debug)]
+pub struct Inputs<'a> {
+    /// `[P, 3]`
+    pub colors_rgb_3d_grad: &'a [f32],
+    /// `[P, M * 3]` <- `[P, M, 3]`
+    pub colors_sh: &'a [f32],
+    /// `[P, 3]`
+    pub conics: &'a [f32],
+    /// `[P, 3]`
+    pub conics_grad: &'a [f32],
+    /// `[P]`
+    pub depths: &'a [f32],
+    /// `[P]`
+    pub depths_grad: &'a [f32],
+    /// `[P, 1]`
+    pub filters_3d: &'a [f32],
+    /// `[P, 3]`
+    pub is_colors_rgb_3d_not_clamped: &'a [f32],
+    /// `[P, 1]` (Inner)
+    pub opacities: &'a [f32],
+    /// `[P]` (Outer)
+    pub opacities_3d_grad: &'a [f32],
+    /// `[P, 2]`
+    pub positions_2d_grad: &'a [f32],
+    /// `[P, 2]`
+    pub positions_2d_grad_abs: &'a [f32],
+    /// `[P, 3]`
+    pub positions_3d: &'a [f32],
+    /// `[P, 2]`
+    pub positions_3d_in_normalized: &'a [f32],
+    /// `[P]`
+    pub radii: &'a [u32],
+    /// `[P, 4]`
+    pub rotations: &'a [f32],
+    /// `[P, 3, 3]`
+    pub rotations_matrix: &'a [f32],
+    /// `[P, 3]`
+    pub scalings: &'a [f32],
+}
+
+/// Outputs.
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    /// `[P, M * 3]` <- `[P, M, 3]`
+    pub colors_sh_grad: Vec<f32>,
+    /// `[P, 2]`
+    pub focal_lengths_grad: Vec<f32>,
+    /// `[P, 1]`
+    pub opacities_grad: Vec<f32>,
+    /// `[P]`
+    pub positions_2d_grad_abs_norm: Vec<f32>,
+    /// `[P]`
+    pub positions_2d_grad_norm: Vec<f32>,
+    /// `[P, 3]`
+    pub positions_3d_grad: Vec<f32>,
+    /// `[P, 4]`
+    pub rotations_grad: Vec<f32>,
+    /// `[P, 3]`
+    pub scalings_grad: Vec<f32>,
+    /// `[P, 6]`
+    pub view_transforms_grad: Vec<f32>,
+}
+
+/// The outputs of a point.
+#[derive(Clone, Copy, Debug)]
+struct Output {
+    color_sh_grad: [Vec3; SH_COUNT_MAX],
+    focal_length_grad: Vec2,
+    opacity_grad: f32,
+    position_2d_grad_abs_norm: f32,
+    position_2d_grad_norm: f32,
+    position_3d_grad: Vec3,
+    rotation_grad: Vec4,
+    scaling_grad: Vec3,
+    view_transform_grad: [f32; 6],
+}
+
+impl Default for Output {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            color_sh_grad: [Default::default(); SH_COUNT_MAX],
+            focal_length_grad: Default::default(),
+            opacity_grad: Default::default(),
+            position_2d_grad_abs_norm: Default::default(),
+            position_2d_grad_norm: Default::default(),
+            position_3d_grad: Default::default(),
+            rotation_grad: Default::default(),
+            scaling_grad: Default::default(),
+            view_transform_grad: Default::default(),
+        }
+    }
+}
+
+/// Transforming the points (backward).
+pub fn main(
+    arguments: &Arguments,
+    inputs: &Inputs,
+) -> Outputs {
+    // P
+    let point_count = arguments.point_count as usize;
+
+    let points = (0..point_count)
+        .into_par_iter()
+        .map(|index| {
+            if inputs.radii[index] == 0 {
+                return Default::default();
+            }
+            transform_backward(arguments, inputs, index)
+        })
+        .collect::<Vec<_>>();
+
+    let mut outputs = Outputs {
+        colors_sh_grad: Vec::with_capacity(point_count * SH_COUNT_MAX * 3),
+        focal_lengths_grad: Vec::with_capacity(point_count * 2),
+        opacities_grad: Vec::with_capacity(point_count),
+        positions_2d_grad_abs_norm: Vec::with_capacity(point_count),
+        positions_2d_grad_norm: Vec::with_capacity(point_count),
+        positions_3d_grad: Vec::with_capacity(point_count * 3),
+        rotations_grad: Vec::with_capacity(point_count * 4),
+        scalings_grad: Vec::with_capacity(point_count * 3),
+        view_transforms_grad: Vec::with_capacity(point_count * 6),
+    };
+    for point in points {
+        outputs
+            .colors_sh_grad
+            .extend(point.color_sh_grad.into_iter().flatten());
+        outputs.focal_lengths_grad.extend(point.focal_length_grad);
+        outputs.opacities_grad.push(point.opacity_grad);
+        outputs
+            .positions_2d_grad_abs_norm
+            .push(point.position_2d_grad_abs_norm);
+        outputs
+            .positions_2d_grad_norm
+            .push(point.position_2d_grad_norm);
+        outputs.positions_3d_grad.extend(point.position_3d_grad);
+        outputs.rotations_grad.extend(point.rotation_grad);
+        outputs.scalings_grad.extend(point.scaling_grad);
+        outputs
+            .view_transforms_grad
+            .extend(point.view_transform_grad);
+    }
+
+    outputs
+}
+
+/// Computing the gradients of the point at `index`.
+fn transform_backward(
+    arguments: &Arguments,
+    inputs: &Inputs,
+    index: usize,
+) -> Output {
+    // Computing the gradients
+    //
+    // ∂L/∂Σ'[2, 2] = -Σ'^-1[2, 2] * ∂L/∂Σ'^-1[2, 2] * Σ'^-1[2, 2]
+
+    let conic = mat2_sym_from_slice(&inputs.conics[index * 3..]);
+    let conic_grad = mat2_sym_from_slice(&inputs.conics_grad[index * 3..]);
+    let mut covariance_2d_grad =
+        mat2_mul(&mat2_mul(&conic, &conic_grad), &conic).map(|row| row.map(|c| -c));
+
+    // Computing the 3D covariance matrix from rotation and scaling
+
+    let rotation_matrix = mat3_from_slice(&inputs.rotations_matrix[index * 9..]);
+    // (Outer)
+    let scaling = vec3_from_slice(&inputs.scalings[index * 3..]).map(f32::exp);
+    let rotation_scaling = [
+        rotation_matrix[0].map(|r| r * scaling[0]),
+        rotation_matrix[1].map(|r| r * scaling[1]),
+        rotation_matrix[2].map(|r| r * scaling[2]),
+    ];
+    let mut covariance_3d =
+        mat3_mul(&rotation_scaling, &mat3_transpose(&rotation_scaling));
+    let filter_3d = arguments.filter_3d_scale * inputs.filters_3d[index];
+    let opacity_compensation_3d = transform::filter_3d_compensation(&scaling, filter_3d);
+    for axis in 0..3 {
+        covariance_3d[axis][axis] += filter_3d;
+    }
+
+    // Projecting the 3D covariance matrix into 2D covariance matrix
+
+    let depth = inputs.depths[index];
+    let focal_length = [arguments.focal_length_x, arguments.focal_length_y];
+    let position_3d_in_normalized = [
+        inputs.positions_3d_in_normalized[index * 2],
+        inputs.positions_3d_in_normalized[index * 2 + 1],
+    ];
+    let position_3d_in_normalized_clamped = [
+        position_3d_in_normalized[0]
+            .clamp(arguments.view_bound_x_min, arguments.view_bound_x_max),
+        position_3d_in_normalized[1]
+            .clamp(arguments.view_bound_y_min, arguments.view_bound_y_max),
+    ];
+    let distortion = transform::Distortion::new(
+        arguments.camera_model,
+        &arguments.distortion,
+        &position_3d_in_normalized_clamped,
+    );
+    let is_equirectangular =
+        arguments.camera_model == transform::CAMERA_MODEL_EQUIRECTANGULAR;
+    let is_orthographic = arguments.camera_model == transform::CAMERA_MODEL_ORTHOGRAPHIC;
+    let (view_rotation, view_translation) =
+        view_rotation_translation(&arguments.view_transform);
+    // Pv[3]
+    let position_3d_in_view = {
+        let position_3d = vec3_from_slice(&inputs.positions_3d[index * 3..]);
+        let position_3d_in_view = mat3_mul_vec3(&view_rotation, &position_3d);
+        [0, 1, 2].map(|axis| position_3d_in_view[axis] + view_translation[axis])
+    };
+    // ∂Pn/∂Pv[2, 3]
+    let equirectangular_jacobian = if is_equirectangular {
+        transform::equirectangular_jacobian(&position_3d_in_view).unwrap_or_default()
+    } else {
+        Mat2x3::default()
+    };
+    let projection_2d_left = if is_equirectangular {
+        [0, 1].map(|row| equirectangular_jacobian[row].map(|j| j * focal_length[row]))
+    } else {
+        let (depth_projected, position_3d_in_normalized_projected) = if is_orthographic {
+            (1.0, [0.0; 2])
+        } else {
+            (depth, position_3d_in_normalized_clamped)
+        };
+        let distortion_jacobian = distortion.jacobian(&position_3d_in_normalized_clamped);
+        [0, 1].map(|row| {
+            let k =
+                distortion_jacobian[row].map(|j| j * focal_length[row] / depth_projected);
+            [
+                k[0],
+                k[1],
+                -k[0] * position_3d_in_normalized_projected[0]
+                    - k[1] * position_3d_in_normalized_projected[1],
+            ]
+        })
+    };
+    let projection_2d = mat2x3_mul_mat3(&projection_2d_left, &view_rotation);
+
+    // Computing the opacity compensation of the 2D mip filter
+    // Σ'u[2, 2] (Symmetric) = T[2, 3] * Σ[3, 3] * T^t[3, 2]
+    // Σ'[2, 2] (Symmetric) = Σ'u[2, 2] + C_f * I[2, 2]
+    // ρ_2d = √(|Σ'u| / |Σ'|)
+    //
+    // ∂ρ_2d/∂Σ'u[2, 2] = (adj(Σ'u)[2, 2] - ρ_2d^2 * adj(Σ')[2, 2]) / |Σ'| / ρ_2d / 2
+
+    let mut opacity_compensation_2d = 1.0;
+    let mut opacity_compensation_2d_to_covariance_2d_grad = [[0.0; 2]; 2];
+    if arguments.filter_mip_2d != 0 {
+        let projection_2d_covariance_3d = [
+            vec3_mul_mat3(&projection_2d[0], &covariance_3d),
+            vec3_mul_mat3(&projection_2d[1], &covariance_3d),
+        ];
+        let [a, b, c] = [
+            dot_3(&projection_2d_covariance_3d[0], &projection_2d[0]),
+            dot_3(&projection_2d_covariance_3d[0], &projection_2d[1]),
+            dot_3(&projection_2d_covariance_3d[1], &projection_2d[1]),
+        ];
+        let a_filtered = a + arguments.filter_2d;
+        let c_filtered = c + arguments.filter_2d;
+        let covariance_2d_det = a_filtered * c_filtered - b * b;
+        let covariance_2d_ratio = (a * c - b * b) / covariance_2d_det;
+        if covariance_2d_ratio > 0.0 {
+            opacity_compensation_2d = covariance_2d_ratio.sqrt();
+            let k = 0.5 / opacity_compensation_2d / covariance_2d_det;
+            let b_grad = (covariance_2d_ratio - 1.0) * b * k;
+            opacity_compensation_2d_to_covariance_2d_grad = [
+                [(c - covariance_2d_ratio * c_filtered) * k, b_grad],
+                [b_grad, (a - covariance_2d_ratio * a_filtered) * k],
+            ];
+        } else {
+            opacity_compensation_2d = 0.0;
+        }
+    }
+
+    // Computing the gradients
+    //
+    // α[1] = sigmoid(α_inner[1]) * ρ_3d * ρ_2d
+    //
+    // ∂L/∂α_inner = ∂L/∂α * sigmoid'(α_inner) * ρ_3d * ρ_2d
+    // ∂L/∂ρ_3d = ∂L/∂α * sigmoid(α_inner) * ρ_2d
+    // ∂L/∂ρ_2d = ∂L/∂α * sigmoid(α_inner) * ρ_3d
+    // ∂L/∂Σ'u[2, 2] = ∂L/∂Σ'[2, 2] + ∂L/∂ρ_2d * ∂ρ_2d/∂Σ'u[2, 2]
+
+    let opacity_3d_grad = inputs.opacities_3d_grad[index];
+    let opacity = sigmoid(inputs.opacities[index]);
+    let opacity_grad = opacity_3d_grad
+        * opacity
+        * (1.0 - opacity)
+        * opacity_compensation_3d
+        * opacity_compensation_2d;
+    let opacity_compensation_3d_grad =
+        opacity_3d_grad * opacity * opacity_compensation_2d;
+    let opacity_compensation_2d_grad =
+        opacity_3d_grad * opacity * opacity_compensation_3d;
+    for i in 0..2 {
+        for j in 0..2 {
+            covariance_2d_grad[i][j] += opacity_compensation_2d_grad
+                * opacity_compensation_2d_to_covariance_2d_grad[i][j];
+        }
+    }
+
+    // Computing the gradients
+    //
+    // ∂L/∂Σ[3, 3] = T^t[3, 2] * ∂L/∂Σ'[2, 2] * T[2, 3]
+    // ∂L/∂T[2, 3] = ∂L/∂Σ'[2, 2] * T[2, 3] * Σ[3, 3] * 2
+    // ∂L/∂J[2, 3] = ∂L/∂T[2, 3] * Rv^t[3, 3]
+
+    // ∂L/∂Σ[3, 3]
+    let mut covariance_3d_grad = Mat3::default();
+    for c in 0..3 {
+        for r in 0..3 {
+            covariance_3d_grad[c][r] = (0..2)
+                .map(|i| {
+                    (0..2)
+                        .map(|j| {
+                            projection_2d[i][r]
+                                * covariance_2d_grad[i][j]
+                                * projection_2d[j][c]
+                        })
+                        .sum::<f32>()
+                })
+                .sum::<f32>();
+        }
+    }
+    // ∂L/∂T[2, 3]
+    let covariance_2d_grad_projection_2d = [0, 1].map(|i| {
+        [0, 1, 2].map(|c| {
+            covariance_2d_grad[i][0] * projection_2d[0][c]
+                + covariance_2d_grad[i][1] * projection_2d[1][c]
+        })
+    });
+    let projection_2d_grad = covariance_2d_grad_projection_2d
+        .map(|row| vec3_mul_mat3(&row, &covariance_3d).map(|c| c * 2.0));
+    // ∂L/∂J[2, 3]
+    let projection_2d_left_grad =
+        projection_2d_grad.map(|row| mat3_mul_vec3(&view_rotation, &row));
+
+    // Computing the gradients
+    //
+    // ∂L/∂Jd[2, 2] = F[2, 2] * ∂L/∂J[2, 3] * [[1, 0, -Pn.x], [0, 1, -Pn.y]]^t / Pv.z
+    // ∂L/∂Pn[2] = -K^t[2, 2] * ∂L/∂J[2, 3].2 / Pv.z +〈∂L/∂Jd, ∂Jd/∂Pn〉
+    // ∂L/∂Pv[3] = [∂L/∂Pn.x / Pv.z,
+    //              ∂L/∂Pn.y / Pv.z,
+    //              -〈∂L/∂J, J〉/ Pv.z -〈∂L/∂Pn, Pn〉/ Pv.z]
+
+    let is_position_3d_in_normalized_not_clamped = [0, 1].map(|i| {
+        if position_3d_in_normalized[i] == position_3d_in_normalized_clamped[i] {
+            1.0
+        } else {
+            0.0
+        }
+    });
+    let [x, y] = position_3d_in_normalized_clamped;
+    let g = &projection_2d_left_grad;
+    // ∂L/∂Jd[2, 2]
+    let distortion_jacobian_grad = [0, 1].map(|row| {
+        [
+            focal_length[row] * (g[row][0] - x * g[row][2]) / depth,
+            focal_length[row] * (g[row][1] - y * g[row][2]) / depth,
+        ]
+    });
+    // ∂L/∂Pn[2]
+    let distortion_jacobian_to_normalized_grad = distortion.jacobian_grad(
+        &position_3d_in_normalized_clamped,
+        &distortion_jacobian_grad,
+    );
+    let position_3d_in_normalized_grad = [0, 1].map(|col| {
+        distortion_jacobian_to_normalized_grad[col]
+            - projection_2d_left[0][col] * g[0][2]
+            - projection_2d_left[1][col] * g[1][2]
+    });
+    //〈∂L/∂J, J〉
+    let projection_2d_left_dot_grad = (0..2)
+        .map(|row| dot_3(&projection_2d_left[row], &g[row]))
+        .sum::<f32>();
+    // ∂L/∂Pv[3]
+    //
+    // J is constant for the orthographic camera model
+    let position_3d_in_view_grad = if is_orthographic {
+        Vec3::default()
+    } else if is_equirectangular {
+        transform::equirectangular_jacobian_grad(
+            &position_3d_in_view,
+            &[0, 1].map(|row| g[row].map(|g| g * focal_length[row])),
+        )
+    } else {
+        [
+            is_position_3d_in_normalized_not_clamped[0]
+                * position_3d_in_normalized_grad[0]
+                / depth,
+            is_position_3d_in_normalized_not_clamped[1]
+                * position_3d_in_normalized_grad[1]
+                / depth,
+            (-projection_2d_left_dot_grad
+                - x * position_3d_in_normalized_grad[0]
+                - y * position_3d_in_normalized_grad[1])
+                / depth,
+        ]
+    };
+
+    // Computing the gradients
+    //
+    // ∂L/∂Pw^t[1, 3] = ∂L/∂Pv^t[1, 3] * Rv[3, 3]
+
+    let mut position_3d_grad = vec3_mul_mat3(&position_3d_in_view_grad, &view_rotation);
+
+    // Computing the gradients
+    //
+    // ∂L/∂RS[3, 3] = 2 * ∂L/∂Σ[3, 3] * RS[3, 3]
+    // ∂L/∂R[3, 3] = ∂L/∂RS[3, 3] * S^t[3, 3]
+    // ∂L/∂S[3, 3] = R^t[3, 3] * ∂L/∂RS[3, 3]
+
+    let rotation_scaling_grad = mat3_mul(&covariance_3d_grad, &rotation_scaling)
+        .map(|column| column.map(|c| c * 2.0));
+    // ∂L/∂R[3, 3]
+    let rotation_matrix_grad = [
+        rotation_scaling_grad[0].map(|r| r * scaling[0]),
+        rotation_scaling_grad[1].map(|r| r * scaling[1]),
+        rotation_scaling_grad[2].map(|r| r * scaling[2]),
+    ];
+    // ∂L/∂S[3, 3] (Inner)
+    let mut scaling_grad = [
+        scaling[0] * dot_3(&rotation_matrix[0], &rotation_scaling_grad[0]),
+        scaling[1] * dot_3(&rotation_matrix[1], &rotation_scaling_grad[1]),
+        scaling[2] * dot_3(&rotation_matrix[2], &rotation_scaling_grad[2]),
+    ];
+
+    // Computing the gradients
+    //
+    // ρ_3d = √(Π(S^2) / Π(S^2 + C_3d * F_3d))
+    //
+    // ∂L/∂S[3] (Inner) += ∂L/∂ρ_3d * ρ_3d * C_3d * F_3d / (S^2[3] + C_3d * F_3d)
+
+    if filter_3d != 0.0 {
+        for axis in 0..3 {
+            scaling_grad[axis] +=
+                opacity_compensation_3d_grad * opacity_compensation_3d * filter_3d
+                    / (scaling[axis] * scaling[axis] + filter_3d);
+        }
+    }
+
+    // Computing the gradients
+    //
+    // ∂L/∂Q[4] = [∂L/∂R * ∂R/∂Q.x, ∂L/∂R * ∂R/∂Q.y, ∂L/∂R * ∂R/∂Q.z, ∂L/∂R * ∂R/∂Q.w]
+
+    let rotation_inner = &inputs.rotations[index * 4..index * 4 + 4];
+    let rotation_inner = [
+        rotation_inner[0],
+        rotation_inner[1],
+        rotation_inner[2],
+        rotation_inner[3],
+    ];
+    let rotation_inner_norm = rotation_inner.iter().map(|q| q * q).sum::<f32>().sqrt();
+    // (Outer)
+    let q = rotation_inner.map(|q| q / rotation_inner_norm);
+    let q_n_2 = q.map(|q| -2.0 * q);
+    let q_n_w = q_n_2[3] / 2.0;
+    let g = &rotation_matrix_grad;
+    let rotation_outer_grad = [
+        dot_3(&g[0], &[0.0, q[1], q[2]])
+            + dot_3(&g[1], &[q[1], q_n_2[0], q[3]])
+            + dot_3(&g[2], &[q[2], q_n_w, q_n_2[0]]),
+        dot_3(&g[0], &[q_n_2[1], q[0], q_n_w])
+            + dot_3(&g[1], &[q[0], 0.0, q[2]])
+            + dot_3(&g[2], &[q[3], q[2], q_n_2[1]]),
+        dot_3(&g[0], &[q_n_2[2], q[3], q[0]])
+            + dot_3(&g[1], &[q_n_w, q_n_2[2], q[1]])
+            + dot_3(&g[2], &[q[0], q[1], 0.0]),
+        dot_3(&g[0], &[0.0, q[2], -q[1]])
+            + dot_3(&g[1], &[-q[2], 0.0, q[0]])
+            + dot_3(&g[2], &[q[1], -q[0], 0.0]),
+    ]
+    .map(|g| g * 2.0);
+    // ∂L/∂Q[4] (Inner)
+    let rotation_grad = normalize_grad_4(&rotation_inner, &rotation_outer_grad);
+
+    // Computing the gradients
+    //
+    // ∂L/∂Pw[1, 3] = ∂L/∂Pv'[1, 2] * ∂Pv'/∂Pn[2, 2] * ∂Pn/∂Pv[2, 3] * ∂Pv/∂Pw[3, 3]
+
+    // ∂L/∂Pv'[1, 2]
+    let position_2d_grad = [
+        inputs.positions_2d_grad[index * 2],
+        inputs.positions_2d_grad[index * 2 + 1],
+    ];
+    // ∂L/∂Pn[1, 2] = ∂L/∂Pv'[1, 2] * F[2, 2] * Jd[2, 2]
+    let distortion_jacobian = distortion.jacobian(&position_3d_in_normalized);
+    let position_2d_to_normalized_grad = [0, 1].map(|col| {
+        position_2d_grad[0] * focal_length[0] * distortion_jacobian[0][col]
+            + position_2d_grad[1] * focal_length[1] * distortion_jacobian[1][col]
+    });
+    // ∂L/∂Pv[1, 3]
+    //
+    // ∂Pn/∂Pv[2, 3] = [[1, 0, 0], [0, 1, 0]] for the orthographic camera model
+    let position_2d_to_3d_in_view_grad = if is_orthographic {
+        [
+            position_2d_to_normalized_grad[0],
+            position_2d_to_normalized_grad[1],
+            0.0,
+        ]
+    } else if is_equirectangular {
+        vec2_mul_mat2x3(&position_2d_to_normalized_grad, &equirectangular_jacobian)
+    } else {
+        [
+            position_2d_to_normalized_grad[0] / depth,
+            position_2d_to_normalized_grad[1] / depth,
+            -(position_2d_to_normalized_grad[0] * position_3d_in_normalized[0]
+                + position_2d_to_normalized_grad[1] * position_3d_in_normalized[1])
+                / depth,
+        ]
+    };
+    // ∂L/∂Pw[1, 3]
+    let position_3d_grad_2d =
+        vec3_mul_mat3(&position_2d_to_3d_in_view_grad, &view_rotation);
+    for axis in 0..3 {
+        position_3d_grad[axis] += position_3d_grad_2d[axis];
+    }
+
+    // Computing the gradients
+    //
+    // ∂L/∂Pw[1, 3] = ∂L/∂D * ∂D/∂Pw[1, 3]
+    // ∂D/∂Pw[1, 3] = [0, 0, 1] * Rv[3, 3]
+    //
+    // ∂D/∂Pw[1, 3] = Pv^t[1, 3] / |Pv| * Rv[3, 3] for the equirectangular camera model
+
+    // ∂L/∂D
+    let depth_grad = inputs.depths_grad[index];
+    // ∂L/∂Pv[1, 3]
+    let depth_to_3d_in_view_grad = if is_equirectangular {
+        position_3d_in_view.map(|p| depth_grad * p / depth)
+    } else {
+        [0.0, 0.0, depth_grad]
+    };
+    // ∂L/∂Pw[1, 3]
+    let position_3d_grad_depth = vec3_mul_mat3(&depth_to_3d_in_view_grad, &view_rotation);
+    for axis in 0..3 {
+        position_3d_grad[axis] += position_3d_grad_depth[axis];
+    }
+
+    // Computing the norm of 2D positions gradient
+    // |∂L/∂Pv'[1, 2] * [I_x / 2, I_y / 2]|
+
+    let position_2d_grad_norm = ((position_2d_grad[0] * arguments.image_size_half_x)
+        .powi(2)
+        + (position_2d_grad[1] * arguments.image_size_half_y).powi(2))
+    .sqrt();
+
+    // Computing the norm of 2D positions absolute gradient
+    // ||∂L/∂Pv'|[1, 2] * [I_x / 2, I_y / 2]|
+    //
+    // It is zero unless the absolute gradient is accumulated in rasterization
+
+    let position_2d_grad_abs = &inputs.positions_2d_grad_abs[index * 2..index * 2 + 2];
+    let position_2d_grad_abs_norm =
+        ((position_2d_grad_abs[0] * arguments.image_size_half_x).powi(2)
+            + (position_2d_grad_abs[1] * arguments.image_size_half_y).powi(2))
+        .sqrt();
+
+    // Computing the view direction in world space
+
+    let position_3d = vec3_from_slice(&inputs.positions_3d[index * 3..]);
+    let view_offset = if is_orthographic {
+        [
+            view_rotation[0][2],
+            view_rotation[1][2],
+            view_rotation[2][2],
+        ]
+    } else {
+        [
+            position_3d[0] - arguments.view_position[0],
+            position_3d[1] - arguments.view_position[1],
+            position_3d[2] - arguments.view_position[2],
+        ]
+    };
+    let vd = normalize_3(&view_offset);
+
+    // Computing the gradients
+    //
+    // ∂L/∂C_sh[16, 3] = D^t[16, 1] * ∂L/∂C_rgb[1, 3]
+    // ∂L/∂Dv[1, 3] = ∂L/∂C_rgb[1, 3] * ∂C_rgb/∂Dv[3, 3]
+    //
+    // C_rgb was clamped
+
+    let color_rgb_3d_grad = [0, 1, 2].map(|channel| {
+        inputs.colors_rgb_3d_grad[index * 3 + channel]
+            * inputs.is_colors_rgb_3d_not_clamped[index * 3 + channel]
+    });
+    let color_sh_basis = transform::sh_basis(arguments.colors_sh_degree_max, &vd);
+    let color_sh_grad = color_sh_basis.map(|basis| color_rgb_3d_grad.map(|g| g * basis));
+
+    let color_sh = &inputs.colors_sh[index * SH_COUNT_MAX * 3..];
+    let color_rgb_3d_to_view_direction_grad =
+        sh_basis_grad(arguments.colors_sh_degree_max, &vd);
+    // ∂L/∂Dv[1, 3]
+    let mut view_direction_grad = Vec3::default();
+    for (basis_index, basis_grad) in
+        color_rgb_3d_to_view_direction_grad.iter().enumerate()
+    {
+        let color_rgb_3d_grad_dot_color_sh = (0..3)
+            .map(|channel| {
+                color_rgb_3d_grad[channel] * color_sh[basis_index * 3 + channel]
+            })
+            .sum::<f32>();
+        for axis in 0..3 {
+            view_direction_grad[axis] +=
+                color_rgb_3d_grad_dot_color_sh * basis_grad[axis];
+        }
+    }
+
+    // ∂L/∂Ov[1, 3] = ∂L/∂Dv[1, 3] * ∂Dv/∂Ov[3, 3]
+    let view_offset_grad = normalize_grad_3(&view_offset, &view_direction_grad);
+    // ∂L/∂Pw[1, 3] = ∂L/∂Ov[1, 3]
+    //
+    // Dv is constant for the orthographic camera model
+    if !is_orthographic {
+        for axis in 0..3 {
+            position_3d_grad[axis] += view_offset_grad[axis];
+        }
+    }
+
+    // Computing the gradients of the view transform in tangent space
+    //
+    // Tv'[4, 4] = exp(ξ) * Tv[4, 4], where ξ[6] = [ρ[3], φ[3]]
+    // Pv'[3] = Pv[3] + ρ[3] + φ[3] × Pv[3]
+    // T'[2, 3] = J[2, 3] * (I + [φ]x)[3, 3] * Rv[3, 3]
+    // V'[3] = V[3] - Rv^t[3, 3] * ρ[3]
+    //
+    // ∂L/∂ρ[3] = ∂L/∂Pv[3] + Rv[3, 3] * ∂L/∂Ov[3]
+    // ∂L/∂φ[3] = Pv[3] × ∂L/∂Pv[3] + Σ(∂L/∂J[2, 3] × J[2, 3])
+    //
+    // ∂L/∂ρ[3] = ∂L/∂Pv[3] for the orthographic camera model
+    // ∂L/∂φ[3] += (Rv[3, 3] * ∂L/∂Ov[3]) × [0, 0, 1] for the orthographic camera model
+
+    // ∂L/∂Pv[3]
+    let position_3d_in_view_grad_total = [0, 1, 2].map(|axis| {
+        position_3d_in_view_grad[axis]
+            + position_2d_to_3d_in_view_grad[axis]
+            + depth_to_3d_in_view_grad[axis]
+    });
+    // Rv[3, 3] * ∂L/∂Ov[3]
+    let view_offset_grad_in_view = mat3_mul_vec3(&view_rotation, &view_offset_grad);
+    let mut view_translation_grad = [0, 1, 2].map(|axis| {
+        position_3d_in_view_grad_total[axis] + view_offset_grad_in_view[axis]
+    });
+    let mut view_rotation_grad =
+        cross_3(&position_3d_in_view, &position_3d_in_view_grad_total);
+    for (row_grad, row) in projection_2d_left_grad.iter().zip(&projection_2d_left) {
+        let view_rotation_grad_projection_2d_left = cross_3(row_grad, row);
+        for axis in 0..3 {
+            view_rotation_grad[axis] += view_rotation_grad_projection_2d_left[axis];
+        }
+    }
+    if is_orthographic {
+        view_translation_grad = position_3d_in_view_grad_total;
+        let view_rotation_grad_view_offset =
+            cross_3(&view_offset_grad_in_view, &[0.0, 0.0, 1.0]);
+        for axis in 0..3 {
+            view_rotation_grad[axis] += view_rotation_grad_view_offset[axis];
+        }
+    }
+    let view_transform_grad = [
+        view_translation_grad[0],
+        view_translation_grad[1],
+        view_translation_grad[2],
+        view_rotation_grad[0],
+        view_rotation_grad[1],
+        view_rotation_grad[2],
+    ];
+
+    // Computing the gradients of the focal lengths
+    //
+    // ∂L/∂F[2] = ∂L/∂Pv'[2] * Pn'[2] +〈∂L/∂J[2, 3], J[2, 3]〉[2] / F[2]
+    //
+    // The wrapping of the equirectangular camera model is ignored
+
+    let position_3d_in_distorted = distortion.position(&position_3d_in_normalized);
+    let focal_length_grad = [0, 1].map(|row| {
+        position_2d_grad[row] * position_3d_in_distorted[row]
+            + dot_3(&projection_2d_left_grad[row], &projection_2d_left[row])
+                / focal_length[row]
+    });
+
+    Output {
+        color_sh_grad,
+        focal_length_grad,
+        opacity_grad,
+        position_2d_grad_abs_norm,
+        position_2d_grad_norm,
+        position_3d_grad,
+        rotation_grad,
+        scaling_grad,
+        view_transform_grad,
+    }
+}
+
+/// Return `∂D/∂Dv[M, 3]` of the SH basis `D[M]`.
+///
+/// The basis above `colors_sh_degree_max` is zero.
+pub(crate) fn sh_basis_grad(
+    colors_sh_degree_max: u32,
+    vd: &Vec3,
+) -> [Vec3; SH_COUNT_MAX] {
+    let (_, c_1, c_2, c_3) = &*SH_COEF;
+    let mut grad = [Vec3::default(); SH_COUNT_MAX];
+
+    if colors_sh_degree_max >= 1 {
+        grad[1] = [0.0, c_1[0] as f32, 0.0];
+        grad[2] = [0.0, 0.0, c_1[1] as f32];
+        grad[3] = [c_1[2] as f32, 0.0, 0.0];
+    }
+
+    if colors_sh_degree_max >= 2 {
+        let [x, y, z] = *vd;
+        let c_2 = c_2.map(|c| c as f32);
+
+        grad[4] = [c_2[0] * y, c_2[0] * x, 0.0];
+        grad[5] = [0.0, c_2[1] * z, c_2[1] * y];
+        grad[6] = [0.0, 0.0, c_2[2] * z * 6.0];
+        grad[7] = [c_2[3] * z, 0.0, c_2[3] * x];
+        grad[8] = [c_2[4] * x * 2.0, c_2[4] * y * -2.0, 0.0];
+
+        if colors_sh_degree_max >= 3 {
+            let c_3 = c_3.map(|c| c as f32);
+            let xx_yy = x * x - y * y;
+            let zz_5_1 = z * z * 5.0 - 1.0;
+
+            grad[9] = [c_3[0] * x * y * 6.0, c_3[0] * xx_yy * 3.0, 0.0];
+            grad[10] = [c_3[1] * y * z, c_3[1] * x * z, c_3[1] * x * y];
+            grad[11] = [0.0, c_3[2] * zz_5_1, c_3[2] * y * z * 10.0];
+            grad[12] = [0.0, 0.0, c_3[3] * zz_5_1 * 3.0];
+            grad[13] = [c_3[4] * zz_5_1, 0.0, c_3[4] * x * z * 10.0];
+            grad[14] = [c_3[5] * x * z * 2.0, c_3[5] * y * z * -2.0, c_3[5] * xx_yy];
+            grad[15] = [c_3[6] * xx_yy * 3.0, c_3[6] * x * y * -6.0, 0.0];
+        }
+    }
+
+    grad
+}
+
+/// `M[2, 2] * N[2, 2]`
+#[inline]
+fn mat2_mul(
+    m: &[[f32; 2]; 2],
+    n: &[[f32; 2]; 2],
+) -> [[f32; 2]; 2] {
+    [
+        [
+            m[0][0] * n[0][0] + m[0][1] * n[1][0],
+            m[0][0] * n[0][1] + m[0][1] * n[1][1],
+        ],
+        [
+            m[1][0] * n[0][0] + m[1][1] * n[1][0],
+            m[1][0] * n[0][1] + m[1][1] * n[1][1],
+        ],
+    ]
+}
+
+#[inline]
+fn mat2_sym_from_slice(a: &[f32]) -> [[f32; 2]; 2] {
+    [[a[0], a[1]], [a[1], a[2]]]
+}
+
+/// `∂L/∂X[3] = ∂L/∂Y[3] * ∂Y/∂X[3, 3]` where `Y[3] = X[3] / |X|`
+#[inline]
+fn normalize_grad_3(
+    x: &Vec3,
+    y_grad: &Vec3,
+) -> Vec3 {
+    let x_dot = dot_3(x, x);
+    let x_y_grad_dot = dot_3(x, y_grad);
+    let factor = x_dot.powf(-1.5);
+    [0, 1, 2].map(|i| (x_dot * y_grad[i] - x[i] * x_y_grad_dot) * factor)
+}
+
+/// `∂L/∂X[4] = ∂L/∂Y[4] * ∂Y/∂X[4, 4]` where `Y[4] = X[4] / |X|`
+#[inline]
+fn normalize_grad_4(
+    x: &Vec4,
+    y_grad: &Vec4,
+) -> Vec4 {
+    let x_dot = x.iter().map(|x| x * x).sum::<f32>();
+    let x_y_grad_dot = x.iter().zip(y_grad).map(|(x, g)| x * g).sum::<f32>();
+    let factor = x_dot.powf(-1.5);
+    [0, 1, 2, 3].map(|i| (x_dot * y_grad[i] - x[i] * x_y_grad_dot) * factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn equirectangular_render_ndarray_autodiff() {
+        let device = Default::default();
+        let view = render::View {
+            camera_model: render::CameraModel::Equirectangular,
+            image_height: 32,
+            image_width: 64,
+            ..VIEW_IDENTITY
+        };
+
+        // The points straddle the seam of ±180°.
+        let mut scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([1.0, 0.5, 0.0], [0.3, 0.2, -4.0], 0.5, 1.0),
+                ([0.0, 0.5, 1.0], [-0.4, -0.3, -5.0], 0.7, 1.0),
+            ],
+            &device,
+        );
+        scene
+            .set_inner_rotations(
+                Tensor::from_data(
+                    TensorData::from([[0.1_f32, 0.2, -0.1, 0.95], [-0.2, 0.1, 0.3, 0.9]]),
+                    &device,
+                )
+                .set_require_grad(true),
+            )
+            .set_inner_scalings(
+                Gaussian3dScene::make_inner_scalings(Tensor::from_data(
+                    TensorData::from([[2.4_f32, 2.0, 2.8], [2.9, 3.3, 2.5]]),
+                    &device,
+                ))
+                .set_require_grad(true),
+            );
+        assert_render_grads_finite_difference(&scene, &view, &Default::default(), 5e-2);
+    }
+
+    #[test]
+    fn filter_render_ndarray_autodiff() {
+        let device = Default::default();
+        let mut scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 0.01),
+                ([0.0, 0.5, 1.0], [-0.3, 0.2, 6.0], 0.4, 0.2),
+            ],
+            &device,
+        );
+        scene.update_filters_3d([&VIEW_IDENTITY], &Default::default());
+
+        let options = Gaussian3dRenderOptions::default()
+            .with_filter_3d_scale(0.2)
+            .with_filter_mip_2d(true);
+        let mut grads = scene
+            .render(&VIEW_IDENTITY, &options)
+            .unwrap()
+            .alphas_2d
+            .sum()
+            .backward();
+
+        // The 3D filters are constant.
+        assert!(scene
+            .filters_3d
+            .as_ref()
+            .unwrap()
+            .grad_remove(&mut grads)
+            .is_none());
+        let opacities_grad = scene.opacities.grad_remove(&mut grads).unwrap();
+        assert!(opacities_grad.min().into_scalar() > 0.0);
+        let scalings_grad = scene.scalings.grad_remove(&mut grads).unwrap();
+        assert!(scalings_grad.is_finite().all().into_scalar());
+    }
+
+    #[test]
+    fn grads_finite_difference_render_ndarray_autodiff() {
+        let scene = scene_smooth::<Autodiff<NdArray>>(&Default::default());
+        for view in views_finite_difference() {
+            assert_render_grads_finite_difference(
+                &scene,
+                &view,
+                &Default::default(),
+                2e-2,
+            );
+        }
+    }
+
+    #[test]
+    fn positions_2d_grad_abs_render_ndarray_autodiff() {
+        assert_positions_2d_grad_abs::<NdArray>(&Default::default());
+    }
+}
@@ -0,0 +1,922 @@
+//! 3DGS renderer with CPU.
+//!
+//! It is a reference implementation of the [JIT renderer](super::jit)
+//! that runs on any [`Backend`].
+//! The tensors are read into the host memory
+//! and the kernels are computed in parallel using [`rayon`].
+
+pub mod kernel;
+
+pub use super::{backward, forward, Gaussian3dRenderOptions, View};
+pub use crate::{backend::Backend, error::Error, scene::gaussian_3d::SH_DEGREE_MAX};
+pub use kernel::{
+    CONTRIBUTOR_COUNT_MAX, FACTOR_RADIUS, FILTER_LOW_PASS, FILTER_MIP_2D, TILE_COUNT_MAX,
+    TILE_COUNT_MAX_32_BIT, TILE_SIZE_X, TILE_SIZE_Y, VIEW_BOUND_MARGIN,
+};
+
+use burn::tensor::{Int, Tensor, TensorData, TensorPrimitive};
+use kernel::*;
+
+/// Maximum of the pixel count in an image (Largest `I_y * I_x`).
+pub const PIXEL_COUNT_MAX: u32 = TILE_SIZE_X * TILE_SIZE_Y * TILE_COUNT_MAX;
+
+/// Render the 3DGS scene (forward).
+///
+/// It computes the colors in RGB space from the 3DGS scene.
+///
+/// The kernels are computed in the following order:
+/// 1. [Transform](transform::main) the scene parameters.
+/// 2. [Rank](rank::main) the points by its tile index and depth, and then sort them.
+/// 3. [Segment](segment::main) the points by its tile index.
+/// 4. [Rasterize](rasterize::main) the points into the image.
+/// 5. [Rasterize](rasterize_features::main) the point features into the image
+///    if they are specified.
+/// 6. [Rasterize](rasterize_labels::main) the point labels into the image
+///    if they are specified.
+pub fn forward<B: Backend>(
+    input: forward::RenderInput<B>,
+    view: &View,
+    options: &Gaussian3dRenderOptions,
+) -> Result<forward::RenderOutput<B>, Error> {
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "start");
+
+    // Specifying the arguments
+
+    let background_rgb = options.background_rgb;
+    let camera_model = view.camera_model.id();
+    let colors_sh_degree_max = options.colors_sh_degree_max;
+    // K
+    let contributor_count = options.contributor_count;
+    // D_far
+    let depth_far = options.depth_far;
+    // D_near
+    let depth_near = options.depth_near;
+    // [k1, k2, k3, k4, p1, p2]
+    let distortion = view.camera_model.coefficients().map(|c| c as f32);
+    // k
+    let factor_radius = options.factor_radius;
+    // C_f
+    let filter_2d = if options.filter_mip_2d {
+        FILTER_MIP_2D as f32
+    } else {
+        options.filter_low_pass
+    };
+    // C_3d
+    let filter_3d_scale = options.filter_3d_scale;
+    // (0: Low-pass filter, 1: Mip filter)
+    let filter_mip_2d = options.filter_mip_2d as u32;
+    // (0: Disabled, 1: Enabled)
+    let positions_2d_grad_abs = options.positions_2d_grad_abs as u32;
+    // I_x
+    let image_size_x = view.image_width;
+    // I_y
+    let image_size_y = view.image_height;
+    // F_x <- I_x / tan(Fov_x / 2) / 2
+    let focal_length_x = view.focal_length_x() as f32;
+    // F_y <- I_y / tan(Fov_y / 2) / 2
+    let focal_length_y = view.focal_length_y() as f32;
+    // I_x / 2
+    let image_size_half_x = (image_size_x as f64 / 2.0) as f32;
+    // I_y / 2
+    let image_size_half_y = (image_size_y as f64 / 2.0) as f32;
+    // C_x <- I_x / 2 + O_x
+    let principal_point_x = view.principal_point_x() as f32;
+    // C_y <- I_y / 2 + O_y
+    let principal_point_y = view.principal_point_y() as f32;
+    // [I_x0, I_y0, I_x', I_y']
+    let [region_offset_x, region_offset_y, region_size_x, region_size_y] =
+        options.region.unwrap_or([0, 0, image_size_x, image_size_y]);
+    // I_y' * I_x'
+    let pixel_count = region_size_x as usize * region_size_y as usize;
+    // P
+    let point_count = input.point_count as u32;
+    // I_x0 / T_x
+    let tile_offset_x = region_offset_x / TILE_SIZE_X;
+    // I_y0 / T_y
+    let tile_offset_y = region_offset_y / TILE_SIZE_Y;
+    // (I_x0 + I_x') / T_x - I_x0 / T_x
+    //
+    // The tiles are aligned to the image rather than the region.
+    let tile_count_x = region_offset_x
+        .saturating_add(region_size_x)
+        .div_ceil(TILE_SIZE_X)
+        - tile_offset_x;
+    // (I_y0 + I_y') / T_y - I_y0 / T_y
+    let tile_count_y = region_offset_y
+        .saturating_add(region_size_y)
+        .div_ceil(TILE_SIZE_Y)
+        - tile_offset_y;
+    // (I_y / T_y) * (I_x / T_x)
+    let tile_count = image_size_x.div_ceil(TILE_SIZE_X) as usize
+        * image_size_y.div_ceil(TILE_SIZE_Y) as usize;
+    // (1: 32-bit, 2: 64-bit)
+    //
+    // The 64-bit point orders are required if there are too many tiles.
+    // It depends on the tiles of the whole image rather than the region,
+    // so that the points are sorted identically in any region.
+    let point_order_word_count = if options.sort_key_bit_count == 32
+        && tile_count <= TILE_COUNT_MAX_32_BIT as usize
+    {
+        1
+    } else {
+        2
+    };
+    // (I_x - C_x + I_x / 2 * C_m) / F_x
+    // -(C_x + I_x / 2 * C_m) / F_x
+    // (I_y - C_y + I_y / 2 * C_m) / F_y
+    // -(C_y + I_y / 2 * C_m) / F_y
+    //
+    // The bounds are undistorted for the distorted camera models.
+    let [view_bound_x_max, view_bound_x_min, view_bound_y_max, view_bound_y_min] = view
+        .camera_model
+        .undistort_bounds([
+            (image_size_x as f64 - view.principal_point_x()
+                + image_size_x as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_x(),
+            -(view.principal_point_x() + image_size_x as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_x(),
+            (image_size_y as f64 - view.principal_point_y()
+                + image_size_y as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_y(),
+            -(view.principal_point_y() + image_size_y as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_y(),
+        ])
+        .map(|bound| bound as f32);
+    let view_position = view.view_position.map(|c| c as f32);
+    let view_transform = view.view_transform.map(|c| c.map(|c| c as f32));
+
+    if colors_sh_degree_max > SH_DEGREE_MAX {
+        return Err(Error::UnsupportedSphericalHarmonicsDegree(
+            colors_sh_degree_max,
+        ));
+    }
+    if contributor_count > CONTRIBUTOR_COUNT_MAX {
+        return Err(Error::InvalidContributorCount(contributor_count));
+    }
+    if !(depth_near > 0.0 && depth_near < depth_far && depth_far.is_finite()) {
+        return Err(Error::InvalidDepthRange(depth_near, depth_far));
+    }
+    if !(factor_radius > 0.0 && factor_radius.is_finite()) {
+        return Err(Error::InvalidFactorRadius(factor_radius));
+    }
+    if !(filter_3d_scale >= 0.0 && filter_3d_scale.is_finite()) {
+        return Err(Error::InvalidFilter3dScale(filter_3d_scale));
+    }
+    if !(options.filter_low_pass >= 0.0 && options.filter_low_pass.is_finite()) {
+        return Err(Error::InvalidFilterLowPass(options.filter_low_pass));
+    }
+    if !matches!(options.sort_key_bit_count, 32 | 64) {
+        return Err(Error::UnsupportedSortKeyBitCount(
+            options.sort_key_bit_count,
+        ));
+    }
+    if region_size_x == 0
+        || region_size_y == 0
+        || region_offset_x as u64 + region_size_x as u64 > image_size_x as u64
+        || region_offset_y as u64 + region_size_y as u64 > image_size_y as u64
+    {
+        return Err(Error::InvalidRegion(
+            [
+                region_offset_x,
+                region_offset_y,
+                region_size_x,
+                region_size_y,
+            ],
+            [image_size_x, image_size_y],
+        ));
+    }
+    if pixel_count == 0 || pixel_count > PIXEL_COUNT_MAX as usize {
+        return Err(Error::InvalidPixelCount(pixel_count));
+    }
+    if point_count == 0 {
+        return Err(Error::MismatchedPointCount(0, "non-zero".into()));
+    }
+
+    // Specifying the inputs
+
+    let device = &input.device;
+    let colors_sh = into_vec_f32::<B>(input.colors_sh.to_owned());
+    let filters_3d = into_vec_f32::<B>(input.filters_3d.to_owned());
+    let opacities = into_vec_f32::<B>(input.opacities.to_owned());
+    let positions = into_vec_f32::<B>(input.positions.to_owned());
+    let rotations = into_vec_f32::<B>(input.rotations.to_owned());
+    let scalings = into_vec_f32::<B>(input.scalings.to_owned());
+
+    // Transforming the parameters
+
+    let outputs_transform = transform::main(
+        &transform::Arguments {
+            camera_model,
+            colors_sh_degree_max,
+            depth_far,
+            depth_near,
+            distortion,
+            factor_radius,
+            filter_2d,
+            filter_3d_scale,
+            filter_mip_2d,
+            focal_length_x,
+            focal_length_y,
+            point_count,
+            principal_point_x,
+            principal_point_y,
+            tile_count_x,
+            tile_count_y,
+            tile_offset_x,
+            tile_offset_y,
+            view_bound_x_max,
+            view_bound_x_min,
+            view_bound_y_max,
+            view_bound_y_min,
+            view_position,
+            view_transform,
+        },
+        &transform::Inputs {
+            colors_sh: &colors_sh,
+            filters_3d: &filters_3d,
+            opacities: &opacities,
+            positions_3d: &positions,
+            rotations: &rotations,
+            scalings: &scalings,
+        },
+    );
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "transform");
+
+    // Ranking and sorting the points by its tile index and depth
+
+    let outputs_rank = rank::main(
+        &rank::Arguments {
+            depth_far,
+            depth_near,
+            point_count,
+            point_order_word_count,
+            tile_count_x,
+        },
+        &rank::Inputs {
+            depths: &outputs_transform.depths,
+            point_tile_bounds: &outputs_transform.point_tile_bounds,
+            radii: &outputs_transform.radii,
+            tile_touched_counts: &outputs_transform.tile_touched_counts,
+        },
+    );
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "rank");
+
+    // Segmenting the points by its tile index
+
+    let outputs_segment = segment::main(
+        &segment::Arguments {
+            point_order_word_count,
+            tile_count_x,
+            tile_count_y,
+        },
+        &segment::Inputs {
+            point_orders: &outputs_rank.point_orders,
+        },
+    );
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "segment");
+
+    // Rasterizing the points into the image
+
+    let outputs_rasterize = rasterize::main(
+        &rasterize::Arguments {
+            background_rgb,
+            camera_model,
+            contributor_count,
+            image_size_x,
+            image_size_y,
+            region_offset_x,
+            region_offset_y,
+            region_size_x,
+            region_size_y,
+            tile_count_x,
+            tile_count_y,
+        },
+        &rasterize::Inputs {
+            colors_rgb_3d: &outputs_transform.colors_rgb_3d,
+            conics: &outputs_transform.conics,
+            depths: &outputs_transform.depths,
+            opacities_3d: &outputs_transform.opacities_3d,
+            point_indices: &outputs_rank.point_indices,
+            positions_2d: &outputs_transform.positions_2d,
+            tile_point_ranges: &outputs_segment.tile_point_ranges,
+        },
+    );
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "rasterize");
+
+    // Rasterizing the point features into the image
+
+    let features_2d = input.features.as_ref().map(|features| {
+        // D
+        let feature_count = B::float_shape(features).dims[1];
+        let features = into_vec_f32::<B>(features.to_owned());
+
+        let outputs_rasterize_features = rasterize_features::main(
+            &rasterize_features::Arguments {
+                camera_model,
+                feature_count: feature_count as u32,
+                image_size_x,
+                image_size_y,
+                region_offset_x,
+                region_offset_y,
+                region_size_x,
+                region_size_y,
+                tile_count_x,
+                tile_count_y,
+            },
+            &rasterize_features::Inputs {
+                conics: &outputs_transform.conics,
+                features: &features,
+                opacities_3d: &outputs_transform.opacities_3d,
+                point_indices: &outputs_rank.point_indices,
+                point_rendered_counts: &outputs_rasterize.point_rendered_counts,
+                positions_2d: &outputs_transform.positions_2d,
+                tile_point_ranges: &outputs_segment.tile_point_ranges,
+            },
+        );
+
+        from_vec_f32::<B>(
+            outputs_rasterize_features.features_2d,
+            [
+                region_size_y as usize,
+                region_size_x as usize,
+                feature_count,
+            ],
+            device,
+        )
+    });
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "rasterize_features");
+
+    // Rasterizing the point labels into the image
+
+    let (label_coverages_2d, labels_2d) = input
+        .labels
+        .map(|labels| {
+            // L
+            let label_count = input.label_count;
+            let labels = into_vec_i32::<B>(labels);
+
+            let outputs_rasterize_labels = rasterize_labels::main(
+                &rasterize_labels::Arguments {
+                    camera_model,
+                    image_size_x,
+                    image_size_y,
+                    label_count,
+                    region_offset_x,
+                    region_offset_y,
+                    region_size_x,
+                    region_size_y,
+                    tile_count_x,
+                    tile_count_y,
+                },
+                &rasterize_labels::Inputs {
+                    conics: &outputs_transform.conics,
+                    labels: &labels,
+                    opacities_3d: &outputs_transform.opacities_3d,
+                    point_indices: &outputs_rank.point_indices,
+                    point_rendered_counts: &outputs_rasterize.point_rendered_counts,
+                    positions_2d: &outputs_transform.positions_2d,
+                    tile_point_ranges: &outputs_segment.tile_point_ranges,
+                },
+            );
+
+            (
+                from_vec_f32::<B>(
+                    outputs_rasterize_labels.label_coverages_2d,
+                    [
+                        region_size_y as usize,
+                        region_size_x as usize,
+                        label_count as usize,
+                    ],
+                    device,
+                ),
+                from_vec_i32::<B>(
+                    outputs_rasterize_labels.labels_2d,
+                    [region_size_y as usize, region_size_x as usize],
+                    device,
+                ),
+            )
+        })
+        .unzip();
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "rasterize_labels");
+
+    // P
+    let point_count_usize = point_count as usize;
+    // T
+    let tile_point_count = outputs_rank.point_indices.len();
+    // I_y'
+    let region_size_y_usize = region_size_y as usize;
+    // I_x'
+    let region_size_x_usize = region_size_x as usize;
+
+    Ok(forward::RenderOutput {
+        colors_rgb_2d: from_vec_f32::<B>(
+            outputs_rasterize.colors_rgb_2d,
+            [region_size_y_usize, region_size_x_usize, 3],
+            device,
+        ),
+        contributor_indices_2d: (contributor_count != 0).then(|| {
+            from_vec_i32::<B>(
+                outputs_rasterize.contributor_indices_2d,
+                [
+                    region_size_y_usize,
+                    region_size_x_usize,
+                    contributor_count as usize,
+                ],
+                device,
+            )
+        }),
+        contributor_weights_2d: (contributor_count != 0).then(|| {
+            from_vec_f32::<B>(
+                outputs_rasterize.contributor_weights_2d,
+                [
+                    region_size_y_usize,
+                    region_size_x_usize,
+                    contributor_count as usize,
+                ],
+                device,
+            )
+        }),
+        depths_2d: from_vec_f32::<B>(
+            outputs_rasterize.depths_2d,
+            [region_size_y_usize, region_size_x_usize],
+            device,
+        ),
+        depths_2d_median: from_vec_f32::<B>(
+            outputs_rasterize.depths_2d_median,
+            [region_size_y_usize, region_size_x_usize],
+            device,
+        ),
+        features_2d,
+        label_coverages_2d,
+        labels_2d,
+        state: backward::RenderInput {
+            background_rgb,
+            camera_model,
+            colors_rgb_3d: from_vec_f32::<B>(
+                outputs_transform.colors_rgb_3d,
+                [point_count_usize, 3],
+                device,
+            ),
+            colors_sh: input.colors_sh,
+            colors_sh_degree_max,
+            conics: from_vec_f32::<B>(
+                outputs_transform.conics,
+                [point_count_usize, 3],
+                device,
+            ),
+            depths: from_vec_f32::<B>(
+                outputs_transform.depths,
+                [point_count_usize],
+                device,
+            ),
+            distortion,
+            features: input.features,
+            filter_2d,
+            filter_3d_scale,
+            filter_mip_2d,
+            filters_3d: input.filters_3d,
+            focal_length_x,
+            focal_length_y,
+            image_size_half_x,
+            image_size_half_y,
+            image_size_x,
+            image_size_y,
+            is_colors_rgb_3d_not_clamped: from_vec_f32::<B>(
+                outputs_transform.is_colors_rgb_3d_not_clamped,
+                [point_count_usize, 3],
+                device,
+            ),
+            opacities: input.opacities,
+            opacities_3d: from_vec_f32::<B>(
+                outputs_transform.opacities_3d,
+                [point_count_usize],
+                device,
+            ),
+            point_count,
+            point_indices: from_vec_u32::<B>(
+                outputs_rank.point_indices,
+                [tile_point_count],
+                device,
+            ),
+            point_rendered_counts: from_vec_u32::<B>(
+                outputs_rasterize.point_rendered_counts,
+                [region_size_y_usize, region_size_x_usize],
+                device,
+            ),
+            positions_2d: from_vec_f32::<B>(
+                outputs_transform.positions_2d,
+                [point_count_usize, 2],
+                device,
+            ),
+            positions_2d_grad_abs,
+            positions_3d: input.positions,
+            positions_3d_in_normalized: from_vec_f32::<B>(
+                outputs_transform.positions_3d_in_normalized,
+                [point_count_usize, 2],
+                device,
+            ),
+            radii: from_vec_u32::<B>(
+                outputs_transform.radii,
+                [point_count_usize],
+                device,
+            ),
+            region_offset_x,
+            region_offset_y,
+            region_size_x,
+            region_size_y,
+            rotations: input.rotations,
+            rotations_matrix: from_vec_f32::<B>(
+                outputs_transform.rotations_matrix,
+                [point_count_usize, 3, 3],
+                device,
+            ),
+            scalings: input.scalings,
+            tile_count_x,
+            tile_count_y,
+            tile_point_ranges: from_vec_u32::<B>(
+                outputs_segment.tile_point_ranges,
+                [tile_count_y as usize, tile_count_x as usize, 2],
+                device,
+            ),
+            transmittances: from_vec_f32::<B>(
+                outputs_rasterize.transmittances,
+                [region_size_y_usize, region_size_x_usize],
+                device,
+            ),
+            view_bound_x_max,
+            view_bound_x_min,
+            view_bound_y_max,
+            view_bound_y_min,
+            view_position,
+            view_transform,
+        },
+    })
+}
+
+/// Render the 3DGS scene (backward).
+///
+/// It computes the gradients of `alphas_2d`, `colors_rgb_2d`, `depths_2d`
+/// and `features_2d` with respect to scene parameters.
+///
+/// The kernels are computed in the following order:
+/// 1. Compute the gradients for [rasterization](rasterize_backward::main).
+/// 2. Compute the gradients for [rasterization of the features](rasterize_features_backward::main)
+///    if they are specified.
+/// 3. Compute the gradients for [transformation](transform_backward::main).
+pub fn backward<B: Backend>(
+    state: backward::RenderInput<B>,
+    alphas_2d_grad: B::FloatTensorPrimitive,
+    colors_rgb_2d_grad: B::FloatTensorPrimitive,
+    depths_2d_grad: B::FloatTensorPrimitive,
+    features_2d_grad: Option<B::FloatTensorPrimitive>,
+) -> backward::RenderOutput<B> {
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::backward", "start");
+
+    // Specifying the inputs
+
+    let device = &B::float_device(&colors_rgb_2d_grad);
+    // P
+    let point_count = state.point_count as usize;
+
+    let alphas_2d_grad = into_vec_f32::<B>(alphas_2d_grad);
+    let colors_rgb_2d_grad = into_vec_f32::<B>(colors_rgb_2d_grad);
+    let colors_rgb_3d = into_vec_f32::<B>(state.colors_rgb_3d);
+    let colors_sh = into_vec_f32::<B>(state.colors_sh);
+    let conics = into_vec_f32::<B>(state.conics);
+    let depths = into_vec_f32::<B>(state.depths);
+    let depths_2d_grad = into_vec_f32::<B>(depths_2d_grad);
+    let filters_3d = into_vec_f32::<B>(state.filters_3d);
+    let is_colors_rgb_3d_not_clamped =
+        into_vec_f32::<B>(state.is_colors_rgb_3d_not_clamped);
+    let opacities = into_vec_f32::<B>(state.opacities);
+    let opacities_3d = into_vec_f32::<B>(state.opacities_3d);
+    let point_indices = into_vec_u32::<B>(state.point_indices);
+    let point_rendered_counts = into_vec_u32::<B>(state.point_rendered_counts);
+    let positions_2d = into_vec_f32::<B>(state.positions_2d);
+    let positions_3d = into_vec_f32::<B>(state.positions_3d);
+    let positions_3d_in_normalized = into_vec_f32::<B>(state.positions_3d_in_normalized);
+    let radii = into_vec_u32::<B>(state.radii);
+    let rotations = into_vec_f32::<B>(state.rotations);
+    let rotations_matrix = into_vec_f32::<B>(state.rotations_matrix);
+    let scalings = into_vec_f32::<B>(state.scalings);
+    let tile_point_ranges = into_vec_u32::<B>(state.tile_point_ranges);
+    let transmittances = into_vec_f32::<B>(state.transmittances);
+
+    // Computing the gradients for rasterization
+
+    let mut outputs_rasterize_backward = rasterize_backward::main(
+        &rasterize_backward::Arguments {
+            background_rgb: state.background_rgb,
+            camera_model: state.camera_model,
+            image_size_x: state.image_size_x,
+            image_size_y: state.image_size_y,
+            point_count: state.point_count,
+            positions_2d_grad_abs: state.positions_2d_grad_abs,
+            region_offset_x: state.region_offset_x,
+            region_offset_y: state.region_offset_y,
+            region_size_x: state.region_size_x,
+            region_size_y: state.region_size_y,
+            tile_count_x: state.tile_count_x,
+            tile_count_y: state.tile_count_y,
+        },
+        &rasterize_backward::Inputs {
+            alphas_2d_grad: &alphas_2d_grad,
+            colors_rgb_2d_grad: &colors_rgb_2d_grad,
+            colors_rgb_3d: &colors_rgb_3d,
+            conics: &conics,
+            depths: &depths,
+            depths_2d_grad: &depths_2d_grad,
+            opacities_3d: &opacities_3d,
+            point_indices: &point_indices,
+            point_rendered_counts: &point_rendered_counts,
+            positions_2d: &positions_2d,
+            tile_point_ranges: &tile_point_ranges,
+            transmittances: &transmittances,
+        },
+    );
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::backward", "rasterize_backward");
+
+    // Computing the gradients for rasterization of the features
+    //
+    // The gradients of the geometry are accumulated
+    // onto those for rasterization of the colors.
+
+    let mut features_grad = None;
+    if let (Some(features), Some(features_2d_grad)) = (state.features, features_2d_grad) {
+        // D
+        let feature_count = B::float_shape(&features).dims[1];
+        let features = into_vec_f32::<B>(features);
+        let features_2d_grad = into_vec_f32::<B>(features_2d_grad);
+
+        let outputs_rasterize_features_backward = rasterize_features_backward::main(
+            &rasterize_features_backward::Arguments {
+                camera_model: state.camera_model,
+                feature_count: feature_count as u32,
+                image_size_x: state.image_size_x,
+                image_size_y: state.image_size_y,
+                point_count: state.point_count,
+                region_offset_x: state.region_offset_x,
+                region_offset_y: state.region_offset_y,
+                region_size_x: state.region_size_x,
+                region_size_y: state.region_size_y,
+                tile_count_x: state.tile_count_x,
+                tile_count_y: state.tile_count_y,
+            },
+            &rasterize_features_backward::Inputs {
+                conics: &conics,
+                features: &features,
+                features_2d_grad: &features_2d_grad,
+                opacities_3d: &opacities_3d,
+                point_indices: &point_indices,
+                point_rendered_counts: &point_rendered_counts,
+                positions_2d: &positions_2d,
+                tile_point_ranges: &tile_point_ranges,
+                transmittances: &transmittances,
+            },
+        );
+
+        [
+            (
+                &mut outputs_rasterize_backward.conics_grad,
+                outputs_rasterize_features_backward.conics_grad,
+            ),
+            (
+                &mut outputs_rasterize_backward.opacities_3d_grad,
+                outputs_rasterize_features_backward.opacities_3d_grad,
+            ),
+            (
+                &mut outputs_rasterize_backward.positions_2d_grad,
+                outputs_rasterize_features_backward.positions_2d_grad,
+            ),
+        ]
+        .into_iter()
+        .for_each(|(target, source)| {
+            target.iter_mut().zip(source).for_each(|(t, s)| *t += s);
+        });
+        features_grad = Some(from_vec_f32::<B>(
+            outputs_rasterize_features_backward.features_grad,
+            [point_count, feature_count],
+            device,
+        ));
+    }
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(
+        target: "gausplat::renderer::gaussian_3d::backward",
+        "rasterize_features_backward",
+    );
+
+    // Computing the gradients for transformation
+
+    let outputs_transform_backward = transform_backward::main(
+        &transform_backward::Arguments {
+            camera_model: state.camera_model,
+            colors_sh_degree_max: state.colors_sh_degree_max,
+            distortion: state.distortion,
+            filter_2d: state.filter_2d,
+            filter_3d_scale: state.filter_3d_scale,
+            filter_mip_2d: state.filter_mip_2d,
+            focal_length_x: state.focal_length_x,
+            focal_length_y: state.focal_length_y,
+            image_size_half_x: state.image_size_half_x,
+            image_size_half_y: state.image_size_half_y,
+            point_count: state.point_count,
+            view_bound_x_max: state.view_bound_x_max,
+            view_bound_x_min: state.view_bound_x_min,
+            view_bound_y_max: state.view_bound_y_max,
+            view_bound_y_min: state.view_bound_y_min,
+            view_position: state.view_position,
+            view_transform: state.view_transform,
+        },
+        &transform_backward::Inputs {
+            colors_rgb_3d_grad: &outputs_rasterize_backward.colors_rgb_3d_grad,
+            colors_sh: &colors_sh,
+            conics: &conics,
+            conics_grad: &outputs_rasterize_backward.conics_grad,
+            depths: &depths,
+            depths_grad: &outputs_rasterize_backward.depths_grad,
+            filters_3d: &filters_3d,
+            is_colors_rgb_3d_not_clamped: &is_colors_rgb_3d_not_clamped,
+            opacities: &opacities,
+            opacities_3d_grad: &outputs_rasterize_backward.opacities_3d_grad,
+            positions_2d_grad: &outputs_rasterize_backward.positions_2d_grad,
+            positions_2d_grad_abs: &outputs_rasterize_backward.positions_2d_grad_abs,
+            positions_3d: &positions_3d,
+            positions_3d_in_normalized: &positions_3d_in_normalized,
+            radii: &radii,
+            rotations: &rotations,
+            rotations_matrix: &rotations_matrix,
+            scalings: &scalings,
+        },
+    );
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::backward", "transform_backward");
+
+    // Reducing the gradients of the view over the points
+
+    // [2]
+    let focal_lengths_grad = B::float_reshape(
+        B::float_sum_dim(
+            from_vec_f32::<B>(
+                outputs_transform_backward.focal_lengths_grad,
+                [point_count, 2],
+                device,
+            ),
+            0,
+        ),
+        [2].into(),
+    );
+    // [6]
+    let view_transform_grad = B::float_reshape(
+        B::float_sum_dim(
+            from_vec_f32::<B>(
+                outputs_transform_backward.view_transforms_grad,
+                [point_count, 6],
+                device,
+            ),
+            0,
+        ),
+        [6].into(),
+    );
+
+    backward::RenderOutput {
+        colors_sh_grad: from_vec_f32::<B>(
+            outputs_transform_backward.colors_sh_grad,
+            [point_count, SH_COUNT_MAX * 3],
+            device,
+        ),
+        features_grad,
+        focal_lengths_grad,
+        opacities_grad: from_vec_f32::<B>(
+            outputs_transform_backward.opacities_grad,
+            [point_count, 1],
+            device,
+        ),
+        positions_2d_grad_abs_norm: (state.positions_2d_grad_abs != 0).then(|| {
+            from_vec_f32::<B>(
+                outputs_transform_backward.positions_2d_grad_abs_norm,
+                [point_count],
+                device,
+            )
+        }),
+        positions_2d_grad_norm: from_vec_f32::<B>(
+            outputs_transform_backward.positions_2d_grad_norm,
+            [point_count],
+            device,
+        ),
+        positions_grad: from_vec_f32::<B>(
+            outputs_transform_backward.positions_3d_grad,
+            [point_count, 3],
+            device,
+        ),
+        rotations_grad: from_vec_f32::<B>(
+            outputs_transform_backward.rotations_grad,
+            [point_count, 4],
+            device,
+        ),
+        scalings_grad: from_vec_f32::<B>(
+            outputs_transform_backward.scalings_grad,
+            [point_count, 3],
+            device,
+        ),
+        view_transform_grad,
+    }
+}
+
+/// Read the float tensor into the host memory.
+#[inline]
+fn into_vec_f32<B: Backend>(tensor: B::FloatTensorPrimitive) -> Vec<f32> {
+    Tensor::<B, 1>::new(TensorPrimitive::Float(tensor))
+        .into_data()
+        .convert::<f32>()
+        .into_vec()
+        .unwrap()
+}
+
+/// Read the int tensor into the host memory.
+#[inline]
+fn into_vec_i32<B: Backend>(tensor: B::IntTensorPrimitive) -> Vec<i32> {
+    Tensor::<B, 1, Int>::new(tensor)
+        .into_data()
+        .convert::<i32>()
+        .into_vec()
+        .unwrap()
+}
+
+/// Read the int tensor into the host memory.
+#[inline]
+fn into_vec_u32<B: Backend>(tensor: B::IntTensorPrimitive) -> Vec<u32> {
+    Tensor::<B, 1, Int>::new(tensor)
+        .into_data()
+        .convert::<u32>()
+        .into_vec()
+        .unwrap()
+}
+
+/// Write the float tensor into the device memory.
+#[inline]
+fn from_vec_f32<B: Backend>(
+    data: Vec<f32>,
+    shape: impl Into<Vec<usize>>,
+    device: &B::Device,
+) -> B::FloatTensorPrimitive {
+    B::float_from_data(
+        TensorData::new(data, shape.into()).convert::<B::FloatElem>(),
+        device,
+    )
+}
+
+/// Write the int tensor into the device memory.
+#[inline]
+fn from_vec_i32<B: Backend>(
+    data: Vec<i32>,
+    shape: impl Into<Vec<usize>>,
+    device: &B::Device,
+) -> B::IntTensorPrimitive {
+    B::int_from_data(
+        TensorData::new(data, shape.into()).convert::<B::IntElem>(),
+        device,
+    )
+}
+
+/// Write the int tensor into the device memory.
+#[inline]
+fn from_vec_u32<B: Backend>(
+    data: Vec<u32>,
+    shape: impl Into<Vec<usize>>,
+    device: &B::Device,
+) -> B::IntTensorPrimitive {
+    B::int_from_data(
+        TensorData::new(data, shape.into()).convert::<B::IntElem>(),
+        device,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn default_render_ndarray() {
+        Gaussian3dScene::<NdArray>::default()
+            .render(&VIEW, &Default::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn default_render_ndarray_autodiff() {
+        Gaussian3dScene::<Autodiff<NdArray>>::default()
+            .render(&VIEW, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .backward();
+    }
+}
@@ -11,8 +11,22 @@ pub struct RenderInput<B: Backend> {
     ///
     /// It is `P`.
     pub point_count: u64,
+    /// Label count.
+    ///
+    /// It is `L`, which is greater than the largest label.
+    pub label_count: u32,
     /// The shape is `[P, M * 3]`.
     pub colors_sh: B::FloatTensorPrimitive,
+    /// The shape is `[P, D]`.
+    ///
+    /// They are rendered alongside the colors if specified.
+    pub features: Option<B::FloatTensorPrimitive>,
+    /// The shape is `[P, 1]`.
+    pub filters_3d: B::FloatTensorPrimitive,
+    /// The shape is `[P]`.
+    ///
+    /// They are rendered into the label maps if specified.
+    pub labels: Option<B::IntTensorPrimitive>,
     /// The shape is `[P, 1]`.
     pub opacities: B::FloatTensorPrimitive,
     /// The shape is `[P, 3]`.
@@ -28,6 +42,20 @@ pub struct RenderInput<B: Backend> {
 pub struct RenderOutput<B: Backend> {
     /// The shape is `[I_y, I_x, 3]`
     pub colors_rgb_2d: B::FloatTensorPrimitive,
+    /// The shape is `[I_y, I_x, K]`
+    pub contributor_indices_2d: Option<B::IntTensorPrimitive>,
+    /// The shape is `[I_y, I_x, K]`
+    pub contributor_weights_2d: Option<B::FloatTensorPrimitive>,
+    /// The shape is `[I_y, I_x]`
+    pub depths_2d: B::FloatTensorPrimitive,
+    /// The shape is `[I_y, I_x]`
+    pub depths_2d_median: B::FloatTensorPrimitive,
+    /// The shape is `[I_y, I_x, D]`
+    pub features_2d: Option<B::FloatTensorPrimitive>,
+    /// The shape is `[I_y, I_x, L]`
+    pub label_coverages_2d: Option<B::FloatTensorPrimitive>,
+    /// The shape is `[I_y, I_x]`
+    pub labels_2d: Option<B::IntTensorPrimitive>,
     /// Rendering inputs (backward).
     pub state: backward::RenderInput<B>,
 }
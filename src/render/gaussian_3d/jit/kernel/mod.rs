@@ -3,6 +3,9 @@
 pub mod rank;
 pub mod rasterize;
 pub mod rasterize_backward;
+pub mod rasterize_features;
+pub mod rasterize_features_backward;
+pub mod rasterize_labels;
 pub mod scan;
 pub mod segment;
 pub mod sort;
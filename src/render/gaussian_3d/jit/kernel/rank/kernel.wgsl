@@ -1,6 +1,12 @@
 struct Arguments {
+    // D_far
+    depth_far: f32,
+    // D_near
+    depth_near: f32,
     // P
     point_count: u32,
+    // (1: 32-bit, 2: 64-bit)
+    point_order_word_count: u32,
     // I_x / T_x
     tile_count_x: u32,
 }
@@ -23,12 +29,12 @@ var<storage, read_write> tile_touched_offsets: array<u32>;
 // [T]
 @group(0) @binding(5)
 var<storage, read_write> point_indices: array<u32>;
-// [T]
+// [T, point_order_word_count] (Low word first)
 @group(0) @binding(6)
 var<storage, read_write> point_orders: array<u32>;
 
-// The difference in bits between depth to depth order (before shifting).
-const FACTOR_DEPTH_ORDER: u32 = (3u << 23) + 0xc0000000;
+// The maximum of depth order (16 bits)
+const DEPTH_ORDER_MAX: u32 = (1u << 16) - 1;
 const GROUP_SIZE: u32 = 256;
 
 @compute @workgroup_size(GROUP_SIZE, 1, 1)
@@ -60,7 +66,13 @@ fn main(
         for (var tile_x = point_tile_bound[1]; tile_x < point_tile_bound[0]; tile_x++) {
             let tile_index = tile_y * arguments.tile_count_x + tile_x;
             let depth = depths[global_index];
-            point_orders[offset] = make_point_order(tile_index, depth);
+            if arguments.point_order_word_count == 2u {
+                let point_order = make_point_order_64(tile_index, depth);
+                point_orders[offset * 2u] = point_order[0];
+                point_orders[offset * 2u + 1u] = point_order[1];
+            } else {
+                point_orders[offset] = make_point_order(tile_index, depth);
+            }
             point_indices[offset] = global_index;
             offset++;
         }
@@ -87,28 +99,48 @@ fn main(
 // | Shifting   | << 16 |                                              |
 // | Tile order |       | = 0b rrrr_rrrr_rrrr_rrrr_0000_0000_0000_0000 |
 // 
-// * Depth order range:
+// * Depth order:
+// 
+// The bits of a positive floating-point number increase monotonically
+// and are nearly proportional to its logarithm,
+// so the depth order is normalized from the bits of depth within [D_near, D_far).
+// 
+// | Depth bits  |                               | B(D) = bitcast<u32>(D) |
+// | Normalizing | (B(D) - B(D_near)) /          |                        |
+// |             | (B(D_far) - B(D_near))        | in [0, 1)              |
+// | Scaling     | * 2^16                        |                        |
+// | Depth order |                               | in [0, 2^16)           |
+// 
+fn make_point_order(tile_index: u32, depth: f32) -> u32 {
+    let depth_bits_near = bitcast<u32>(arguments.depth_near);
+    let depth_bits_range = bitcast<u32>(arguments.depth_far) - depth_bits_near;
+    let depth_order = u32(
+        f32(bitcast<u32>(depth) - depth_bits_near) /
+        f32(depth_bits_range) *
+        f32(DEPTH_ORDER_MAX + 1)
+    );
+    return (tile_index << 16) | min(depth_order, DEPTH_ORDER_MAX);
+}
+
+// Computing the point order (64-bit).
+// 
+// ## Arguments
+// 
+// | Tile index | 32 bits | Unsigned integer      |
+// | Depth      | 32 bits | Floating-point number |
 // 
-// | Depth order min | 2^1  | 2^(-127 + 2^(4) * (8))     |
-// | Depth order max | 2^17 | 2^(-127 + 2^(4) * (8 + 1)) |
+// ## Returns
 // 
-// * Depth range:
+// | Depth order | 32 bits | Low order word  |
+// | Tile order  | 32 bits | High order word |
 // 
-// | Depth order min |   2^1  |   0b 0_1000_0000_000_0000_0000_0000_0000_0000 |
-// | Depth order max |   2^17 |   0b 0_1001_0000_000_0000_0000_0000_0000_0000 |
-// | Multiplying     | * 2^-3 | - 0b 0_0000_0011_000_0000_0000_0000_0000_0000 |
-// | Depth min       |   2^-2 | = 0b 0_0111_1101_000_0000_0000_0000_0000_0000 |
-// | Depth max       |   2^14 | = 0b 0_1000_1101_000_0000_0000_0000_0000_0000 |
+// ## Details
 // 
 // * Depth order:
 // 
-// | Depth       |       |   0b 0_xxxx_xxxx_nnn_nnnn_nnnn_nnnn_nnnn_nnnn |
-// | Multiplying | * 2^3 | + 0b 0_0000_0011_000_0000_0000_0000_0000_0000 |
-// | Depth order |       | = 0b 0_1000_xxxx_nnn_nnnn_nnnn_nnnn_nnnn_nnnn |
-// | Unsetting   |       | + 0b 1_1000_0000_000_0000_0000_0000_0000_0000 |
-// | Shifting    | >> 11 |                                               |
-// | Depth order |       | = 0b 0_0000_0000_000_0000_xxxx_nnnn_nnnn_nnnn |
+// The bits of a positive floating-point number increase monotonically,
+// so the depth order keeps the full precision of depth.
 // 
-fn make_point_order(tile_index: u32, depth: f32) -> u32 {
-    return (tile_index << 16) | (bitcast<u32>(depth) + FACTOR_DEPTH_ORDER >> 11);
+fn make_point_order_64(tile_index: u32, depth: f32) -> vec2<u32> {
+    return vec2<u32>(bitcast<u32>(depth), tile_index);
 }
@@ -9,8 +9,14 @@ use bytemuck::{bytes_of, from_bytes, Pod, Zeroable};
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Arguments {
+    /// `D_far`
+    pub depth_far: f32,
+    /// `D_near`
+    pub depth_near: f32,
     /// `P`
     pub point_count: u32,
+    /// `(1: 32-bit, 2: 64-bit)`
+    pub point_order_word_count: u32,
     /// `I_x / T_x`
     pub tile_count_x: u32,
 }
@@ -35,14 +41,16 @@ pub struct Inputs<R: JitRuntime> {
 pub struct Outputs<R: JitRuntime> {
     /// `[T]`
     pub point_indices: JitTensor<R>,
-    /// `[T]`
+    /// `[T]` for 32-bit or `[T, 2]` for 64-bit
     pub point_orders: JitTensor<R>,
 }
 
 /// Group size.
 pub const GROUP_SIZE: u32 = 256;
 /// Maximum of `(I_y / T_y) * (I_x / T_x)`
-pub const TILE_COUNT_MAX: u32 = 1 << 16;
+pub const TILE_COUNT_MAX: u32 = 1 << 22;
+/// Maximum of `(I_y / T_y) * (I_x / T_x)` for 32-bit point orders
+pub const TILE_COUNT_MAX_32_BIT: u32 = 1 << 16;
 /// `E[T / P]`
 pub const FACTOR_TILE_POINT_COUNT: u32 = 65;
 
@@ -65,9 +73,14 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     // [T]
     let point_indices =
         JitBackend::<R, F, I, B>::int_empty([tile_point_count].into(), device);
-    // [T]
-    let point_orders =
-        JitBackend::<R, F, I, B>::int_empty([tile_point_count].into(), device);
+    // [T] or [T, 2]
+    let point_orders = JitBackend::<R, F, I, B>::int_empty(
+        match arguments.point_order_word_count {
+            2 => [tile_point_count, 2].into(),
+            _ => [tile_point_count].into(),
+        },
+        device,
+    );
 
     // Launching the kernel
 
@@ -97,3 +110,23 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         point_orders,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn options_render_ndarray_and_wgpu() {
+        let scene = scene_random::<NdArray>(48, &Default::default());
+        let options = [
+            Gaussian3dRenderOptions::default().with_sort_key_bit_count(64),
+            Gaussian3dRenderOptions::default()
+                .with_contributor_count(4)
+                .with_region(Some([8, 5, 40, 30])),
+        ];
+        for options in options {
+            assert_render_ndarray_and_wgpu(&scene, &VIEW_RANDOM, &options);
+        }
+    }
+}
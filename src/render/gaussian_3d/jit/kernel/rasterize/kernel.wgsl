@@ -1,8 +1,22 @@
 struct Arguments {
+    // C_rgb_bg
+    background_rgb: array<f32, 3>,
+    // (0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)
+    camera_model: u32,
+    // K (0 ~ K_max)
+    contributor_count: u32,
     // I_x
     image_size_x: u32,
     // I_y
     image_size_y: u32,
+    // I_x0 (0 ~ I_x)
+    region_offset_x: u32,
+    // I_y0 (0 ~ I_y)
+    region_offset_y: u32,
+    // I_x' (1 ~ I_x - I_x0)
+    region_size_x: u32,
+    // I_y' (1 ~ I_y - I_y0)
+    region_size_y: u32,
 }
 
 @group(0) @binding(0)
@@ -13,45 +27,69 @@ var<storage, read_write> colors_rgb_3d: array<array<f32, 3>>;
 // [P, 3] (Symmetric mat2x2)
 @group(0) @binding(2)
 var<storage, read_write> conics: array<array<f32, 3>>;
-// [P, 1] (Inner)
+// [P]
 @group(0) @binding(3)
+var<storage, read_write> depths: array<f32>;
+// [P] (Outer)
+@group(0) @binding(4)
 var<storage, read_write> opacities_3d: array<f32>;
 // [T] (0 ~ P)
-@group(0) @binding(4)
+@group(0) @binding(5)
 var<storage, read_write> point_indices: array<u32>;
 // [P, 2]
-@group(0) @binding(5)
-var<storage, read_write> positions_2d: array<vec2<f32>>;
-// [I_y / T_y, I_x / T_x, 2]
 @group(0) @binding(6)
+var<storage, read_write> positions_2d: array<vec2<f32>>;
+// [I_y' / T_y, I_x' / T_x, 2]
+@group(0) @binding(7)
 var<storage, read_write> tile_point_ranges: array<vec2<u32>>;
 
-// [I_y, I_x, 3] (0.0 ~ 1.0)
-@group(0) @binding(7)
-var<storage, read_write> colors_rgb_2d: array<array<f32, 3>>;
-// [I_y, I_x]
+// [I_y', I_x', 3] (0.0 ~ 1.0)
 @group(0) @binding(8)
-var<storage, read_write> point_rendered_counts: array<u32>;
-// [I_y, I_x] (0.0 ~ 1.0)
+var<storage, read_write> colors_rgb_2d: array<array<f32, 3>>;
+// [I_y', I_x', K] (-1 ~ P)
 @group(0) @binding(9)
+var<storage, read_write> contributor_indices_2d: array<i32>;
+// [I_y', I_x', K] (0.0 ~ 1.0)
+@group(0) @binding(10)
+var<storage, read_write> contributor_weights_2d: array<f32>;
+// [I_y', I_x']
+@group(0) @binding(11)
+var<storage, read_write> depths_2d: array<f32>;
+// [I_y', I_x']
+@group(0) @binding(12)
+var<storage, read_write> depths_2d_median: array<f32>;
+// [I_y', I_x']
+@group(0) @binding(13)
+var<storage, read_write> point_rendered_counts: array<u32>;
+// [I_y', I_x'] (0.0 ~ 1.0)
+@group(0) @binding(14)
 var<storage, read_write> transmittances: array<f32>;
 
 // [T_x * T_y, 3]
 var<workgroup> colors_rgb_3d_in_batch: array<vec3<f32>, BATCH_SIZE>;
 // [T_x * T_y, 2, 2]
 var<workgroup> conics_in_batch: array<mat2x2<f32>, BATCH_SIZE>;
+// [T_x * T_y]
+var<workgroup> depths_in_batch: array<f32, BATCH_SIZE>;
 // [T_x * T_y, 1]
 var<workgroup> opacities_3d_in_batch: array<f32, BATCH_SIZE>;
+// [T_x * T_y] (0 ~ P)
+var<workgroup> point_indices_in_batch: array<u32, BATCH_SIZE>;
 // [T_x * T_y, 2]
 var<workgroup> positions_2d_in_batch: array<vec2<f32>, BATCH_SIZE>;
 // (0 ~ T_x * T_y)
 var<workgroup> pixel_done_count: atomic<u32>;
 
+const CAMERA_MODEL_EQUIRECTANGULAR: u32 = 4;
+const CONTRIBUTOR_INDEX_NONE: i32 = -1;
 const OPACITY_2D_MAX: f32 = 252.0 / 255.0;
 const OPACITY_2D_MIN: f32 = 1.0 / 255.0;
+const TRANSMITTANCE_MEDIAN: f32 = 0.5;
 const TRANSMITTANCE_MIN: f32 = pow(1.0 - OPACITY_2D_MAX, 2.0);
 // T_x * T_y
 const BATCH_SIZE: u32 = TILE_SIZE_X * TILE_SIZE_Y;
+// K_max
+const CONTRIBUTOR_COUNT_MAX: u32 = 16;
 // T_x
 const TILE_SIZE_X: u32 = 16;
 // T_y
@@ -62,24 +100,36 @@ fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>,
     // (0 ~ T_x * T_y)
     @builtin(local_invocation_index) local_index: u32,
-    // (0 ~ I_x / T_x, 0 ~ I_y / T_y)
+    // (0 ~ I_x' / T_x + 1, 0 ~ I_y' / T_y + 1)
     @builtin(workgroup_id) tile_id: vec3<u32>,
-    // (I_x / T_x, I_y / T_y)
+    // (I_x' / T_x + 1, I_y' / T_y + 1)
     @builtin(num_workgroups) tile_count: vec3<u32>,
 ) {
     // Specifying the index
 
     // (0 ~ I_x, 0 ~ I_y)
-    let pixel = global_id.xy;
-    // (0 ~ I_y * I_x)
-    let pixel_index = pixel.y * arguments.image_size_x + pixel.x;
-    // (0 ~ (I_y / T_y) * (I_x / T_x))
+    //
+    // The tiles are aligned to the image rather than the region.
+    let pixel = global_id.xy + vec2<u32>(
+        arguments.region_offset_x / TILE_SIZE_X * TILE_SIZE_X,
+        arguments.region_offset_y / TILE_SIZE_Y * TILE_SIZE_Y,
+    );
+    // (0 ~ I_x', 0 ~ I_y')
+    //
+    // It wraps around for the pixels before the region, which are invalid.
+    let pixel_in_region = pixel - vec2<u32>(arguments.region_offset_x, arguments.region_offset_y);
+    // (0 ~ I_y' * I_x')
+    let pixel_index = pixel_in_region.y * arguments.region_size_x + pixel_in_region.x;
+    // (0 ~ (I_y' / T_y) * (I_x' / T_x))
     let tile_index = tile_id.y * tile_count.x + tile_id.x;
 
     // Specifying the parameters
 
-    let is_pixel_valid = pixel.x < arguments.image_size_x && pixel.y < arguments.image_size_y;
+    let is_pixel_valid =
+        pixel_in_region.x < arguments.region_size_x &&
+        pixel_in_region.y < arguments.region_size_y;
     let position_pixel = vec2<f32>(pixel);
+    let image_size_x = f32(arguments.image_size_x);
     var point_range = vec2<u32>();
     // R
     var tile_point_count = 0u;
@@ -92,6 +142,17 @@ fn main(
     // R / (T_x * T_y)
     let batch_count = (tile_point_count + BATCH_SIZE - 1) / BATCH_SIZE;
     var color_rgb_2d = vec3<f32>();
+    // K
+    let contributor_count = arguments.contributor_count;
+    // [K] (Sorted by the weights in descending order)
+    var contributor_indices_state: array<i32, CONTRIBUTOR_COUNT_MAX>;
+    // [K]
+    var contributor_weights_state: array<f32, CONTRIBUTOR_COUNT_MAX>;
+    for (var rank = 0u; rank < contributor_count; rank++) {
+        contributor_indices_state[rank] = CONTRIBUTOR_INDEX_NONE;
+    }
+    var depth_2d = 0.0;
+    var depth_2d_median = 0.0;
     var is_pixel_done = !is_pixel_valid;
     if local_index == 0 {
         pixel_done_count = 0u;
@@ -126,8 +187,10 @@ fn main(
             let point_index = point_indices[index];
             colors_rgb_3d_in_batch[local_index] = vec_from_array_f32_3(colors_rgb_3d[point_index]);
             conics_in_batch[local_index] = mat_sym_from_array_f32_3(conics[point_index]);
+            depths_in_batch[local_index] = depths[point_index];
             // (Outer)
-            opacities_3d_in_batch[local_index] = sigmoid_f32(opacities_3d[point_index]);
+            opacities_3d_in_batch[local_index] = opacities_3d[point_index];
+            point_indices_in_batch[local_index] = point_index;
             positions_2d_in_batch[local_index] = positions_2d[point_index];
         }
         workgroupBarrier();
@@ -148,10 +211,15 @@ fn main(
             // Computing the density of the point in the pixel
             // D[2, 1] = Pv'[2, 1] - Px[2, 1]
             // σ[n] = e^(-0.5 * D^t[1, 2] * Σ'^-1[2, 2] * D[2, 1])[n]
+            //
+            // D.x is wrapped around the seam for the equirectangular camera model
 
             let conic = conics_in_batch[batch_pixel_index];
             let position_2d = positions_2d_in_batch[batch_pixel_index];
-            let position_offset = position_2d - position_pixel;
+            var position_offset = position_2d - position_pixel;
+            if arguments.camera_model == CAMERA_MODEL_EQUIRECTANGULAR {
+                position_offset.x -= image_size_x * round(position_offset.x / image_size_x);
+            }
             let density = exp(-0.5 * dot(position_offset * conic, position_offset));
 
             // Skipping if the density is greater than one
@@ -190,6 +258,36 @@ fn main(
             let color_rgb_3d = colors_rgb_3d_in_batch[batch_pixel_index];
             color_rgb_2d += color_rgb_3d * opacity_2d * transmittance_state;
 
+            // Blending the depths of the pixel into the 2D depth
+            // D'[n + 1] = D'[n] + D[n] * α'[n] * t[n]
+
+            let depth = depths_in_batch[batch_pixel_index];
+            depth_2d += depth * opacity_2d * transmittance_state;
+
+            // Finding the median depth where the transmittance crosses the half
+            // t[n] >= 0.5 > t[n + 1]
+
+            if transmittance_state >= TRANSMITTANCE_MEDIAN && transmittance < TRANSMITTANCE_MEDIAN {
+                depth_2d_median = depth;
+            }
+
+            // Recording the point if it is one of the top contributors of the pixel
+            // w[n] = α'[n] * t[n]
+            //
+            // The lighter contributors are shifted down by insertion.
+
+            let weight = opacity_2d * transmittance_state;
+            if contributor_count != 0 && weight > contributor_weights_state[contributor_count - 1] {
+                var rank = contributor_count - 1;
+                while rank > 0 && contributor_weights_state[rank - 1] < weight {
+                    contributor_indices_state[rank] = contributor_indices_state[rank - 1];
+                    contributor_weights_state[rank] = contributor_weights_state[rank - 1];
+                    rank--;
+                }
+                contributor_indices_state[rank] = i32(point_indices_in_batch[batch_pixel_index]);
+                contributor_weights_state[rank] = weight;
+            }
+
             // Updating the states of the pixel
 
             point_rendered_count = point_rendered_state;
@@ -204,18 +302,36 @@ fn main(
     if is_pixel_valid {
         // Painting the pixel
 
-        // [I_y, I_x, 3]
+        // Blending the background color of the pixel into the 2D color in RGB space
+        // C_rgb' = C_rgb'[N] + C_rgb_bg * t[N]
+
+        color_rgb_2d += vec_from_array_f32_3(arguments.background_rgb) * transmittance_state;
+
+        // [I_y', I_x', 3]
         colors_rgb_2d[pixel_index] = array<f32, 3>(
             color_rgb_2d[0],
             color_rgb_2d[1],
             color_rgb_2d[2],
         );
 
+        // [I_y', I_x', K]
+        for (var rank = 0u; rank < contributor_count; rank++) {
+            contributor_indices_2d[pixel_index * contributor_count + rank] =
+                contributor_indices_state[rank];
+            contributor_weights_2d[pixel_index * contributor_count + rank] =
+                contributor_weights_state[rank];
+        }
+
+        // [I_y', I_x']
+        depths_2d[pixel_index] = depth_2d;
+        // [I_y', I_x']
+        depths_2d_median[pixel_index] = depth_2d_median;
+
         // Recording the states
 
-        // [I_y, I_x]
+        // [I_y', I_x']
         point_rendered_counts[pixel_index] = point_rendered_count;
-        // [I_y, I_x]
+        // [I_y', I_x']
         transmittances[pixel_index] = transmittance_state;
     }
 }
@@ -228,12 +344,6 @@ fn mat_sym_from_array_f32_3(a: array<f32, 3>) -> mat2x2<f32> {
     return mat2x2<f32>(a[0], a[1], a[1], a[2]);
 }
 
-// y = e^x / (1 + e^x)
-fn sigmoid_f32(x: f32) -> f32 {
-    let x_exp = exp(x);
-    return x_exp / (1.0 + x_exp);
-}
-
 fn vec_from_array_f32_3(a: array<f32, 3>) -> vec3<f32> {
     return vec3<f32>(a[0], a[1], a[2]);
 }
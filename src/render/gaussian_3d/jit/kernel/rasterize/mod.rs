@@ -9,18 +9,51 @@ use bytemuck::{bytes_of, Pod, Zeroable};
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Arguments {
+    /// $ C_{rgb,bg} \in \mathbb{R}^{3} $
+    ///
+    /// Background color in RGB space.
+    pub background_rgb: [f32; 3],
+    /// $ \text{camera} \in \{0, 1, 2, 3, 4\} $
+    ///
+    /// The identifier of the camera model.
+    pub camera_model: u32,
+    /// $ K \in [0, K_{max}] $
+    ///
+    /// The count of top contributors to record for each pixel.
+    /// They are not recorded if it is zero.
+    pub contributor_count: u32,
     /// $ \text{im}_x $
     pub image_size_x: u32,
     /// $ \text{im}_y $
     pub image_size_y: u32,
+    /// $ \text{im}_{x,0} $
+    ///
+    /// The offset of the rendering region.
+    pub region_offset_x: u32,
+    /// $ \text{im}_{y,0} $
+    ///
+    /// The offset of the rendering region.
+    pub region_offset_y: u32,
+    /// $ \text{im}_x^' $
+    ///
+    /// The width of the rendering region.
+    pub region_size_x: u32,
+    /// $ \text{im}_y^' $
+    ///
+    /// The height of the rendering region.
+    pub region_size_y: u32,
 
-    /// $ \frac{\text{im}_x}{\text{t}_x} $
+    /// $ \lceil \frac{\text{im}_{x,0} + \text{im}_x^'}{\text{t}_x} \rceil
+    /// - \lfloor \frac{\text{im}_{x,0}}{\text{t}_x} \rfloor $
     ///
-    /// $ \text{t}_x $ is the tile width.
+    /// It is the count of tiles intersecting the region,
+    /// and $ \text{t}_x $ is the tile width.
     pub tile_count_x: u32,
-    /// $ \frac{\text{im}_y}{\text{t}_y} $
+    /// $ \lceil \frac{\text{im}_{y,0} + \text{im}_y^'}{\text{t}_y} \rceil
+    /// - \lfloor \frac{\text{im}_{y,0}}{\text{t}_y} \rfloor $
     ///
-    /// $ \text{t}_y $ is the tile height.
+    /// It is the count of tiles intersecting the region,
+    /// and $ \text{t}_y $ is the tile height.
     pub tile_count_y: u32,
 }
 
@@ -35,7 +68,13 @@ pub struct Inputs<R: JitRuntime> {
     ///
     /// It can be $ \mathbb{R}^{3} $ since it is symmetric.
     pub conics: JitTensor<R>,
+    /// $ D \in \mathbb{R} $ of $ p $ points.
+    ///
+    /// Depth in view space.
+    pub depths: JitTensor<R>,
     /// $ \alpha \in \mathbb{R} $ of $ p $ points.
+    ///
+    /// Opacity compensated by the filters.
     pub opacities_3d: JitTensor<R>,
     /// $ i \in [0, p) $.
     ///
@@ -45,23 +84,49 @@ pub struct Inputs<R: JitRuntime> {
     ///
     /// 2D position in screen space.
     pub positions_2d: JitTensor<R>,
-    /// $ [i_{start}, i_{end}) $ of each tile.
+    /// $ [i_{start}, i_{end}) $ of each tile in the region.
     pub tile_point_ranges: JitTensor<R>,
 }
 
 /// Outputs.
 #[derive(Clone, Debug)]
 pub struct Outputs<R: JitRuntime> {
-    /// $ C_{rgb}^' \in \mathbb{R}^{3} $ of each image pixel.
+    /// $ C_{rgb}^' \in \mathbb{R}^{3} $ of each region pixel.
     pub colors_rgb_2d: JitTensor<R>,
-    /// Rendered point count of each image pixel.
+    /// $ i \in [0, p) $ of $ K $ top contributors of each region pixel.
+    ///
+    /// They are sorted by the weights in descending order,
+    /// and the absent ones are [`CONTRIBUTOR_INDEX_NONE`].
+    /// It is `None` if [`Arguments::contributor_count`] is zero.
+    pub contributor_indices_2d: Option<JitTensor<R>>,
+    /// $ \alpha_n^' T_n \in \mathbb{R} $ of $ K $ top contributors of each region pixel.
+    ///
+    /// The weights of the absent ones are zero.
+    /// It is `None` if [`Arguments::contributor_count`] is zero.
+    pub contributor_weights_2d: Option<JitTensor<R>>,
+    /// $ D^' \in \mathbb{R} $ of each region pixel.
+    ///
+    /// Alpha-blended depth in view space.
+    pub depths_2d: JitTensor<R>,
+    /// $ D_{med}^' \in \mathbb{R} $ of each region pixel.
+    ///
+    /// Depth of the point where the transmittance falls below one half.
+    /// It is zero if the transmittance never does.
+    pub depths_2d_median: JitTensor<R>,
+    /// Rendered point count of each region pixel.
     pub point_rendered_counts: JitTensor<R>,
     /// $ T_{last} $
     ///
-    /// Last transmittance of each image pixel.
+    /// Last transmittance of each region pixel.
     pub transmittances: JitTensor<R>,
 }
 
+/// $ K_{max} $
+///
+/// The maximum count of top contributors recorded for each pixel.
+pub const CONTRIBUTOR_COUNT_MAX: u32 = 16;
+/// The index of the absent contributors.
+pub const CONTRIBUTOR_INDEX_NONE: i32 = -1;
 /// $ \text{t}_x $
 pub const TILE_SIZE_X: u32 = 16;
 /// $ \text{t}_y $
@@ -87,6 +152,18 @@ pub const TILE_SIZE_Y: u32 = 16;
 /// $$ \alpha_n^' \leftarrow \alpha_n \sigma_n $$
 /// $$ T_{n + 1} \leftarrow T_n (1 - \alpha_n^') $$
 /// $$ C_{rgb}^' \leftarrow C_{rgb,n}^' + (C_{rgb} \cdot \alpha_n^' \cdot T_n) $$
+///
+/// 4. Composite [$ C_{rgb,bg} $](Arguments::background_rgb) of each pixel
+///    using the last transmittance $ T_{last} $:
+/// $$ C_{rgb}^' \leftarrow C_{rgb}^' + (C_{rgb,bg} \cdot T_{last}) $$
+///
+/// 5. Accumulate [$ D^' $](Outputs::depths_2d) of each pixel
+///    using [$ D_n $](Inputs::depths) of each point $ n $ in the same way:
+/// $$ D^' \leftarrow D_n^' + (D_n \cdot \alpha_n^' \cdot T_n) $$
+///
+/// 6. Record the indices of $ K $ points with the highest weights
+///    $ \alpha_n^' \cdot T_n $ of each pixel
+///    if [$ K $](Arguments::contributor_count) is positive.
 pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     arguments: Arguments,
     inputs: Inputs<R>,
@@ -97,22 +174,50 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     let client = &inputs.colors_rgb_3d.client;
     let device = &inputs.colors_rgb_3d.device;
-    // I_x
-    let image_size_x = arguments.image_size_x as usize;
-    // I_y
-    let image_size_y = arguments.image_size_y as usize;
+    // K
+    let contributor_count = arguments.contributor_count as usize;
+    // I_x'
+    let region_size_x = arguments.region_size_x as usize;
+    // I_y'
+    let region_size_y = arguments.region_size_y as usize;
+    // [I_y', I_x', K] or [1] if K is zero
+    //
+    // The buffers are never empty.
+    let contributor_shape = if contributor_count == 0 {
+        vec![1]
+    } else {
+        vec![region_size_y, region_size_x, contributor_count]
+    };
 
-    // [I_x, I_y, 3]
+    // [I_y', I_x', 3]
     let colors_rgb_2d = JitBackend::<R, F, I, B>::float_empty(
-        [image_size_y, image_size_x, 3].into(),
+        [region_size_y, region_size_x, 3].into(),
+        device,
+    );
+    // [I_y', I_x', K]
+    let contributor_indices_2d =
+        JitBackend::<R, F, I, B>::int_empty(contributor_shape.to_owned().into(), device);
+    // [I_y', I_x', K]
+    let contributor_weights_2d =
+        JitBackend::<R, F, I, B>::float_zeros(contributor_shape.into(), device);
+    // [I_y', I_x']
+    let depths_2d = JitBackend::<R, F, I, B>::float_empty(
+        [region_size_y, region_size_x].into(),
+        device,
+    );
+    // [I_y', I_x']
+    let depths_2d_median = JitBackend::<R, F, I, B>::float_empty(
+        [region_size_y, region_size_x].into(),
         device,
     );
-    // [I_x, I_y]
-    let point_rendered_counts =
-        JitBackend::<R, F, I, B>::int_empty([image_size_y, image_size_x].into(), device);
-    // [I_x, I_y]
+    // [I_y', I_x']
+    let point_rendered_counts = JitBackend::<R, F, I, B>::int_empty(
+        [region_size_y, region_size_x].into(),
+        device,
+    );
+    // [I_y', I_x']
     let transmittances = JitBackend::<R, F, I, B>::float_empty(
-        [image_size_y, image_size_x].into(),
+        [region_size_y, region_size_x].into(),
         device,
     );
 
@@ -132,11 +237,16 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             client.create(bytes_of(&arguments)).binding(),
             inputs.colors_rgb_3d.handle.binding(),
             inputs.conics.handle.binding(),
+            inputs.depths.handle.binding(),
             inputs.opacities_3d.handle.binding(),
             inputs.point_indices.handle.binding(),
             inputs.positions_2d.handle.binding(),
             inputs.tile_point_ranges.handle.binding(),
             colors_rgb_2d.handle.to_owned().binding(),
+            contributor_indices_2d.handle.to_owned().binding(),
+            contributor_weights_2d.handle.to_owned().binding(),
+            depths_2d.handle.to_owned().binding(),
+            depths_2d_median.handle.to_owned().binding(),
             point_rendered_counts.handle.to_owned().binding(),
             transmittances.handle.to_owned().binding(),
         ],
@@ -144,7 +254,31 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     Outputs {
         colors_rgb_2d,
+        contributor_indices_2d: (contributor_count != 0)
+            .then_some(contributor_indices_2d),
+        contributor_weights_2d: (contributor_count != 0)
+            .then_some(contributor_weights_2d),
+        depths_2d,
+        depths_2d_median,
         point_rendered_counts,
         transmittances,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn options_render_ndarray_and_wgpu() {
+        let scene = scene_random::<NdArray>(48, &Default::default());
+        let options = [
+            Gaussian3dRenderOptions::default().with_background_rgb([0.2, 0.4, 0.6]),
+            Gaussian3dRenderOptions::default().with_contributor_count(4),
+        ];
+        for options in options {
+            assert_render_ndarray_and_wgpu(&scene, &VIEW_RANDOM, &options);
+        }
+    }
+}
@@ -1,57 +1,88 @@
 struct Arguments {
+    // C_rgb_bg
+    background_rgb: array<f32, 3>,
+    // (0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)
+    camera_model: u32,
     // I_x
     image_size_x: u32,
     // I_y
     image_size_y: u32,
+    // (0: Disabled, 1: Enabled)
+    positions_2d_grad_abs: u32,
+    // I_x0 (0 ~ I_x)
+    region_offset_x: u32,
+    // I_y0 (0 ~ I_y)
+    region_offset_y: u32,
+    // I_x' (1 ~ I_x - I_x0)
+    region_size_x: u32,
+    // I_y' (1 ~ I_y - I_y0)
+    region_size_y: u32,
 }
 
 @group(0) @binding(0)
 var<storage, read_write> arguments: Arguments;
-// [I_y, I_x, 3]
+// [I_y', I_x']
 @group(0) @binding(1)
+var<storage, read_write> alphas_2d_grad: array<f32>;
+// [I_y', I_x', 3]
+@group(0) @binding(2)
 var<storage, read_write> colors_rgb_2d_grad: array<array<f32, 3>>;
 // [P, 3] (0.0 ~ 1.0)
-@group(0) @binding(2)
+@group(0) @binding(3)
 var<storage, read_write> colors_rgb_3d: array<array<f32, 3>>;
 // [P, 3] (Symmetric mat2x2)
-@group(0) @binding(3)
-var<storage, read_write> conics: array<array<f32, 3>>;
-// [P, 1] (Inner)
 @group(0) @binding(4)
+var<storage, read_write> conics: array<array<f32, 3>>;
+// [P]
+@group(0) @binding(5)
+var<storage, read_write> depths: array<f32>;
+// [I_y', I_x']
+@group(0) @binding(6)
+var<storage, read_write> depths_2d_grad: array<f32>;
+// [P] (Outer)
+@group(0) @binding(7)
 var<storage, read_write> opacities_3d: array<f32>;
 // [T] (0 ~ P)
-@group(0) @binding(5)
+@group(0) @binding(8)
 var<storage, read_write> point_indices: array<u32>;
-// [I_y, I_x]
-@group(0) @binding(6)
+// [I_y', I_x']
+@group(0) @binding(9)
 var<storage, read_write> point_rendered_counts: array<u32>;
 // [P, 2]
-@group(0) @binding(7)
+@group(0) @binding(10)
 var<storage, read_write> positions_2d: array<vec2<f32>>;
-// [I_y / T_y, I_x / T_x, 2]
-@group(0) @binding(8)
+// [I_y' / T_y, I_x' / T_x, 2]
+@group(0) @binding(11)
 var<storage, read_write> tile_point_ranges: array<vec2<u32>>;
-// [I_y, I_x] (0.0 ~ 1.0)
-@group(0) @binding(9)
+// [I_y', I_x'] (0.0 ~ 1.0)
+@group(0) @binding(12)
 var<storage, read_write> transmittances: array<f32>;
 
 // [P, 3]
-@group(0) @binding(10)
+@group(0) @binding(13)
 var<storage, read_write> colors_rgb_3d_grad: array<atomic<f32>>;
 // [P, 3] (Symmetric mat2x2)
-@group(0) @binding(11)
+@group(0) @binding(14)
 var<storage, read_write> conics_grad: array<atomic<f32>>;
-// [P, 1] (Inner)
-@group(0) @binding(12)
+// [P]
+@group(0) @binding(15)
+var<storage, read_write> depths_grad: array<atomic<f32>>;
+// [P] (Outer)
+@group(0) @binding(16)
 var<storage, read_write> opacities_3d_grad: array<atomic<f32>>;
 // [P, 2]
-@group(0) @binding(13)
+@group(0) @binding(17)
 var<storage, read_write> positions_2d_grad: array<atomic<f32>>;
+// [P, 2]
+@group(0) @binding(18)
+var<storage, read_write> positions_2d_grad_abs: array<atomic<f32>>;
 
 // [T_x * T_y, 3]
 var<workgroup> colors_rgb_3d_in_batch: array<vec3<f32>, BATCH_SIZE>;
 // [T_x * T_y, 2, 2]
 var<workgroup> conics_in_batch: array<mat2x2<f32>, BATCH_SIZE>;
+// [T_x * T_y]
+var<workgroup> depths_in_batch: array<f32, BATCH_SIZE>;
 // [T_x * T_y, 1]
 var<workgroup> opacities_3d_in_batch: array<f32, BATCH_SIZE>;
 // [T_x * T_y]
@@ -59,6 +90,7 @@ var<workgroup> point_indices_in_batch: array<u32, BATCH_SIZE>;
 // [T_x * T_y, 2]
 var<workgroup> positions_2d_in_batch: array<vec2<f32>, BATCH_SIZE>;
 
+const CAMERA_MODEL_EQUIRECTANGULAR: u32 = 4;
 const OPACITY_2D_MAX: f32 = 252.0 / 255.0;
 const OPACITY_2D_MIN: f32 = 1.0 / 255.0;
 // T_x * T_y
@@ -73,24 +105,36 @@ fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>,
     // (0 ~ T_x * T_y)
     @builtin(local_invocation_index) local_index: u32,
-    // (0 ~ I_x / T_x, 0 ~ I_y / T_y)
+    // (0 ~ I_x' / T_x + 1, 0 ~ I_y' / T_y + 1)
     @builtin(workgroup_id) tile_id: vec3<u32>,
-    // (I_x / T_x, I_y / T_y)
+    // (I_x' / T_x + 1, I_y' / T_y + 1)
     @builtin(num_workgroups) tile_count: vec3<u32>,
 ) {
     // Specifying the index
 
     // (0 ~ I_x, 0 ~ I_y)
-    let pixel = global_id.xy;
-    // (0 ~ I_y * I_x)
-    let pixel_index = pixel.y * arguments.image_size_x + pixel.x;
-    // (0 ~ (I_y / T_y) * (I_x / T_x))
+    //
+    // The tiles are aligned to the image rather than the region.
+    let pixel = global_id.xy + vec2<u32>(
+        arguments.region_offset_x / TILE_SIZE_X * TILE_SIZE_X,
+        arguments.region_offset_y / TILE_SIZE_Y * TILE_SIZE_Y,
+    );
+    // (0 ~ I_x', 0 ~ I_y')
+    //
+    // It wraps around for the pixels before the region, which are invalid.
+    let pixel_in_region = pixel - vec2<u32>(arguments.region_offset_x, arguments.region_offset_y);
+    // (0 ~ I_y' * I_x')
+    let pixel_index = pixel_in_region.y * arguments.region_size_x + pixel_in_region.x;
+    // (0 ~ (I_y' / T_y) * (I_x' / T_x))
     let tile_index = tile_id.y * tile_count.x + tile_id.x;
 
     // Specifying the parameters
 
-    let is_pixel_valid = pixel.x < arguments.image_size_x && pixel.y < arguments.image_size_y;
+    let is_pixel_valid =
+        pixel_in_region.x < arguments.region_size_x &&
+        pixel_in_region.y < arguments.region_size_y;
     let position_pixel = vec2<f32>(pixel);
+    let image_size_x = f32(arguments.image_size_x);
     var point_range = vec2<u32>();
     // R
     var tile_point_count = 0u;
@@ -107,15 +151,22 @@ fn main(
         colors_rgb_2d_grad[pixel_index][1],
         colors_rgb_2d_grad[pixel_index][2],
     );
-    var color_rgb_2d_state = vec3<f32>();
+    let alpha_2d_grad = alphas_2d_grad[pixel_index];
+    let depth_2d_grad = depths_2d_grad[pixel_index];
+    // C_rgb'[N] = C_rgb_bg
+    var color_rgb_2d_state = vec_from_array_f32_3(arguments.background_rgb);
     var color_rgb_3d_state = vec3<f32>();
+    var depth_2d_state = 0.0;
+    var depth_3d_state = 0.0;
     var opacity_2d_state = 0.0;
     var point_rendered_count = u32();
     var point_rendered_state = tile_point_count;
+    var transmittance_last = f32();
     var transmittance_state = f32();
     if is_pixel_valid {
         point_rendered_count = point_rendered_counts[pixel_index];
-        transmittance_state = transmittances[pixel_index];
+        transmittance_last = transmittances[pixel_index];
+        transmittance_state = transmittance_last;
     }
 
     // Processing batches of points of the tile
@@ -130,8 +181,9 @@ fn main(
             let point_index = point_indices[index];
             colors_rgb_3d_in_batch[local_index] = vec_from_array_f32_3(colors_rgb_3d[point_index]);
             conics_in_batch[local_index] = mat_sym_from_array_f32_3(conics[point_index]);
+            depths_in_batch[local_index] = depths[point_index];
             // (Outer)
-            opacities_3d_in_batch[local_index] = sigmoid_f32(opacities_3d[point_index]);
+            opacities_3d_in_batch[local_index] = opacities_3d[point_index];
             point_indices_in_batch[local_index] = point_index;
             positions_2d_in_batch[local_index] = positions_2d[point_index];
         }
@@ -157,10 +209,15 @@ fn main(
 
             // Computing the density of the point in the pixel
             // σ[n] = e^(-0.5 * D^t[1, 2] * Σ'^-1[2, 2] * D[2, 1])[n]
+            //
+            // D.x is wrapped around the seam for the equirectangular camera model
 
             let conic = conics_in_batch[batch_index];
             let position_2d = positions_2d_in_batch[batch_index];
-            let position_offset = position_2d - position_pixel;
+            var position_offset = position_2d - position_pixel;
+            if arguments.camera_model == CAMERA_MODEL_EQUIRECTANGULAR {
+                position_offset.x -= image_size_x * round(position_offset.x / image_size_x);
+            }
             let density = exp(-0.5 * dot(position_offset * conic, position_offset));
 
             // Skipping if the density is greater than one
@@ -185,12 +242,18 @@ fn main(
             // C_rgb'[n] = C_rgb[n + 1] * α'[n + 1] +
             //             C_rgb'[n + 1] * (1 - α'[n + 1])
             // C_rgb[n]  = C_rgb[n]
+            // D'[n]     = D[n + 1] * α'[n + 1] +
+            //             D'[n + 1] * (1 - α'[n + 1])
+            // D[n]      = D[n]
             // α'[n]     = α[n] * σ[n]
             // t[n]      = t[n + 1] / (1 - α'[n])
 
             color_rgb_2d_state = color_rgb_3d_state * opacity_2d_state
                                + color_rgb_2d_state * (1.0 - opacity_2d_state);
             color_rgb_3d_state = colors_rgb_3d_in_batch[batch_index];
+            depth_2d_state = depth_3d_state * opacity_2d_state
+                           + depth_2d_state * (1.0 - opacity_2d_state);
+            depth_3d_state = depths_in_batch[batch_index];
             opacity_2d_state = opacity_2d;
             transmittance_state /= 1.0 - opacity_2d;
 
@@ -214,19 +277,36 @@ fn main(
 
             let color_rgb_3d_grad = opacity_2d * transmittance_state * color_rgb_2d_grad;
             let opacity_2d_grad_terms = (color_rgb_3d_state - color_rgb_2d_state) * color_rgb_2d_grad;
-            let opacity_2d_grad = transmittance_state * (
+            var opacity_2d_grad = transmittance_state * (
                 opacity_2d_grad_terms[0] +
                 opacity_2d_grad_terms[1] +
                 opacity_2d_grad_terms[2]
             );
 
+            // Computing the gradients of the point
+            //
+            // ∂L/∂D[n]  = ∂L/∂D' * α'[n] * t[n]
+            // ∂L/∂α'[n] += ∂L/∂D' * t[n] * (D[n] - D'[n])
+
+            let depth_grad = opacity_2d * transmittance_state * depth_2d_grad;
+            opacity_2d_grad +=
+                transmittance_state * (depth_3d_state - depth_2d_state) * depth_2d_grad;
+
+            // Computing the gradients of the point
+            //
+            // A' = 1 - t[N]
+            // t[N] = t[n] * (1 - α'[n]) * ... * (1 - α'[N - 1])
+            //
+            // ∂L/∂α'[n] += ∂L/∂A' * t[N] / (1 - α'[n])
+
+            opacity_2d_grad += alpha_2d_grad * transmittance_last / (1.0 - opacity_2d);
+
             // Computing the gradients of the point
             // 
             // ∂L/∂α[n] = ∂L/∂α'[n] * σ[n]
             // ∂L/∂σ[n] = ∂L/∂α'[n] * α[n]
 
-            // (Inner)
-            let opacity_3d_grad = sigmoid_grad_f32(opacity_3d) * density * opacity_2d_grad;
+            let opacity_3d_grad = density * opacity_2d_grad;
             let density_grad = opacity_3d * opacity_2d_grad;
 
             // Computing the gradients of the point
@@ -262,11 +342,23 @@ fn main(
             atomicAdd(&conics_grad[3 * point_index + 0], conic_grad[0]);
             atomicAdd(&conics_grad[3 * point_index + 1], conic_grad[1]);
             atomicAdd(&conics_grad[3 * point_index + 2], conic_grad[2]);
+            // [P]
+            atomicAdd(&depths_grad[point_index], depth_grad);
             // [P, 1]
             atomicAdd(&opacities_3d_grad[point_index], opacity_3d_grad);
             // [P, 2]
             atomicAdd(&positions_2d_grad[2 * point_index + 0], position_2d_grad[0]);
             atomicAdd(&positions_2d_grad[2 * point_index + 1], position_2d_grad[1]);
+
+            // Accumulating the absolute gradients of the point if enabled
+            //
+            // |∂L/∂P|[2] = sum(|∂L/∂P[2]| of each pixel)
+
+            if arguments.positions_2d_grad_abs != 0 {
+                // [P, 2]
+                atomicAdd(&positions_2d_grad_abs[2 * point_index + 0], abs(position_2d_grad[0]));
+                atomicAdd(&positions_2d_grad_abs[2 * point_index + 1], abs(position_2d_grad[1]));
+            }
         }
 
         tile_point_count -= batch_point_count;
@@ -281,19 +373,6 @@ fn mat_sym_from_array_f32_3(a: array<f32, 3>) -> mat2x2<f32> {
     return mat2x2<f32>(a[0], a[1], a[1], a[2]);
 }
 
-// y = e^x / (1 + e^x)
-fn sigmoid_f32(x: f32) -> f32 {
-    let x_exp = exp(x);
-    return x_exp / (1.0 + x_exp);
-}
-
-// dy/dx = dy/d(e^x) * de^x/dx
-//       = (1 + e^x)^-2 * e^x
-//       = y * (1 - y)
-fn sigmoid_grad_f32(y: f32) -> f32 {
-    return y * (1.0 - y);
-}
-
 fn vec_from_array_f32_3(a: array<f32, 3>) -> vec3<f32> {
     return vec3<f32>(a[0], a[1], a[2]);
 }
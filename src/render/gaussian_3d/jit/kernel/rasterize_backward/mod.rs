@@ -12,39 +12,59 @@ use bytemuck::bytes_of;
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Arguments {
+    /// `C_rgb_bg`
+    pub background_rgb: [f32; 3],
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
     /// `I_x`
     pub image_size_x: u32,
     /// `I_y`
     pub image_size_y: u32,
+    /// `(0: Disabled, 1: Enabled)`
+    pub positions_2d_grad_abs: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
 
     /// `P`
     pub point_count: u32,
-    /// `I_x / T_x`
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
     pub tile_count_x: u32,
-    /// `I_y / T_y`
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
     pub tile_count_y: u32,
 }
 
 /// Inputs.
 #[derive(Clone, Debug)]
 pub struct Inputs<R: JitRuntime> {
-    /// `[I_y, I_x, 3]`
+    /// `[I_y', I_x']`
+    pub alphas_2d_grad: JitTensor<R>,
+    /// `[I_y', I_x', 3]`
     pub colors_rgb_2d_grad: JitTensor<R>,
     /// `[P, 3]`
     pub colors_rgb_3d: JitTensor<R>,
     /// `[P, 3]`
     pub conics: JitTensor<R>,
-    /// `[P, 1]`
+    /// `[P]`
+    pub depths: JitTensor<R>,
+    /// `[I_y', I_x']`
+    pub depths_2d_grad: JitTensor<R>,
+    /// `[P]` (Outer)
     pub opacities_3d: JitTensor<R>,
     /// `[T]`
     pub point_indices: JitTensor<R>,
-    /// `[I_y, I_x]`
+    /// `[I_y', I_x']`
     pub point_rendered_counts: JitTensor<R>,
     /// `[P, 2]`
     pub positions_2d: JitTensor<R>,
-    /// `[I_y / T_y, I_x / T_x, 2]`
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
     pub tile_point_ranges: JitTensor<R>,
-    /// `[I_y, I_x]`
+    /// `[I_y', I_x']`
     pub transmittances: JitTensor<R>,
 }
 
@@ -55,10 +75,17 @@ pub struct Outputs<R: JitRuntime> {
     pub colors_rgb_3d_grad: JitTensor<R>,
     /// `[P, 3]`
     pub conics_grad: JitTensor<R>,
-    /// `[P, 1]`
+    /// `[P]`
+    pub depths_grad: JitTensor<R>,
+    /// `[P]` (Outer)
     pub opacities_3d_grad: JitTensor<R>,
     /// `[P, 2]`
     pub positions_2d_grad: JitTensor<R>,
+    /// `[P, 2]`
+    ///
+    /// It is the sum of the absolute gradients of each pixel,
+    /// which is zero unless [`Arguments::positions_2d_grad_abs`] is enabled.
+    pub positions_2d_grad_abs: JitTensor<R>,
 }
 
 /// Compute the gradient of the rasterization.
@@ -79,10 +106,13 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         JitBackend::<R, F, I, B>::float_zeros([point_count, 3].into(), device);
     let conics_grad =
         JitBackend::<R, F, I, B>::float_zeros([point_count, 3].into(), device);
+    let depths_grad = JitBackend::<R, F, I, B>::float_zeros([point_count].into(), device);
     let opacities_3d_grad =
-        JitBackend::<R, F, I, B>::float_zeros([point_count, 1].into(), device);
+        JitBackend::<R, F, I, B>::float_zeros([point_count].into(), device);
     let positions_2d_grad =
         JitBackend::<R, F, I, B>::float_zeros([point_count, 2].into(), device);
+    let positions_2d_grad_abs =
+        JitBackend::<R, F, I, B>::float_zeros([point_count, 2].into(), device);
 
     // Launching the kernel
 
@@ -98,9 +128,12 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         CubeCount::Static(arguments.tile_count_x, arguments.tile_count_y, 1),
         vec![
             client.create(bytes_of(&arguments)).binding(),
+            inputs.alphas_2d_grad.handle.binding(),
             inputs.colors_rgb_2d_grad.handle.binding(),
             inputs.colors_rgb_3d.handle.binding(),
             inputs.conics.handle.binding(),
+            inputs.depths.handle.binding(),
+            inputs.depths_2d_grad.handle.binding(),
             inputs.opacities_3d.handle.binding(),
             inputs.point_indices.handle.binding(),
             inputs.point_rendered_counts.handle.binding(),
@@ -109,15 +142,19 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             inputs.transmittances.handle.binding(),
             colors_rgb_3d_grad.handle.to_owned().binding(),
             conics_grad.handle.to_owned().binding(),
+            depths_grad.handle.to_owned().binding(),
             opacities_3d_grad.handle.to_owned().binding(),
             positions_2d_grad.handle.to_owned().binding(),
+            positions_2d_grad_abs.handle.to_owned().binding(),
         ],
     );
 
     Outputs {
         colors_rgb_3d_grad,
         conics_grad,
+        depths_grad,
         opacities_3d_grad,
         positions_2d_grad,
+        positions_2d_grad_abs,
     }
 }
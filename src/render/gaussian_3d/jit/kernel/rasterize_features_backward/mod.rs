@@ -0,0 +1,142 @@
+//! Rasterizing the point features to the image (backward).
+
+pub use super::*;
+pub use rasterize::{TILE_SIZE_X, TILE_SIZE_Y};
+pub use rasterize_features::FEATURE_CHUNK_SIZE;
+
+use burn::tensor::ops::FloatTensorOps;
+use bytemuck::{bytes_of, Pod, Zeroable};
+
+/// Arguments.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `D`
+    pub feature_count: u32,
+    /// `I_x`
+    pub image_size_x: u32,
+    /// `I_y`
+    pub image_size_y: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
+
+    /// `P`
+    pub point_count: u32,
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Debug)]
+pub struct Inputs<R: JitRuntime> {
+    /// `[P, 3]`
+    pub conics: JitTensor<R>,
+    /// `[P, D]`
+    pub features: JitTensor<R>,
+    /// `[I_y', I_x', D]`
+    pub features_2d_grad: JitTensor<R>,
+    /// `[P]` (Outer)
+    pub opacities_3d: JitTensor<R>,
+    /// `[T]`
+    pub point_indices: JitTensor<R>,
+    /// `[I_y', I_x']`
+    pub point_rendered_counts: JitTensor<R>,
+    /// `[P, 2]`
+    pub positions_2d: JitTensor<R>,
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
+    pub tile_point_ranges: JitTensor<R>,
+    /// `[I_y', I_x']`
+    pub transmittances: JitTensor<R>,
+}
+
+/// Outputs.
+#[derive(Clone, Debug)]
+pub struct Outputs<R: JitRuntime> {
+    /// `[P, 3]`
+    pub conics_grad: JitTensor<R>,
+    /// `[P, D]`
+    pub features_grad: JitTensor<R>,
+    /// `[P]` (Outer)
+    pub opacities_3d_grad: JitTensor<R>,
+    /// `[P, 2]`
+    pub positions_2d_grad: JitTensor<R>,
+}
+
+/// Compute the gradient of the rasterization of the features.
+pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
+    arguments: Arguments,
+    inputs: Inputs<R>,
+) -> Outputs<R> {
+    impl_kernel_source!(Kernel, "kernel.wgsl");
+
+    // Specifying the parameters
+
+    let client = &inputs.features_2d_grad.client;
+    let device = &inputs.features_2d_grad.device;
+    // D
+    let feature_count = arguments.feature_count as usize;
+    // P
+    let point_count = arguments.point_count as usize;
+
+    let conics_grad =
+        JitBackend::<R, F, I, B>::float_zeros([point_count, 3].into(), device);
+    let features_grad = JitBackend::<R, F, I, B>::float_zeros(
+        [point_count, feature_count].into(),
+        device,
+    );
+    let opacities_3d_grad =
+        JitBackend::<R, F, I, B>::float_zeros([point_count].into(), device);
+    let positions_2d_grad =
+        JitBackend::<R, F, I, B>::float_zeros([point_count, 2].into(), device);
+
+    // Launching the kernel
+
+    client.execute(
+        Box::new(SourceKernel::new(
+            Kernel,
+            CubeDim {
+                x: TILE_SIZE_X,
+                y: TILE_SIZE_Y,
+                z: 1,
+            },
+        )),
+        CubeCount::Static(
+            arguments.tile_count_x,
+            arguments.tile_count_y,
+            arguments.feature_count.div_ceil(FEATURE_CHUNK_SIZE),
+        ),
+        vec![
+            client.create(bytes_of(&arguments)).binding(),
+            inputs.conics.handle.binding(),
+            inputs.features.handle.binding(),
+            inputs.features_2d_grad.handle.binding(),
+            inputs.opacities_3d.handle.binding(),
+            inputs.point_indices.handle.binding(),
+            inputs.point_rendered_counts.handle.binding(),
+            inputs.positions_2d.handle.binding(),
+            inputs.tile_point_ranges.handle.binding(),
+            inputs.transmittances.handle.binding(),
+            conics_grad.handle.to_owned().binding(),
+            features_grad.handle.to_owned().binding(),
+            opacities_3d_grad.handle.to_owned().binding(),
+            positions_2d_grad.handle.to_owned().binding(),
+        ],
+    );
+
+    Outputs {
+        conics_grad,
+        features_grad,
+        opacities_3d_grad,
+        positions_2d_grad,
+    }
+}
@@ -0,0 +1,250 @@
+struct Arguments {
+    // (0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)
+    camera_model: u32,
+    // I_x
+    image_size_x: u32,
+    // I_y
+    image_size_y: u32,
+    // L
+    label_count: u32,
+    // I_x0 (0 ~ I_x)
+    region_offset_x: u32,
+    // I_y0 (0 ~ I_y)
+    region_offset_y: u32,
+    // I_x' (1 ~ I_x - I_x0)
+    region_size_x: u32,
+    // I_y' (1 ~ I_y - I_y0)
+    region_size_y: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> arguments: Arguments;
+// [P, 3] (Symmetric mat2x2)
+@group(0) @binding(1)
+var<storage, read_write> conics: array<array<f32, 3>>;
+// [P]
+@group(0) @binding(2)
+var<storage, read_write> labels: array<i32>;
+// [P] (Outer)
+@group(0) @binding(3)
+var<storage, read_write> opacities_3d: array<f32>;
+// [T] (0 ~ P)
+@group(0) @binding(4)
+var<storage, read_write> point_indices: array<u32>;
+// [I_y', I_x']
+@group(0) @binding(5)
+var<storage, read_write> point_rendered_counts: array<u32>;
+// [P, 2]
+@group(0) @binding(6)
+var<storage, read_write> positions_2d: array<vec2<f32>>;
+// [I_y' / T_y, I_x' / T_x, 2]
+@group(0) @binding(7)
+var<storage, read_write> tile_point_ranges: array<vec2<u32>>;
+
+// [I_y', I_x', L] (0.0 ~ 1.0)
+@group(0) @binding(8)
+var<storage, read_write> label_coverages_2d: array<f32>;
+// [I_y', I_x']
+@group(0) @binding(9)
+var<storage, read_write> labels_2d: array<i32>;
+
+// [T_x * T_y, 2, 2]
+var<workgroup> conics_in_batch: array<mat2x2<f32>, BATCH_SIZE>;
+// [T_x * T_y]
+var<workgroup> labels_in_batch: array<i32, BATCH_SIZE>;
+// [T_x * T_y, 1]
+var<workgroup> opacities_3d_in_batch: array<f32, BATCH_SIZE>;
+// [T_x * T_y, 2]
+var<workgroup> positions_2d_in_batch: array<vec2<f32>, BATCH_SIZE>;
+// (0 ~ T_x * T_y)
+var<workgroup> pixel_done_count: atomic<u32>;
+
+const CAMERA_MODEL_EQUIRECTANGULAR: u32 = 4;
+const LABEL_2D_NONE: i32 = -1;
+const OPACITY_2D_MAX: f32 = 252.0 / 255.0;
+const OPACITY_2D_MIN: f32 = 1.0 / 255.0;
+// T_x * T_y
+const BATCH_SIZE: u32 = TILE_SIZE_X * TILE_SIZE_Y;
+// T_x
+const TILE_SIZE_X: u32 = 16;
+// T_y
+const TILE_SIZE_Y: u32 = 16;
+
+@compute @workgroup_size(TILE_SIZE_X, TILE_SIZE_Y, 1)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    // (0 ~ T_x * T_y)
+    @builtin(local_invocation_index) local_index: u32,
+    // (0 ~ I_x' / T_x + 1, 0 ~ I_y' / T_y + 1)
+    @builtin(workgroup_id) tile_id: vec3<u32>,
+    // (I_x' / T_x + 1, I_y' / T_y + 1)
+    @builtin(num_workgroups) tile_count: vec3<u32>,
+) {
+    // Specifying the index
+
+    // (0 ~ I_x, 0 ~ I_y)
+    //
+    // The tiles are aligned to the image rather than the region.
+    let pixel = global_id.xy + vec2<u32>(
+        arguments.region_offset_x / TILE_SIZE_X * TILE_SIZE_X,
+        arguments.region_offset_y / TILE_SIZE_Y * TILE_SIZE_Y,
+    );
+    // (0 ~ I_x', 0 ~ I_y')
+    //
+    // It wraps around for the pixels before the region, which are invalid.
+    let pixel_in_region = pixel - vec2<u32>(arguments.region_offset_x, arguments.region_offset_y);
+    // (0 ~ I_y' * I_x')
+    let pixel_index = pixel_in_region.y * arguments.region_size_x + pixel_in_region.x;
+    // (0 ~ (I_y' / T_y) * (I_x' / T_x))
+    let tile_index = tile_id.y * tile_count.x + tile_id.x;
+
+    // Specifying the parameters
+
+    let is_pixel_valid =
+        pixel_in_region.x < arguments.region_size_x &&
+        pixel_in_region.y < arguments.region_size_y;
+    let position_pixel = vec2<f32>(pixel);
+    let image_size_x = f32(arguments.image_size_x);
+    var point_range = vec2<u32>();
+    // R
+    var tile_point_count = 0u;
+    if tile_index < arrayLength(&tile_point_ranges) {
+        point_range = tile_point_ranges[tile_index];
+        if point_range.y > point_range.x {
+            tile_point_count = point_range.y - point_range.x;
+        }
+    }
+    // R / (T_x * T_y)
+    let batch_count = (tile_point_count + BATCH_SIZE - 1) / BATCH_SIZE;
+    var label_2d = LABEL_2D_NONE;
+    var point_rendered_count = 0u;
+    if is_pixel_valid {
+        point_rendered_count = point_rendered_counts[pixel_index];
+    }
+    var is_pixel_done = !is_pixel_valid;
+    if local_index == 0 {
+        pixel_done_count = 0u;
+    }
+    var point_rendered_state = 0u;
+    var transmittance_state = 1.0;
+    var was_pixel_done = false;
+    var weight_max = 0.0;
+
+    // Processing batches of points of the tile
+    // (0 ~ R / (T_x * T_y))
+
+    for (var batch_index = 0u; batch_index < batch_count; batch_index++) {
+        // Specifying the task status of the pixel
+
+        if is_pixel_done && !was_pixel_done {
+            was_pixel_done = true;
+            atomicAdd(&pixel_done_count, 1u);
+        }
+        workgroupBarrier();
+
+        // Leaving if all pixels in the tile finished rendering
+
+        if pixel_done_count == BATCH_SIZE {
+            break;
+        }
+
+        // Specifying the parameters in the batch
+
+        let index = point_range.x + batch_index * BATCH_SIZE + local_index;
+        if index < point_range.y {
+            let point_index = point_indices[index];
+            conics_in_batch[local_index] = mat_sym_from_array_f32_3(conics[point_index]);
+            labels_in_batch[local_index] = labels[point_index];
+            // (Outer)
+            opacities_3d_in_batch[local_index] = opacities_3d[point_index];
+            positions_2d_in_batch[local_index] = positions_2d[point_index];
+        }
+        workgroupBarrier();
+
+        // Skipping if the pixel is finished rendering
+
+        if is_pixel_done {
+            continue;
+        }
+
+        // Computing the 2D labels of the pixel using the batch parameters
+        // (0 ~ T_x * T_y)
+
+        let batch_point_count = min(tile_point_count, BATCH_SIZE);
+        for (var batch_pixel_index = 0u; batch_pixel_index < batch_point_count; batch_pixel_index++) {
+            // Leaving after the last point rendered in the pixel
+
+            if point_rendered_state == point_rendered_count {
+                is_pixel_done = true;
+                break;
+            }
+            point_rendered_state++;
+
+            // Computing the density of the point in the pixel
+            // D[2, 1] = Pv'[2, 1] - Px[2, 1]
+            // σ[n] = e^(-0.5 * D^t[1, 2] * Σ'^-1[2, 2] * D[2, 1])[n]
+            //
+            // D.x is wrapped around the seam for the equirectangular camera model
+
+            let conic = conics_in_batch[batch_pixel_index];
+            let position_2d = positions_2d_in_batch[batch_pixel_index];
+            var position_offset = position_2d - position_pixel;
+            if arguments.camera_model == CAMERA_MODEL_EQUIRECTANGULAR {
+                position_offset.x -= image_size_x * round(position_offset.x / image_size_x);
+            }
+            let density = exp(-0.5 * dot(position_offset * conic, position_offset));
+
+            // Skipping if the density is greater than one
+
+            if density > 1.0 {
+                continue;
+            }
+
+            // Computing the 2D opacity of the point in the pixel
+            // α'[n] = α[n] * σ[n]
+
+            let opacity_3d = opacities_3d_in_batch[batch_pixel_index];
+            let opacity_2d = min(opacity_3d * density, OPACITY_2D_MAX);
+
+            // Skipping if the 2D opacity is too low
+
+            if opacity_2d < OPACITY_2D_MIN {
+                continue;
+            }
+
+            // Accumulating the blending weight of the point into its label
+            // w[n] = α'[n] * t[n]
+            //
+            // The label of the point with the largest weight is dominant.
+
+            let label = labels_in_batch[batch_pixel_index];
+            let weight = opacity_2d * transmittance_state;
+            if weight > weight_max {
+                weight_max = weight;
+                label_2d = label;
+            }
+            if label >= 0 && u32(label) < arguments.label_count {
+                // [I_y', I_x', L]
+                label_coverages_2d[pixel_index * arguments.label_count + u32(label)] += weight;
+            }
+
+            // Updating the states of the pixel
+            // t[n + 1] = t[n] * (1 - α'[n])
+
+            transmittance_state *= 1.0 - opacity_2d;
+        }
+
+        tile_point_count -= batch_point_count;
+    }
+
+    // Specifying the results
+
+    if is_pixel_valid {
+        // [I_y', I_x']
+        labels_2d[pixel_index] = label_2d;
+    }
+}
+
+fn mat_sym_from_array_f32_3(a: array<f32, 3>) -> mat2x2<f32> {
+    return mat2x2<f32>(a[0], a[1], a[1], a[2]);
+}
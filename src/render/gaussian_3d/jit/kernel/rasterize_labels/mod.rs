@@ -0,0 +1,135 @@
+//! Rasterizing the point labels to the image.
+
+pub use super::*;
+pub use rasterize::{TILE_SIZE_X, TILE_SIZE_Y};
+
+use burn::tensor::ops::{FloatTensorOps, IntTensorOps};
+use bytemuck::{bytes_of, Pod, Zeroable};
+
+/// Arguments.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)`
+    pub camera_model: u32,
+    /// `I_x`
+    pub image_size_x: u32,
+    /// `I_y`
+    pub image_size_y: u32,
+    /// `L`
+    pub label_count: u32,
+    /// `I_x0`
+    pub region_offset_x: u32,
+    /// `I_y0`
+    pub region_offset_y: u32,
+    /// `I_x'`
+    pub region_size_x: u32,
+    /// `I_y'`
+    pub region_size_y: u32,
+
+    /// `(I_x0 + I_x') / T_x - I_x0 / T_x`
+    pub tile_count_x: u32,
+    /// `(I_y0 + I_y') / T_y - I_y0 / T_y`
+    pub tile_count_y: u32,
+}
+
+/// Inputs.
+#[derive(Clone, Debug)]
+pub struct Inputs<R: JitRuntime> {
+    /// `[P, 3]`
+    pub conics: JitTensor<R>,
+    /// `[P]`
+    pub labels: JitTensor<R>,
+    /// `[P]` (Outer)
+    pub opacities_3d: JitTensor<R>,
+    /// `[T]`
+    pub point_indices: JitTensor<R>,
+    /// `[I_y', I_x']`
+    pub point_rendered_counts: JitTensor<R>,
+    /// `[P, 2]`
+    pub positions_2d: JitTensor<R>,
+    /// `[I_y' / T_y, I_x' / T_x, 2]`
+    pub tile_point_ranges: JitTensor<R>,
+}
+
+/// Outputs.
+#[derive(Clone, Debug)]
+pub struct Outputs<R: JitRuntime> {
+    /// `[I_y', I_x', L]`
+    pub label_coverages_2d: JitTensor<R>,
+    /// `[I_y', I_x']`
+    pub labels_2d: JitTensor<R>,
+}
+
+/// `l'_none`
+///
+/// The label of the pixels without any rendered point.
+pub const LABEL_2D_NONE: i32 = -1;
+
+/// Rasterize the point labels to the image.
+///
+/// It weights the points in the same order and with the same weights
+/// as [`rasterize`], and each pixel stops at its rendered point count of the color pass.
+///
+/// For each pixel, the label of the point with the largest weight
+/// $ \alpha_n^' T_n $ is dominant, and the weights are accumulated into
+/// the coverage of each label in $ [0, L) $.
+pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
+    arguments: Arguments,
+    inputs: Inputs<R>,
+) -> Outputs<R> {
+    impl_kernel_source!(Kernel, "kernel.wgsl");
+
+    // Specifying the parameters
+
+    let client = &inputs.labels.client;
+    let device = &inputs.labels.device;
+    // L
+    let label_count = arguments.label_count as usize;
+    // I_x'
+    let region_size_x = arguments.region_size_x as usize;
+    // I_y'
+    let region_size_y = arguments.region_size_y as usize;
+
+    // [I_y', I_x', L]
+    let label_coverages_2d = JitBackend::<R, F, I, B>::float_zeros(
+        [region_size_y, region_size_x, label_count].into(),
+        device,
+    );
+    // [I_y', I_x']
+    let labels_2d = JitBackend::<R, F, I, B>::int_empty(
+        [region_size_y, region_size_x].into(),
+        device,
+    );
+
+    // Launching the kernel
+
+    client.execute(
+        Box::new(SourceKernel::new(
+            Kernel,
+            CubeDim {
+                x: TILE_SIZE_X,
+                y: TILE_SIZE_Y,
+                z: 1,
+            },
+        )),
+        CubeCount::Static(arguments.tile_count_x, arguments.tile_count_y, 1),
+        vec![
+            client.create(bytes_of(&arguments)).binding(),
+            inputs.conics.handle.binding(),
+            inputs.labels.handle.binding(),
+            inputs.opacities_3d.handle.binding(),
+            inputs.point_indices.handle.binding(),
+            inputs.point_rendered_counts.handle.binding(),
+            inputs.positions_2d.handle.binding(),
+            inputs.tile_point_ranges.handle.binding(),
+            label_coverages_2d.handle.to_owned().binding(),
+            labels_2d.handle.to_owned().binding(),
+        ],
+    );
+
+    Outputs {
+        label_coverages_2d,
+        labels_2d,
+    }
+}
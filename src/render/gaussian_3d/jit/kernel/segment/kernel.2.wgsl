@@ -2,11 +2,14 @@
 @group(0) @binding(0) var<storage, read_write>
 tile_point_count: u32;
 
-// [T] (0 ~ (I_y / T_y) * (I_x / T_x) in high order bits)
+// (1: 32-bit, 2: 64-bit)
 @group(0) @binding(1) var<storage, read_write>
+point_order_word_count: u32;
+// [T, point_order_word_count] (0 ~ (I_y / T_y) * (I_x / T_x) in high order bits)
+@group(0) @binding(2) var<storage, read_write>
 point_orders: array<u32>;
 // [I_y / T_y, I_x / T_x, 2]
-@group(0) @binding(2) var<storage, read_write>
+@group(0) @binding(3) var<storage, read_write>
 tile_point_ranges: array<array<u32, 2>>;
 
 const GROUP_SIZE: u32 = 256;
@@ -27,7 +30,7 @@ fn main(
 
     // Specifying the current tile index
 
-    let tile_index_current = point_orders[global_index] >> 16;
+    let tile_index_current = tile_index_from_point_order(global_index);
 
     // Specifying the range of the first point tile
 
@@ -38,7 +41,7 @@ fn main(
 
     // Finding the ranges of each point tile
 
-    let tile_index_previous = point_orders[global_index - 1] >> 16;
+    let tile_index_previous = tile_index_from_point_order(global_index - 1);
     if tile_index_current != tile_index_previous {
         tile_point_ranges[tile_index_previous][1] = global_index;
         tile_point_ranges[tile_index_current][0] = global_index;
@@ -50,3 +53,11 @@ fn main(
         tile_point_ranges[tile_index_current][1] = tile_point_count;
     }
 }
+
+// Specifying the tile index from the point order at the index.
+fn tile_index_from_point_order(index: u32) -> u32 {
+    if point_order_word_count == 2u {
+        return point_orders[index * 2u + 1u];
+    }
+    return point_orders[index] >> 16;
+}
@@ -3,12 +3,14 @@
 pub use super::*;
 
 use burn::tensor::ops::IntTensorOps;
-use bytemuck::{Pod, Zeroable};
+use bytemuck::{bytes_of, Pod, Zeroable};
 
 /// Arguments.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Arguments {
+    /// `(1: 32-bit, 2: 64-bit)`
+    pub point_order_word_count: u32,
     /// `I_x / T_x`
     pub tile_count_x: u32,
     /// `I_y / T_y`
@@ -18,7 +20,7 @@ pub struct Arguments {
 /// Inputs.
 #[derive(Clone, Debug)]
 pub struct Inputs<R: JitRuntime> {
-    /// `[T]`
+    /// `[T]` for 32-bit or `[T, 2]` for 64-bit
     pub point_orders: JitTensor<R>,
     /// `T`
     pub tile_point_count: JitTensor<R>,
@@ -87,6 +89,9 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         CubeCount::Dynamic(group_count.handle.binding()),
         vec![
             inputs.tile_point_count.handle.binding(),
+            client
+                .create(bytes_of(&arguments.point_order_word_count))
+                .binding(),
             inputs.point_orders.handle.binding(),
             tile_point_ranges.handle.to_owned().binding(),
         ],
@@ -1,5 +1,7 @@
 struct Arguments {
-    // (0 ~ 32: +log2(R))
+    // (1: 32-bit, 2: 64-bit)
+    key_word_count: u32,
+    // (0 ~ 32 * key_word_count: +log2(R))
     radix_shift: u32,
 }
 
@@ -8,7 +10,7 @@ arguments: Arguments;
 // N
 @group(0) @binding(1) var<storage, read_write>
 count: u32;
-// [N] = [N' / G, N / N', G]
+// [N, key_word_count] = [N' / G, N / N', G, key_word_count] (Low word first)
 @group(0) @binding(2) var<storage, read_write>
 keys_input: array<u32>;
 
@@ -22,6 +24,8 @@ counts_radix_in_group: array<atomic<u32>, RADIX_COUNT>;
 
 // log2(N')
 const BLOCK_COUNT_GROUP_SHIFT: u32 = 14;
+// 32 - 1
+const DIV_32_MASK: u32 = (1u << 5) - 1;
 // R
 const RADIX_COUNT: u32 = 1u << RADIX_COUNT_SHIFT;
 // log2(R)
@@ -46,6 +50,10 @@ fn main(
 
     // N / N'
     let block_count_group = max(count >> BLOCK_COUNT_GROUP_SHIFT, 1u);
+    // (0 ~ key_word_count)
+    let key_word_index = arguments.radix_shift >> 5;
+    // (0 ~ 32)
+    let key_word_shift = arguments.radix_shift & DIV_32_MASK;
     counts_radix_in_group[local_index] = 0u;
     workgroupBarrier();
 
@@ -58,7 +66,9 @@ fn main(
             (group_index * block_count_group + block_index) * GROUP_SIZE + local_index;
         if input_index < count {
             // (0 ~ R)
-            let radix = keys_input[input_index] >> arguments.radix_shift & RADIX_MASK;
+            let key_word =
+                keys_input[input_index * arguments.key_word_count + key_word_index];
+            let radix = key_word >> key_word_shift & RADIX_MASK;
             atomicAdd(&counts_radix_in_group[radix], 1u);
         }
     }
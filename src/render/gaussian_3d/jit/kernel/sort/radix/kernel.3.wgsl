@@ -1,5 +1,7 @@
 struct Arguments {
-    // (0 ~ 32: +log2(R))
+    // (1: 32-bit, 2: 64-bit)
+    key_word_count: u32,
+    // (0 ~ 32 * key_word_count: +log2(R))
     radix_shift: u32,
 }
 
@@ -11,14 +13,14 @@ count: u32;
 // [N' / G, R]
 @group(0) @binding(2) var<storage, read_write>
 counts_radix_group: array<u32>;
-// [N] = [N' / G, N / N', G]
+// [N, key_word_count] = [N' / G, N / N', G, key_word_count] (Low word first)
 @group(0) @binding(3) var<storage, read_write>
 keys_input: array<u32>;
 // [N]
 @group(0) @binding(4) var<storage, read_write>
 values_input: array<u32>;
 
-// [N]
+// [N, key_word_count] (Low word first)
 @group(0) @binding(5) var<storage, read_write>
 keys_out: array<u32>;
 // [N]
@@ -49,6 +51,8 @@ const RADIX_COUNT_SHIFT: u32 = 8;
 const RADIX_MASK: u32 = RADIX_COUNT - 1;
 // G <- R
 const GROUP_SIZE: u32 = RADIX_COUNT;
+// The maximum of key_word_count
+const KEY_WORD_COUNT_MAX: u32 = 2;
 
 @compute @workgroup_size(GROUP_SIZE, 1, 1)
 fn main(
@@ -72,6 +76,12 @@ fn main(
 
     // N / N'
     let block_count_group = max(count >> BLOCK_COUNT_GROUP_SHIFT, 1u);
+    // (1 ~ 2)
+    let key_word_count = arguments.key_word_count;
+    // (0 ~ key_word_count)
+    let key_word_index = arguments.radix_shift >> 5;
+    // (0 ~ 32)
+    let key_word_shift = arguments.radix_shift & DIV_32_MASK;
 
     // Scanning radix counts in all groups into radix offsets of the group
 
@@ -117,7 +127,7 @@ fn main(
         let input_index =
             (group_index * block_count_group + block_index) * GROUP_SIZE + local_index;
         let is_input_index_valid = input_index < count;
-        var key_input = 0u;
+        var key_input = array<u32, KEY_WORD_COUNT_MAX>();
         var value_input = 0u;
         var offset_radix_group = 0u;
         var radix = 0u;
@@ -133,9 +143,11 @@ fn main(
         // Fetching the radix and offset
 
         if is_input_index_valid {
-            key_input = keys_input[input_index];
+            for (var index = 0u; index < key_word_count; index++) {
+                key_input[index] = keys_input[input_index * key_word_count + index];
+            }
             value_input = values_input[input_index];
-            radix = key_input >> arguments.radix_shift & RADIX_MASK;
+            radix = key_input[key_word_index] >> key_word_shift & RADIX_MASK;
             offset_radix_group = offsets_radix_group[radix];
             // [R, G / 32] <- [R, G]
             atomicOr(&masks_radix_in_block[radix][mask_radix_index], mask_radix_local);
@@ -169,7 +181,9 @@ fn main(
             // [N]
 
             let position = offset_radix_group + offset_radix_local;
-            keys_out[position] = key_input;
+            for (var index = 0u; index < key_word_count; index++) {
+                keys_out[position * key_word_count + index] = key_input[index];
+            }
             values_out[position] = value_input;
 
             // Adding the radix count of the block to the radix offset of the group
@@ -10,7 +10,9 @@ use std::mem::swap;
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Arguments {
-    /// `(0 ~ 32: +log2(R))`
+    /// `(1: 32-bit, 2: 64-bit)`
+    pub key_word_count: u32,
+    /// `(0 ~ 32 * key_word_count: +log2(R))`
     pub radix_shift: u32,
 }
 
@@ -20,6 +22,9 @@ pub struct Inputs<R: JitRuntime> {
     /// The count of items to sort.
     pub count: JitTensor<R>,
     /// The keys of items to sort.
+    ///
+    /// The shape is `[N]` for 32-bit keys or `[N, 2]` for 64-bit keys.
+    /// The 64-bit keys are stored as 32-bit words in low word first order.
     pub keys: JitTensor<R>,
     /// The values of items to sort.
     pub values: JitTensor<R>,
@@ -41,8 +46,10 @@ pub const BLOCK_COUNT_GROUP_SHIFT: u32 = 14;
 pub const GROUP_COUNT_MAX: u32 = (2 << BLOCK_COUNT_GROUP_SHIFT) / GROUP_SIZE;
 /// `G <- R`
 pub const GROUP_SIZE: u32 = RADIX_COUNT as u32;
-/// `|Key|`
-pub const KEY_BIT_COUNT: usize = size_of::<u32>() << 3;
+/// `|Key word|`
+pub const KEY_WORD_BIT_COUNT: usize = size_of::<u32>() << 3;
+/// Maximum of the word count in a key.
+pub const KEY_WORD_COUNT_MAX: usize = 2;
 /// `R`
 pub const RADIX_COUNT: usize = 1 << RADIX_COUNT_SHIFT;
 /// `log2(R)`
@@ -51,11 +58,12 @@ pub const RADIX_COUNT_SHIFT: u32 = 8;
 /// Sort the items by the keys.
 ///
 /// Radix-Sort-256 is a stable sort.
+/// The keys are either 32-bit or 64-bit according to their shape.
 pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     inputs: Inputs<R>
 ) -> Outputs<R> {
-    // NOTE: The key size is fixed.
-    debug_assert_eq!(size_of::<I>(), KEY_BIT_COUNT >> 3);
+    // NOTE: The key word size is fixed.
+    debug_assert_eq!(size_of::<I>(), KEY_WORD_BIT_COUNT >> 3);
 
     impl_kernel_source!(Kernel1, "kernel.1.wgsl");
     impl_kernel_source!(Kernel2, "kernel.2.wgsl");
@@ -69,13 +77,21 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     let mut values_input = inputs.values;
     // N
     let count = keys_input.shape.dims[0];
+    // (1 ~ 2)
+    let key_word_count = keys_input.shape.dims.get(1).copied().unwrap_or(1);
     let device = &keys_input.device.to_owned();
 
-    let mut arguments = Arguments { radix_shift: 0 };
+    debug_assert!((1..=KEY_WORD_COUNT_MAX).contains(&key_word_count));
+
+    let mut arguments = Arguments {
+        key_word_count: key_word_count as u32,
+        radix_shift: 0,
+    };
     // (N' / G, 1, 1)
     let group_count = JitBackend::<R, F, I, B>::int_empty([3].into(), device);
-    // [N]
-    let mut keys_output = JitBackend::<R, F, I, B>::int_empty([count].into(), device);
+    // [N, key_word_count]
+    let mut keys_output =
+        JitBackend::<R, F, I, B>::int_empty(keys_input.shape.to_owned(), device);
     // [N]
     let mut values_output = JitBackend::<R, F, I, B>::int_empty([count].into(), device);
     // [2 * N' / G, R]
@@ -97,7 +113,9 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     // Launching the kernel 2 and 3 iteratively
 
-    for radix_shift in (0..KEY_BIT_COUNT as u32).step_by(RADIX_COUNT_SHIFT as usize) {
+    for radix_shift in (0..(KEY_WORD_BIT_COUNT * key_word_count) as u32)
+        .step_by(RADIX_COUNT_SHIFT as usize)
+    {
         // Specifying the parameters for the pass
 
         arguments.radix_shift = radix_shift;
@@ -301,4 +319,72 @@ mod tests {
                 assert_eq!(output, target, "value index: {index}");
             });
     }
+
+    #[test]
+    fn sort_stable_random_64_bit() {
+        use super::*;
+        use crate::backend::{Wgpu, WgpuDevice, WgpuRuntime};
+        use burn::tensor::TensorData;
+        use bytemuck::cast_slice;
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        use rayon::slice::ParallelSliceMut;
+
+        type B = Wgpu;
+        type R = WgpuRuntime;
+        type F = f32;
+        type I = i32;
+        let device = &WgpuDevice::default();
+
+        let count = (1 << 18) - 1;
+        // The high words have few distinct values to test the stability.
+        let keys_source = StdRng::from_entropy()
+            .sample_iter(rand_distr::Uniform::new(0, 1_u64 << 36))
+            .take(count)
+            .collect::<Vec<_>>();
+        let values_source = (0..count as u32).collect::<Vec<_>>();
+
+        let (keys_target, values_target) = {
+            let mut items_source = keys_source
+                .iter()
+                .zip(&values_source)
+                .map(|(&key, &value)| (key, value))
+                .collect::<Vec<_>>();
+            items_source.par_sort_by_key(|p| p.0);
+            items_source.into_iter().collect::<(Vec<_>, Vec<_>)>()
+        };
+
+        let keys_source = keys_source
+            .into_iter()
+            .flat_map(|key| [key as u32, (key >> 32) as u32])
+            .collect::<Vec<_>>();
+        let keys = B::int_from_data(TensorData::new(keys_source, [count, 2]), device);
+        let values = B::int_from_data(TensorData::new(values_source, [count]), device);
+        let count = B::int_from_data([count].into(), device);
+        let Outputs { keys, values } = main::<R, F, I, u32>(Inputs {
+            count,
+            keys,
+            values,
+        });
+        let keys_output = &keys.client.read(vec![keys.handle.to_owned().binding()])[0];
+        let keys_output = cast_slice::<u8, u32>(keys_output)
+            .chunks_exact(2)
+            .map(|words| words[0] as u64 | (words[1] as u64) << 32)
+            .collect::<Vec<_>>();
+        let values_output =
+            &values.client.read(vec![values.handle.to_owned().binding()])[0];
+        let values_output = cast_slice::<u8, u32>(values_output);
+
+        keys_output.iter().zip(&keys_target).enumerate().for_each(
+            |(index, (&output, &target))| {
+                assert_eq!(output, target, "key index: {index}");
+            },
+        );
+        values_output
+            .iter()
+            .zip(&values_target)
+            .enumerate()
+            .for_each(|(index, (&output, &target))| {
+                assert_eq!(output, target, "value index: {index}");
+            });
+    }
 }
@@ -1,24 +1,48 @@
 struct Arguments {
+    // (0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)
+    camera_model: u32,
     // (0 ~ 3)
     colors_sh_degree_max: u32,
+    // D_far
+    depth_far: f32,
+    // D_near
+    depth_near: f32,
+    // [k1, k2, k3, k4, p1, p2]
+    distortion: array<f32, 6>,
+    // k
+    factor_radius: f32,
+    // C_f
+    filter_2d: f32,
+    // C_3d
+    filter_3d_scale: f32,
+    // (0: Low-pass filter, 1: Mip filter)
+    filter_mip_2d: u32,
     // F_x <- I_x / tan(Fov_x / 2) / 2
     focal_length_x: f32,
     // F_y <- I_y / tan(Fov_y / 2) / 2
     focal_length_y: f32,
-    // I_x / 2
-    image_size_half_x: f32,
-    // I_y / 2
-    image_size_half_y: f32,
     // P
     point_count: u32,
-    // I_x / T_x (0 ~ )
+    // C_x <- I_x / 2 + O_x
+    principal_point_x: f32,
+    // C_y <- I_y / 2 + O_y
+    principal_point_y: f32,
+    // I_x' / T_x (0 ~ )
     tile_count_x: i32,
-    // I_y / T_y (0 ~ )
+    // I_y' / T_y (0 ~ )
     tile_count_y: i32,
-    // tan(Fov_x / 2) * (C_f + 1)
-    view_bound_x: f32,
-    // tan(Fov_y / 2) * (C_f + 1)
-    view_bound_y: f32,
+    // I_x0 / T_x (0 ~ )
+    tile_offset_x: i32,
+    // I_y0 / T_y (0 ~ )
+    tile_offset_y: i32,
+    // (I_x - C_x + I_x / 2 * C_f) / F_x
+    view_bound_x_max: f32,
+    // -(C_x + I_x / 2 * C_f) / F_x
+    view_bound_x_min: f32,
+    // (I_y - C_y + I_y / 2 * C_f) / F_y
+    view_bound_y_max: f32,
+    // -(C_y + I_y / 2 * C_f) / F_y
+    view_bound_y_min: f32,
     // V[3]
     view_position: vec3<f32>,
     // Rv[3, 3]
@@ -27,50 +51,72 @@ struct Arguments {
     view_translation: vec3<f32>,
 }
 
+// The lens distortion at a normalized position Pn[2]
+// Pn'[2] = Pn[2] * ρ(r^2) + Tn(Pn)
+struct Distortion {
+    // ρ
+    radial: f32,
+    // ∂ρ/∂r^2
+    radial_grad: f32,
+    // ∂^2ρ/∂(r^2)^2
+    radial_grad_2: f32,
+    // [p1, p2]
+    tangential: vec2<f32>,
+}
+
 @group(0) @binding(0)
 var<storage, read_write> arguments: Arguments;
 // [P, M, 3]
 @group(0) @binding(1)
 var<storage, read_write> colors_sh: array<array<array<f32, 3>, SH_COUNT_MAX>>;
-// [P, 3]
+// [P, 1]
 @group(0) @binding(2)
+var<storage, read_write> filters_3d: array<f32>;
+// [P, 1] (Inner)
+@group(0) @binding(3)
+var<storage, read_write> opacities: array<f32>;
+// [P, 3]
+@group(0) @binding(4)
 var<storage, read_write> positions_3d: array<array<f32, 3>>;
 // [P, 4] (x, y, z, w) (Inner)
-@group(0) @binding(3)
+@group(0) @binding(5)
 var<storage, read_write> rotations: array<vec4<f32>>;
 // [P, 3] (Inner)
-@group(0) @binding(4)
+@group(0) @binding(6)
 var<storage, read_write> scalings: array<array<f32, 3>>;
 
 // [P, 3] (0.0, 1.0)
-@group(0) @binding(5)
+@group(0) @binding(7)
 var<storage, read_write> colors_rgb_3d: array<array<f32, 3>>;
 // [P, 3] (Symmetric mat2x2)
-@group(0) @binding(6)
+@group(0) @binding(8)
 var<storage, read_write> conics: array<array<f32, 3>>;
 // [P] (0 ~ )
-@group(0) @binding(7)
+@group(0) @binding(9)
 var<storage, read_write> depths: array<f32>;
 // [P, 3] (0.0, 1.0)
-@group(0) @binding(8)
+@group(0) @binding(10)
 var<storage, read_write> is_colors_rgb_3d_not_clamped: array<array<f32, 3>>;
+// [P] (Outer)
+@group(0) @binding(11)
+var<storage, read_write> opacities_3d: array<f32>;
 // [P, 4] (x max, x min, y max, y min)
-@group(0) @binding(9)
+@group(0) @binding(12)
 var<storage, read_write> point_tile_bounds: array<vec4<u32>>;
 // [P, 2]
-@group(0) @binding(10)
+@group(0) @binding(13)
 var<storage, read_write> positions_2d: array<vec2<f32>>;
 // [P, 2]
-@group(0) @binding(11)
+@group(0) @binding(14)
 var<storage, read_write> positions_3d_in_normalized: array<vec2<f32>>;
 // [P]
-@group(0) @binding(12)
+@group(0) @binding(15)
 var<storage, read_write> radii: array<u32>;
 // [P, 3, 3]
-@group(0) @binding(13)
+@group(0) @binding(16)
 var<storage, read_write> rotations_matrix: array<array<f32, 9>>;
 // [P]
-@group(0) @binding(14)
+@group(0) @binding(17)
 var<storage, read_write> tile_touched_counts: array<u32>;
 
 // The real coefficients of orthonormalized spherical harmonics from degree 0 to 3
@@ -101,13 +147,17 @@ const SH_C_3: array<f32, 7> = array<f32, 7>(
 // M
 const SH_COUNT_MAX: u32 = 16;
 
-// The depth range is restricted by 16-bit depth order for sorting
-const DEPTH_MAX: f32 = f32(1u << (17 - 3));
-const DEPTH_MIN: f32 = 1.0 / f32(1u << (3 - 1));
-// The r for `0.9973 = ∫[-r, r] e^(-0.5 * x^2) dx / √2π`
-const FACTOR_RADIUS: f32 = 2.9999771;
-// C_f
-const FILTER_LOW_PASS: f32 = 0.3;
+// The camera models
+const CAMERA_MODEL_EQUIRECTANGULAR: u32 = 4;
+const CAMERA_MODEL_FISHEYE: u32 = 2;
+const CAMERA_MODEL_OPENCV: u32 = 1;
+const CAMERA_MODEL_ORTHOGRAPHIC: u32 = 3;
+const CAMERA_MODEL_PINHOLE: u32 = 0;
+// The bound of r^2 to expand the fisheye distortion into series
+const FISHEYE_SERIES_BOUND: f32 = 1e-2;
+
+// 2π
+const TAU: f32 = 6.2831855;
 // T_x
 const TILE_SIZE_X: f32 = 16.0;
 // T_y
@@ -137,11 +187,21 @@ fn main(
     let position_3d = vec_from_array_f32_3(positions_3d[index]);
     let view_rotation = arguments.view_rotation;
     let position_3d_in_view = view_rotation * position_3d + arguments.view_translation;
-    let depth = position_3d_in_view.z;
+
+    // D = Pv.z
+    //
+    // D = |Pv| for the equirectangular camera model
+
+    let is_equirectangular = arguments.camera_model == CAMERA_MODEL_EQUIRECTANGULAR;
+    var depth = position_3d_in_view.z;
+    if is_equirectangular {
+        depth = length(position_3d_in_view);
+    }
 
     // Performing viewing-frustum culling
+    // D in [D_near, D_far)
 
-    if depth < DEPTH_MIN || depth >= DEPTH_MAX {
+    if depth < arguments.depth_near || depth >= arguments.depth_far {
         return;
     }
 
@@ -170,7 +230,8 @@ fn main(
 
     // Computing the 3D covariance matrix from rotation and scaling
     // RS[3, 3] = R[3, 3] * S[3, 3]
-    // Σ[3, 3] (Symmetric) = RS[3, 3] * RS^t[3, 3]
+    // Σ[3, 3] (Symmetric) = RS[3, 3] * RS^t[3, 3] + C_3d * F_3d * I[3, 3]
+    // ρ_3d = √(|S^2[3, 3]| / |S^2[3, 3] + C_3d * F_3d * I[3, 3]|)
     // 
     // S is diagonal
     // 
@@ -196,49 +257,121 @@ fn main(
         rotation_matrix[1] * scaling[1],
         rotation_matrix[2] * scaling[2],
     );
-    let covariance_3d = rotation_scaling * transpose(rotation_scaling);
+    let filter_3d = arguments.filter_3d_scale * filters_3d[index];
+    let covariance_3d =
+        rotation_scaling * transpose(rotation_scaling) + mat3x3<f32>(
+            filter_3d, 0.0, 0.0,
+            0.0, filter_3d, 0.0,
+            0.0, 0.0, filter_3d,
+        );
+    var opacity_compensation_3d = 1.0;
+    if filter_3d != 0.0 {
+        let scaling_p2 = scaling * scaling;
+        let ratio = scaling_p2 / (scaling_p2 + filter_3d);
+        opacity_compensation_3d = sqrt(ratio.x * ratio.y * ratio.z);
+    }
 
-    // Transforming the 3D position to 2D position (view => normalized => clip => screen)
-    // Pv'[2, 1] <- Pv[3, 1]
-    // Pv'[2, 1] = [f.x * Pv.x / Pv.z + (I.x * 0.5 - 0.5)
-    //              f.y * Pv.y / Pv.z + (I.y * 0.5 - 0.5)]
+    // Transforming the 3D position to 2D position
+    // (view => normalized => distorted => clip => screen)
+    // Pn[2] = [Pv.x / Pv.z, Pv.y / Pv.z]
+    // Pn'[2] = Pn[2] * ρ(r^2) + Tn(Pn)
+    // Pv'[2] = F[2] * Pn'[2] + C[2] - 0.5
+    //
+    // Pn[2] = [Pv.x, Pv.y] for the orthographic camera model
+    // Pn[2] = [atan2(Pv.x, Pv.z), atan2(Pv.y, √(Pv.x^2 + Pv.z^2))]
+    // for the equirectangular camera model
 
+    let is_orthographic = arguments.camera_model == CAMERA_MODEL_ORTHOGRAPHIC;
     let focal_length = vec2<f32>(arguments.focal_length_x, arguments.focal_length_y);
-    let position_3d_in_normalized = position_3d_in_view.xy / depth;
-    let position_3d_in_clip = position_3d_in_normalized * focal_length;
-    let position_2d = position_3d_in_clip + vec2<f32>(
-        arguments.image_size_half_x,
-        arguments.image_size_half_y,
+    var position_3d_in_normalized = position_3d_in_view.xy / depth;
+    if is_orthographic {
+        position_3d_in_normalized = position_3d_in_view.xy;
+    }
+    if is_equirectangular {
+        position_3d_in_normalized = vec2<f32>(
+            atan2(position_3d_in_view.x, position_3d_in_view.z),
+            atan2(position_3d_in_view.y, length(position_3d_in_view.xz)),
+        );
+    }
+    let position_3d_in_normalized_clamped = clamp(
+        position_3d_in_normalized,
+        vec2<f32>(arguments.view_bound_x_min, arguments.view_bound_y_min),
+        vec2<f32>(arguments.view_bound_x_max, arguments.view_bound_y_max),
+    );
+
+    // Performing viewing-frustum culling for the distorted camera models
+
+    if (arguments.camera_model == CAMERA_MODEL_OPENCV ||
+        arguments.camera_model == CAMERA_MODEL_FISHEYE) &&
+        any(position_3d_in_normalized != position_3d_in_normalized_clamped) {
+        return;
+    }
+
+    let distortion = distort(position_3d_in_normalized_clamped);
+    let position_3d_in_distorted = distort_position(distortion, position_3d_in_normalized);
+    var position_2d = position_3d_in_distorted * focal_length + vec2<f32>(
+        arguments.principal_point_x,
+        arguments.principal_point_y,
     ) - 0.5;
 
+    // Wrapping around the horizontal seam for the equirectangular camera model
+    // Pv'.x in [-0.5, I_x - 0.5)
+    //
+    // I_x = 2π * F_x
+
+    let image_period_x = TAU * focal_length.x;
+    if is_equirectangular {
+        position_2d.x -= image_period_x * floor((position_2d.x + 0.5) / image_period_x);
+    }
+
     // Projecting the 3D covariance matrix into 2D covariance matrix
+    // K[2, 2] = F[2, 2] * Jd[2, 2]
+    // J[2, 3] = K[2, 2] * [[1, 0, -Pn.x], [0, 1, -Pn.y]] / Pv.z
     // T[2, 3] = J[2, 3] * Rv[3, 3]
-    // Σ'[2, 2] (Symmetric) = T[2, 3] * Σ[3, 3] * T^t[3, 2] + C[2, 2]
+    // Σ'[2, 2] (Symmetric) = T[2, 3] * Σ[3, 3] * T^t[3, 2] + C_f * I[2, 2]
+    // ρ_2d = √max(|Σ' - C_f * I[2, 2]| / |Σ'|, 0) for the mip filter, 1 otherwise
     //
-    // J = [[f.x / Pv.z, 0,          -f.x * Pv.x / Pv.z^2]
-    //      [0,          f.y / Pv.z, -f.y * Pv.y / Pv.z^2]]
-    // C = [[C_f, 0  ]
-    //      [0,   C_f]]
+    // Pn.x and Pn.y are the clamped
     //
-    // Pv.x and Pv.y are the clamped
-
-    let focal_length_normalized = focal_length / depth;
-    let view_bound = vec2<f32>(arguments.view_bound_x, arguments.view_bound_y);
-    let position_3d_in_normalized_clamped = clamp(
-        position_3d_in_normalized,
-        -view_bound,
-        view_bound,
+    // J[2, 3] = F[2, 2] * [[1, 0, 0], [0, 1, 0]] for the orthographic camera model
+    // J[2, 3] = F[2, 2] * ∂Pn/∂Pv[2, 3] for the equirectangular camera model
+
+    var depth_projected = depth;
+    var position_3d_in_normalized_projected = position_3d_in_normalized_clamped;
+    if is_orthographic {
+        depth_projected = 1.0;
+        position_3d_in_normalized_projected = vec2<f32>();
+    }
+    let focal_length_normalized = focal_length / depth_projected;
+    let distortion_jacobian = distort_jacobian(distortion, position_3d_in_normalized_clamped);
+    let projection_2d_scaled = mat2x2<f32>(
+        focal_length_normalized * distortion_jacobian[0],
+        focal_length_normalized * distortion_jacobian[1],
+    );
+    var projection_2d_left = mat3x2<f32>(
+        projection_2d_scaled[0],
+        projection_2d_scaled[1],
+        -(projection_2d_scaled * position_3d_in_normalized_projected),
     );
-    let projection_2d = mat3x2<f32>(
-        vec2<f32>(focal_length_normalized.x, 0.0),
-        vec2<f32>(0.0, focal_length_normalized.y),
-        -focal_length_normalized * position_3d_in_normalized_clamped,
-    ) * view_rotation;
-    let covariance_2d =
-        projection_2d * covariance_3d * transpose(projection_2d) + mat2x2<f32>(
-            FILTER_LOW_PASS, 0.0,
-            0.0, FILTER_LOW_PASS,
+    if is_equirectangular {
+        // Leaving at the poles
+        if all(position_3d_in_view.xz == vec2<f32>()) {
+            return;
+        }
+        let jacobian = equirectangular_jacobian(position_3d_in_view);
+        projection_2d_left = mat3x2<f32>(
+            focal_length * jacobian[0],
+            focal_length * jacobian[1],
+            focal_length * jacobian[2],
         );
+    }
+    let projection_2d = projection_2d_left * view_rotation;
+    let covariance_2d_unfiltered =
+        projection_2d * covariance_3d * transpose(projection_2d);
+    let covariance_2d = covariance_2d_unfiltered + mat2x2<f32>(
+        arguments.filter_2d, 0.0,
+        0.0, arguments.filter_2d,
+    );
 
     // Computing the inverse of the 2D covariance matrix
     // Σ'^-1[2, 2] (Symmetric) <- Σ'[2, 2]
@@ -250,6 +383,11 @@ fn main(
     let conic =
         vec3<f32>(covariance_2d[1][1], -covariance_2d[0][1], covariance_2d[0][0]) /
         covariance_2d_det;
+    var opacity_compensation_2d = 1.0;
+    if arguments.filter_mip_2d != 0u {
+        opacity_compensation_2d =
+            sqrt(max(determinant(covariance_2d_unfiltered) / covariance_2d_det, 0.0));
+    }
 
     // Computing the max radius using the 2D covariance matrix
     // r <- Σ'[2, 2]
@@ -281,12 +419,16 @@ fn main(
         covariance_2d_diag_mean + eigenvalue_difference,
         covariance_2d_diag_mean - eigenvalue_difference,
     );
-    let radius = ceil(sqrt(max(eigenvalue_max, 0.0)) * FACTOR_RADIUS);
+    let radius = ceil(sqrt(max(eigenvalue_max, 0.0)) * arguments.factor_radius);
 
-    // Checking the tiles touched
+    // Checking the tiles touched in the region
     // (x max, x min, y max, y min)
 
-    let point_tile_bound = bitcast<vec4<u32>>(
+    let tile_offset = vec4<i32>(
+        arguments.tile_offset_x, arguments.tile_offset_x,
+        arguments.tile_offset_y, arguments.tile_offset_y,
+    );
+    var point_tile_bound = bitcast<vec4<u32>>(
         clamp(
             vec4<i32>(
                 i32((position_2d.x + radius + TILE_SIZE_X - 1.0) / TILE_SIZE_X),
@@ -294,13 +436,25 @@ fn main(
                 i32((position_2d.y + radius + TILE_SIZE_Y - 1.0) / TILE_SIZE_Y),
                 i32((position_2d.y - radius) / TILE_SIZE_Y),
             ),
-            vec4<i32>(),
-            vec4<i32>(
+            tile_offset,
+            tile_offset + vec4<i32>(
                 arguments.tile_count_x, arguments.tile_count_x,
                 arguments.tile_count_y, arguments.tile_count_y,
             ),
-        )
+        ) - tile_offset
     );
+
+    // Touching the full rows if the point crosses the horizontal seam
+    // for the equirectangular camera model
+
+    if is_equirectangular && (
+        position_2d.x - radius < -0.5 ||
+        position_2d.x + radius >= image_period_x - 0.5
+    ) {
+        point_tile_bound[0] = u32(arguments.tile_count_x);
+        point_tile_bound[1] = 0u;
+    }
+
     let tile_point_count =
         (point_tile_bound[0] - point_tile_bound[1]) *
         (point_tile_bound[2] - point_tile_bound[3]);
@@ -314,8 +468,13 @@ fn main(
     // Computing the view direction in world space
     // Ov[3] = Pw[3] - V[3]
     // Dv[3] = Ov[3] / |Ov|
+    //
+    // Ov[3] = Rv^t[3, 3] * [0, 0, 1] for the orthographic camera model
 
-    let view_offset = position_3d - arguments.view_position;
+    var view_offset = position_3d - arguments.view_position;
+    if is_orthographic {
+        view_offset = vec3<f32>(0.0, 0.0, 1.0) * view_rotation;
+    }
     var view_direction = view_offset;
     if all(view_direction == vec3<f32>()) {
         return;
@@ -391,6 +550,12 @@ fn main(
     let is_color_rgb_3d_not_clamped = color_rgb_3d >= vec3<f32>();
     color_rgb_3d = select(vec3<f32>(), color_rgb_3d, is_color_rgb_3d_not_clamped);
 
+    // Computing the 3D opacity compensated by the filters
+    // α[1] = sigmoid(α_inner[1]) * ρ_3d * ρ_2d
+
+    let opacity_3d =
+        sigmoid_f32(opacities[index]) * opacity_compensation_3d * opacity_compensation_2d;
+
     // Specifying the results
 
     // [P, 3]
@@ -403,6 +568,8 @@ fn main(
     is_colors_rgb_3d_not_clamped[index] = array_from_vec_f32_3(
         vec3<f32>(is_color_rgb_3d_not_clamped)
     );
+    // [P]
+    opacities_3d[index] = opacity_3d;
     // [P, 4]
     point_tile_bounds[index] = point_tile_bound;
     // [P, 2]
@@ -429,6 +596,125 @@ fn array_from_vec_f32_3(v: vec3<f32>) -> array<f32, 3> {
     return array<f32, 3>(v[0], v[1], v[2]);
 }
 
+// Evaluating the lens distortion at the normalized position Pn[2]
+fn distort(position: vec2<f32>) -> Distortion {
+    let k1 = arguments.distortion[0];
+    let k2 = arguments.distortion[1];
+    let k3 = arguments.distortion[2];
+    let k4 = arguments.distortion[3];
+    let r2 = dot(position, position);
+
+    switch arguments.camera_model {
+        // ρ = (1 + k1 * r^2 + k2 * r^4 + k3 * r^6) / (1 + k4 * r^2)
+        case CAMERA_MODEL_OPENCV: {
+            let numerator = 1.0 + r2 * (k1 + r2 * (k2 + r2 * k3));
+            let numerator_grad = k1 + r2 * (2.0 * k2 + r2 * 3.0 * k3);
+            let numerator_grad_2 = 2.0 * k2 + r2 * 6.0 * k3;
+            let denominator = 1.0 + r2 * k4;
+            let radial = numerator / denominator;
+            let radial_grad = (numerator_grad - radial * k4) / denominator;
+            let radial_grad_2 = (numerator_grad_2 - 2.0 * radial_grad * k4) / denominator;
+            return Distortion(
+                radial,
+                radial_grad,
+                radial_grad_2,
+                vec2<f32>(arguments.distortion[4], arguments.distortion[5]),
+            );
+        }
+        // ρ = θ_d(atan(r)) / r
+        //
+        // It is expanded into the series of r^2 around zero.
+        case CAMERA_MODEL_FISHEYE: {
+            if r2 < FISHEYE_SERIES_BOUND {
+                let c_1 = k1 - 1.0 / 3.0;
+                let c_2 = 0.2 - k1 + k2;
+                let c_3 = -1.0 / 7.0 + 14.0 / 15.0 * k1 - 5.0 / 3.0 * k2 + k3;
+                return Distortion(
+                    1.0 + r2 * (c_1 + r2 * (c_2 + r2 * c_3)),
+                    c_1 + r2 * (2.0 * c_2 + r2 * 3.0 * c_3),
+                    2.0 * c_2 + r2 * 6.0 * c_3,
+                    vec2<f32>(),
+                );
+            }
+            let r = sqrt(r2);
+            let angle = atan(r);
+            let angle2 = angle * angle;
+            let angle_distorted =
+                angle * (1.0 + angle2 * (k1 + angle2 * (k2 + angle2 * (k3 + angle2 * k4))));
+            let angle_distorted_grad =
+                1.0 + angle2 * (3.0 * k1 + angle2 * (5.0 * k2 + angle2 * (7.0 * k3 + angle2 * 9.0 * k4)));
+            let angle_distorted_grad_2 =
+                angle * (6.0 * k1 + angle2 * (20.0 * k2 + angle2 * (42.0 * k3 + angle2 * 72.0 * k4)));
+            // h = θ_d(atan(r)), h' = ∂h/∂r, h'' = ∂^2h/∂r^2
+            let angle_r = 1.0 / (1.0 + r2);
+            let h = angle_distorted;
+            let h_1 = angle_distorted_grad * angle_r;
+            let h_2 = (angle_distorted_grad_2 - 2.0 * r * angle_distorted_grad) * angle_r * angle_r;
+            // ρ = h / r, ∂ρ/∂r = (h' - ρ) / r
+            let radial = h / r;
+            let radial_grad_r = (h_1 - radial) / r;
+            return Distortion(
+                radial,
+                radial_grad_r / (2.0 * r),
+                (h_2 - 3.0 * radial_grad_r) / (4.0 * r2 * r),
+                vec2<f32>(),
+            );
+        }
+        default: {
+            return Distortion(1.0, 0.0, 0.0, vec2<f32>());
+        }
+    }
+}
+
+// Jd[2, 2] (Symmetric) = ∂Pn'/∂Pn = ρ * I + 2 * ∂ρ/∂r^2 * Pn * Pn^t + ∂Tn/∂Pn
+fn distort_jacobian(distortion: Distortion, position: vec2<f32>) -> mat2x2<f32> {
+    let x = position.x;
+    let y = position.y;
+    let p1 = distortion.tangential.x;
+    let p2 = distortion.tangential.y;
+    let xy = 2.0 * distortion.radial_grad * x * y + 2.0 * p1 * x + 2.0 * p2 * y;
+    return mat2x2<f32>(
+        distortion.radial + 2.0 * distortion.radial_grad * x * x + 2.0 * p1 * y + 6.0 * p2 * x,
+        xy,
+        xy,
+        distortion.radial + 2.0 * distortion.radial_grad * y * y + 6.0 * p1 * y + 2.0 * p2 * x,
+    );
+}
+
+// Pn'[2] = Pn[2] * ρ + [2 * p1 * x * y + p2 * (r^2 + 2 * x^2),
+//                       p1 * (r^2 + 2 * y^2) + 2 * p2 * x * y]
+fn distort_position(distortion: Distortion, position: vec2<f32>) -> vec2<f32> {
+    let x = position.x;
+    let y = position.y;
+    let p1 = distortion.tangential.x;
+    let p2 = distortion.tangential.y;
+    let r2 = dot(position, position);
+    return position * distortion.radial + vec2<f32>(
+        2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+        p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+    );
+}
+
+// ∂Pn/∂Pv[2, 3] of the equirectangular projection
+// ∂Pn/∂Pv[2, 3] = [[Pv.z / ρ^2,           0,          -Pv.x / ρ^2          ]
+//                  [-Pv.x * Pv.y / r^2 ρ, ρ / r^2,    -Pv.z * Pv.y / r^2 ρ ]]
+// ρ = √(Pv.x^2 + Pv.z^2)
+// r = |Pv|
+fn equirectangular_jacobian(position: vec3<f32>) -> mat3x2<f32> {
+    let x = position.x;
+    let y = position.y;
+    let z = position.z;
+    let rho2 = x * x + z * z;
+    let rho = sqrt(rho2);
+    let r2 = rho2 + y * y;
+    let r2_rho = r2 * rho;
+    return mat3x2<f32>(
+        z / rho2, -x * y / r2_rho,
+        0.0, rho / r2,
+        -x / rho2, -z * y / r2_rho,
+    );
+}
+
 fn mat_from_array_f32_3x3(a: array<f32, 9>) -> mat3x3<f32> {
     return mat3x3<f32>(
         a[0], a[1], a[2],
@@ -437,6 +723,12 @@ fn mat_from_array_f32_3x3(a: array<f32, 9>) -> mat3x3<f32> {
     );
 }
 
+// y = e^x / (1 + e^x)
+fn sigmoid_f32(x: f32) -> f32 {
+    let x_exp = exp(x);
+    return x_exp / (1.0 + x_exp);
+}
+
 fn vec_from_array_f32_3(a: array<f32, 3>) -> vec3<f32> {
     return vec3<f32>(a[0], a[1], a[2]);
 }
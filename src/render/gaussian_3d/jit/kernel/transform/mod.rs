@@ -9,36 +9,89 @@ use bytemuck::{bytes_of, Pod, Zeroable};
 #[repr(C, align(16))]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Arguments {
+    /// The [identifier](crate::render::view::CameraModel::id) of the camera model.
+    pub camera_model: u32,
     /// $ 0 \sim 3 $
     pub colors_sh_degree_max: u32,
+    /// $ d_{\text{far}} $
+    ///
+    /// It is the far clip plane of depth.
+    pub depth_far: f32,
+    /// $ d_{\text{near}} $
+    ///
+    /// It is the near clip plane of depth.
+    pub depth_near: f32,
+    /// The [distortion coefficients](crate::render::view::CameraModel::coefficients)
+    /// $ [k_1, k_2, k_3, k_4, p_1, p_2] $.
+    pub distortion: [f32; 6],
+    /// $ k $
+    ///
+    /// It is the factor of the radius to the standard deviation,
+    /// which is [`FACTOR_RADIUS`] by default.
+    pub factor_radius: f32,
+    /// $ c_f $
+    ///
+    /// It is the variance of the 2D filter,
+    /// which is [`FILTER_LOW_PASS`] or [`FILTER_MIP_2D`] by default.
+    pub filter_2d: f32,
+    /// $ c_{3d} $
+    ///
+    /// It is the variance scale of the 3D smoothing filter.
+    pub filter_3d_scale: f32,
+    /// $ 0 $ for the low-pass filter or $ 1 $ for the mip filter.
+    pub filter_mip_2d: u32,
     /// $ \text{fl}_x = \frac{\text{im}_x}{2 \tan\left(\frac{\text{fov}_x}{2}\right)} $
     pub focal_length_x: f32,
     /// $ \text{fl}_y = \frac{\text{im}_y}{2 \tan\left(\frac{\text{fov}_y}{2}\right)} $
     pub focal_length_y: f32,
-    /// $ \frac{\text{im}_x}{2} $
-    pub image_size_half_x: f32,
-    /// $ \frac{\text{im}_y}{2} $
-    pub image_size_half_y: f32,
     /// $ p $
     pub point_count: u32,
-    /// $ \frac{\text{im}_x}{\text{t}_x} $
+    /// $ \text{c}_x = \frac{\text{im}_x}{2} + \text{o}_x $
+    ///
+    /// $ \text{o}_x $ is the offset of the principal point.
+    pub principal_point_x: f32,
+    /// $ \text{c}_y = \frac{\text{im}_y}{2} + \text{o}_y $
     ///
-    /// $ \text{t}_x $ is the tile width.
+    /// $ \text{o}_y $ is the offset of the principal point.
+    pub principal_point_y: f32,
+    /// $ \lceil \frac{\text{im}_{x,0} + \text{im}_x^'}{\text{t}_x} \rceil
+    /// - \lfloor \frac{\text{im}_{x,0}}{\text{t}_x} \rfloor $
+    ///
+    /// $ \text{im}_x^' $ is the width of the rendering region,
+    /// and $ \text{t}_x $ is the tile width.
     pub tile_count_x: i32,
-    /// $ \frac{\text{im}_y}{\text{t}_y} $
+    /// $ \lceil \frac{\text{im}_{y,0} + \text{im}_y^'}{\text{t}_y} \rceil
+    /// - \lfloor \frac{\text{im}_{y,0}}{\text{t}_y} \rfloor $
     ///
-    /// $ \text{t}_y $ is the tile height.
+    /// $ \text{im}_y^' $ is the height of the rendering region,
+    /// and $ \text{t}_y $ is the tile height.
     pub tile_count_y: i32,
-    /// $ \tan\left(\frac{\text{fov}_x}{2}\right) \cdot (c_f + 1) $
+    /// $ \lfloor \frac{\text{im}_{x,0}}{\text{t}_x} \rfloor $
+    ///
+    /// $ \text{im}_{x,0} $ is the offset of the rendering region.
+    pub tile_offset_x: i32,
+    /// $ \lfloor \frac{\text{im}_{y,0}}{\text{t}_y} \rfloor $
     ///
-    /// $ c_f $ is [`FILTER_LOW_PASS`].
-    pub view_bound_x: f32,
-    /// $ \tan\left(\frac{\text{fov}_y}{2}\right) \cdot (c_f + 1) $
+    /// $ \text{im}_{y,0} $ is the offset of the rendering region.
+    pub tile_offset_y: i32,
+    /// $ \frac{\text{im}_x - \text{c}_x + \frac{\text{im}_x}{2} \cdot c_m}{\text{fl}_x} $
     ///
-    /// $ c_f $ is [`FILTER_LOW_PASS`].
-    pub view_bound_y: f32,
+    /// $ c_m $ is [`VIEW_BOUND_MARGIN`].
+    pub view_bound_x_max: f32,
+    /// $ - \frac{\text{c}_x + \frac{\text{im}_x}{2} \cdot c_m}{\text{fl}_x} $
+    ///
+    /// $ c_m $ is [`VIEW_BOUND_MARGIN`].
+    pub view_bound_x_min: f32,
+    /// $ \frac{\text{im}_y - \text{c}_y + \frac{\text{im}_y}{2} \cdot c_m}{\text{fl}_y} $
+    ///
+    /// $ c_m $ is [`VIEW_BOUND_MARGIN`].
+    pub view_bound_y_max: f32,
+    /// $ - \frac{\text{c}_y + \frac{\text{im}_y}{2} \cdot c_m}{\text{fl}_y} $
+    ///
+    /// $ c_m $ is [`VIEW_BOUND_MARGIN`].
+    pub view_bound_y_min: f32,
     /// Padding.
-    pub _padding_1: [u32; 2],
+    pub _padding_1: [u32; 1],
     /// $ V_p \in \mathbb{R}^3 $
     ///
     /// It is the position in world space.
@@ -60,6 +113,12 @@ pub struct Inputs<R: JitRuntime> {
     ///
     /// $ m $ is [`SH_COUNT_MAX`](crate::spherical_harmonics::SH_COUNT_MAX).
     pub colors_sh: JitTensor<R>,
+    /// $ F_{3d} \in \mathbb{R} $ of $ p $ points.
+    ///
+    /// It is the squared sampling interval of the 3D smoothing filter.
+    pub filters_3d: JitTensor<R>,
+    /// $ \alpha_{\text{inner}} \in \mathbb{R} $ of $ p $ points.
+    pub opacities: JitTensor<R>,
     /// $ P \in \mathbb{R}^3 $ of $ p $ points.
     pub positions_3d: JitTensor<R>,
     /// $ R \in \mathbb{R}^4 $ of $ p $ points.
@@ -83,6 +142,10 @@ pub struct Outputs<R: JitRuntime> {
     pub depths: JitTensor<R>,
     /// $ \neg \text{clamped}(C_{rgb}) \in \mathbb{R}^3 $ of $ p $ points.
     pub is_colors_rgb_3d_not_clamped: JitTensor<R>,
+    /// $ \alpha \in \mathbb{R} $ of $ p $ points.
+    ///
+    /// Opacity compensated by the filters.
+    pub opacities_3d: JitTensor<R>,
     /// $ [x_{\max}\ x_{\min}\ y_{\max}\ y_{\min}] $ of $ p $ points.
     ///
     /// $ x, y \in \mathbb{N} $ are the tile indices.
@@ -99,10 +162,21 @@ pub struct Outputs<R: JitRuntime> {
     pub tile_touched_counts: JitTensor<R>,
 }
 
+/// $ k $
+///
+/// $$ 0.9973 = \int_{-k}^{k} \exp(-\frac{x^2}{2}) dx $$
+pub const FACTOR_RADIUS: f64 = 2.9999771;
 /// $ c_f $
 pub const FILTER_LOW_PASS: f64 = 0.3;
+/// $ c_f $ of the 2D mip filter.
+pub const FILTER_MIP_2D: f64 = 0.1;
 /// Group size.
 pub const GROUP_SIZE: u32 = 256;
+/// $ c_m $
+///
+/// The margin of the view bounds relative to the half image size.
+/// The points slightly outside the image are kept, since they may still cover it.
+pub const VIEW_BOUND_MARGIN: f64 = 0.3;
 
 /// Transforming the points.
 ///
@@ -124,24 +198,27 @@ pub const GROUP_SIZE: u32 = 256;
 /// \end{bmatrix} $$
 ///
 /// 4. Compute the 3D covariance matrix from the rotation and scaling [$ S $](Inputs::scalings)
-///    using inverse single value decomposition (SVD):
+///    using inverse single value decomposition (SVD),
+///    and smooth it by the [3D filter](Inputs::filters_3d):
 /// $$ S_s = \begin{bmatrix}
 ///     S.x & 0 & 0
 /// \\\ 0 & S.y & 0
 /// \\\ 0 & 0 & S.z
 /// \end{bmatrix} $$
-/// $$ \Sigma = R_s S_s^2 R_s^T = (R_s S_s) (R_s S_s)^T \in \mathbb{R}^{3 \times 3} $$
+/// $$ \Sigma = R_s S_s^2 R_s^T + c_{3d} F_{3d} I
+/// = (R_s S_s) (R_s S_s)^T + c_{3d} F_{3d} I \in \mathbb{R}^{3 \times 3} $$
+/// $$ \rho_{3d} = \sqrt{\frac{|S_s^2|}{|S_s^2 + c_{3d} F_{3d} I|}} $$
 ///
 /// 5. Project the 3D position [$ P $](Inputs::positions_3d) from view space
 ///    onto [screen space](Outputs::positions_2d)
 ///    using focal length [$ \text{fl} $](Arguments::focal_length_x)
-///    and image size [$ \text{im} $](Arguments::image_size_half_x):
+///    and principal point [$ \text{c} $](Arguments::principal_point_x):
 /// $$ P_v^' = \begin{bmatrix}
 ///     \frac{P_v.x}{P_v.z} \cdot \text{fl}_x
 /// \\\ \frac{P_v.y}{P_v.z} \cdot \text{fl}_y
 /// \end{bmatrix} + \begin{bmatrix}
-///     \frac{\text{im}_x - 1}{2}
-/// \\\ \frac{\text{im}_y - 1}{2}
+///     \text{c}_x - \frac{1}{2}
+/// \\\ \text{c}_y - \frac{1}{2}
 /// \end{bmatrix} $$
 ///
 /// 6. Project the 3D covariance matrix from world space
@@ -156,14 +233,18 @@ pub const GROUP_SIZE: u32 = 256;
 /// \end{bmatrix} $$
 /// $$ \Sigma^' = J R_v \Sigma (J R_v)^T + C \in \mathbb{R}^{2 \times 2} $$
 ///
+///    The opacity is compensated for the [mip filter](Arguments::filter_mip_2d):
+/// $$ \rho_{2d} = \sqrt{\frac{|\Sigma^' - C|}{|\Sigma^'|}} $$
+///
 /// 7. Estimate the maximum radius [$ r $](Outputs::radii) from the 2D covariance
 ///    using eigenvalue decomposition:
 /// $$ |\Sigma^' - \lambda I| = 0 $$
 /// $$ \lambda = \frac{\Sigma_{11}^' + \Sigma_{22}^'}{2}
 /// \pm \sqrt{(\frac{\Sigma_{11}^' + \Sigma_{22}^'}{2})^2 - |\Sigma^'|} $$
-/// $$ 0.9973 = \int_{-k}^{k} \exp(-\frac{x^2}{2}) dx $$
 /// $$ r = k \sqrt{\lambda_{\max}} $$
 ///
+///    $ k $ is the [factor of the radius](Arguments::factor_radius).
+///
 /// 8. Compute the [tile bounds](Outputs::point_tile_bounds)
 ///    and touched tile count [$ T $](Outputs::tile_touched_counts)
 ///    using tile size [$ t $](Arguments::tile_count_x):
@@ -173,6 +254,9 @@ pub const GROUP_SIZE: u32 = 256;
 /// \text{clamp}(\frac{[(P_v^'.y - r)\ (P_v^'.y + r)]}{t_y}) $$
 /// $$ T = (x_{\max} - x_{\min}) \cdot (y_{\max} - y_{\min}) $$
 ///
+///    The bounds are clamped to the tiles of the rendering region
+///    and offset by [$ \frac{\text{im}_{x,0}}{\text{t}_x} $](Arguments::tile_offset_x).
+///
 /// 9. Compute the viewing direction in world space
 ///    using view position [$ V_p $](Arguments::view_position):
 /// $$ D_v = \frac{P - V_p}{| P - V_p |} \in \mathbb{R}^3 $$
@@ -181,6 +265,10 @@ pub const GROUP_SIZE: u32 = 256;
 ///     to [RGB](Outputs::colors_rgb_3d) space:
 /// $$ D = f(D_v) \in \mathbb{R}^m $$
 /// $$ C_{rgb} = D \cdot C_{sh} \in \mathbb{R}^3 $$
+///
+/// 11. Compensate the [opacity](Inputs::opacities)
+///     for the [filters](Outputs::opacities_3d):
+/// $$ \alpha = \text{sigmoid}(\alpha_{\text{inner}}) \cdot \rho_{3d} \cdot \rho_{2d} $$
 pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     arguments: Arguments,
     inputs: Inputs<R>,
@@ -200,6 +288,8 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     let depths = JitBackend::<R, F, I, B>::float_empty([point_count].into(), device);
     let is_colors_rgb_3d_not_clamped =
         JitBackend::<R, F, I, B>::float_empty([point_count, 3].into(), device);
+    let opacities_3d =
+        JitBackend::<R, F, I, B>::float_empty([point_count].into(), device);
     let point_tile_bounds =
         JitBackend::<R, F, I, B>::int_empty([point_count, 4].into(), device);
     let positions_2d =
@@ -227,6 +317,8 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         vec![
             client.create(bytes_of(&arguments)).binding(),
             inputs.colors_sh.handle.binding(),
+            inputs.filters_3d.handle.binding(),
+            inputs.opacities.handle.binding(),
             inputs.positions_3d.handle.binding(),
             inputs.rotations.handle.binding(),
             inputs.scalings.handle.binding(),
@@ -234,6 +326,7 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             conics.handle.to_owned().binding(),
             depths.handle.to_owned().binding(),
             is_colors_rgb_3d_not_clamped.handle.to_owned().binding(),
+            opacities_3d.handle.to_owned().binding(),
             point_tile_bounds.handle.to_owned().binding(),
             positions_2d.handle.to_owned().binding(),
             positions_3d_in_normalized.handle.to_owned().binding(),
@@ -248,6 +341,7 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         conics,
         depths,
         is_colors_rgb_3d_not_clamped,
+        opacities_3d,
         point_tile_bounds,
         positions_2d,
         positions_3d_in_normalized,
@@ -256,3 +350,57 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         tile_touched_counts,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn camera_model_render_ndarray_and_wgpu() {
+        let scene = scene_random::<NdArray>(48, &Default::default());
+        let camera_models = [
+            render::CameraModel::OpenCv {
+                k1: -0.15,
+                k2: 0.03,
+                k3: 0.0,
+                k4: 0.0,
+                p1: 0.002,
+                p2: -0.001,
+            },
+            render::CameraModel::Fisheye {
+                k1: 0.05,
+                k2: -0.01,
+                k3: 0.0,
+                k4: 0.0,
+            },
+            render::CameraModel::Orthographic,
+            render::CameraModel::Equirectangular,
+        ];
+        for camera_model in camera_models {
+            let view = render::View {
+                camera_model,
+                ..VIEW_RANDOM
+            };
+            assert_render_ndarray_and_wgpu(&scene, &view, &Default::default());
+        }
+    }
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn options_render_ndarray_and_wgpu() {
+        let mut scene = scene_random::<NdArray>(48, &Default::default());
+        scene.update_filters_3d([&VIEW_RANDOM], &Default::default());
+        let options = [
+            Gaussian3dRenderOptions::default()
+                .with_depth_far(6.5)
+                .with_depth_near(4.0),
+            Gaussian3dRenderOptions::default()
+                .with_filter_3d_scale(0.2)
+                .with_filter_mip_2d(true),
+        ];
+        for options in options {
+            assert_render_ndarray_and_wgpu(&scene, &VIEW_RANDOM, &options);
+        }
+    }
+}
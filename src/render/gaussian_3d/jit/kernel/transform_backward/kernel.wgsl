@@ -1,6 +1,16 @@
 struct Arguments {
+    // (0: Pinhole, 1: OpenCV, 2: Fisheye, 3: Orthographic, 4: Equirectangular)
+    camera_model: u32,
     // (0 ~ 3)
     colors_sh_degree_max: u32,
+    // [k1, k2, k3, k4, p1, p2]
+    distortion: array<f32, 6>,
+    // C_f
+    filter_2d: f32,
+    // C_3d
+    filter_3d_scale: f32,
+    // (0: Low-pass filter, 1: Mip filter)
+    filter_mip_2d: u32,
     // F_x <- I_x / tan(Fov_x / 2) / 2
     focal_length_x: f32,
     // F_y <- I_y / tan(Fov_y / 2) / 2
@@ -11,10 +21,14 @@ struct Arguments {
     image_size_half_y: f32,
     // P
     point_count: u32,
-    // tan(Fov_x / 2) * (C_f + 1)
-    view_bound_x: f32,
-    // tan(Fov_y / 2) * (C_f + 1)
-    view_bound_y: f32,
+    // (I_x - C_x + I_x / 2 * C_f) / F_x
+    view_bound_x_max: f32,
+    // -(C_x + I_x / 2 * C_f) / F_x
+    view_bound_x_min: f32,
+    // (I_y - C_y + I_y / 2 * C_f) / F_y
+    view_bound_y_max: f32,
+    // -(C_y + I_y / 2 * C_f) / F_y
+    view_bound_y_min: f32,
     // V[3]
     view_position: vec3<f32>,
     // Rv[3, 3]
@@ -23,6 +37,19 @@ struct Arguments {
     view_translation: vec3<f32>,
 }
 
+// The lens distortion at a normalized position Pn[2]
+// Pn'[2] = Pn[2] * ρ(r^2) + Tn(Pn)
+struct Distortion {
+    // ρ
+    radial: f32,
+    // ∂ρ/∂r^2
+    radial_grad: f32,
+    // ∂^2ρ/∂(r^2)^2
+    radial_grad_2: f32,
+    // [p1, p2]
+    tangential: vec2<f32>,
+}
+
 @group(0) @binding(0)
 var<storage, read_write> arguments: Arguments;
 // [P, 3]
@@ -40,46 +67,73 @@ var<storage, read_write> conics_grad: array<array<f32, 3>>;
 // [P]
 @group(0) @binding(5)
 var<storage, read_write> depths: array<f32>;
-// [P, 3] (0.0, 1.0)
+// [P]
 @group(0) @binding(6)
+var<storage, read_write> depths_grad: array<f32>;
+// [P, 1]
+@group(0) @binding(7)
+var<storage, read_write> filters_3d: array<f32>;
+// [P, 3] (0.0, 1.0)
+@group(0) @binding(8)
 var<storage, read_write> is_colors_rgb_3d_not_clamped: array<array<f32, 3>>;
+// [P, 1] (Inner)
+@group(0) @binding(9)
+var<storage, read_write> opacities: array<f32>;
+// [P] (Outer)
+@group(0) @binding(10)
+var<storage, read_write> opacities_3d_grad: array<f32>;
 // [P, 2]
-@group(0) @binding(7)
+@group(0) @binding(11)
 var<storage, read_write> positions_2d_grad: array<vec2<f32>>;
+// [P, 2]
+@group(0) @binding(12)
+var<storage, read_write> positions_2d_grad_abs: array<vec2<f32>>;
 // [P, 3]
-@group(0) @binding(8)
+@group(0) @binding(13)
 var<storage, read_write> positions_3d: array<array<f32, 3>>;
 // [P, 2]
-@group(0) @binding(9)
+@group(0) @binding(14)
 var<storage, read_write> positions_3d_in_normalized: array<vec2<f32>>;
 // [P]
-@group(0) @binding(10)
+@group(0) @binding(15)
 var<storage, read_write> radii: array<u32>;
 // [P, 4] (x, y, z, w) (Inner)
-@group(0) @binding(11)
+@group(0) @binding(16)
 var<storage, read_write> rotations: array<vec4<f32>>;
 // [P, 3, 3]
-@group(0) @binding(12)
+@group(0) @binding(17)
 var<storage, read_write> rotations_matrix: array<array<f32, 9>>;
 // [P, 3] (Inner)
-@group(0) @binding(13)
+@group(0) @binding(18)
 var<storage, read_write> scalings: array<array<f32, 3>>;
 
 // [P, 16, 3]
-@group(0) @binding(14)
+@group(0) @binding(19)
 var<storage, read_write> colors_sh_grad: array<array<array<f32, 3>, 16>>;
+// [P, 2]
+@group(0) @binding(20)
+var<storage, read_write> focal_lengths_grad: array<vec2<f32>>;
+// [P, 1] (Inner)
+@group(0) @binding(21)
+var<storage, read_write> opacities_grad: array<f32>;
 // [P]
-@group(0) @binding(15)
+@group(0) @binding(22)
+var<storage, read_write> positions_2d_grad_abs_norm: array<f32>;
+// [P]
+@group(0) @binding(23)
 var<storage, read_write> positions_2d_grad_norm: array<f32>;
 // [P, 3]
-@group(0) @binding(16)
+@group(0) @binding(24)
 var<storage, read_write> positions_3d_grad: array<array<f32, 3>>;
 // [P, 4] (x, y, z, w) (Inner)
-@group(0) @binding(17)
+@group(0) @binding(25)
 var<storage, read_write> rotations_grad: array<vec4<f32>>;
 // [P, 3] (Inner)
-@group(0) @binding(18)
+@group(0) @binding(26)
 var<storage, read_write> scalings_grad: array<array<f32, 3>>;
+// [P, 6] (ρ, φ)
+@group(0) @binding(27)
+var<storage, read_write> view_transforms_grad: array<array<f32, 6>>;
 
 // The real coefficients of orthonormalized spherical harmonics from degree 0 to 3
 const SH_C_0: array<f32, 1> = array<f32, 1>(
@@ -107,6 +161,14 @@ const SH_C_3: array<f32, 7> = array<f32, 7>(
     -0.5900436,
 );
 
+// The camera models
+const CAMERA_MODEL_EQUIRECTANGULAR: u32 = 4;
+const CAMERA_MODEL_FISHEYE: u32 = 2;
+const CAMERA_MODEL_OPENCV: u32 = 1;
+const CAMERA_MODEL_ORTHOGRAPHIC: u32 = 3;
+const CAMERA_MODEL_PINHOLE: u32 = 0;
+// The bound of r^2 to expand the fisheye distortion into series
+const FISHEYE_SERIES_BOUND: f32 = 1e-2;
 const GROUP_SIZE: u32 = GROUP_SIZE_X * GROUP_SIZE_Y;
 const GROUP_SIZE_X: u32 = 16;
 const GROUP_SIZE_Y: u32 = 16;
@@ -140,12 +202,13 @@ fn main(
     let conic = mat_sym_from_array_f32_3(conics[index]);
     let conic_grad = mat_sym_from_array_f32_3(conics_grad[index]);
     // ∂L/∂Σ'[2, 2]
-    let covariance_2d_grad = -1.0 * conic * conic_grad * conic;
+    var covariance_2d_grad = -1.0 * conic * conic_grad * conic;
 
     // Computing the 3D covariance matrix from rotation and scaling
     // 
     // RS[3, 3] = R[3, 3] * S[3, 3]
-    // Σ[3, 3] (Symmetric) = RS[3, 3] * RS^t[3, 3]
+    // Σ[3, 3] (Symmetric) = RS[3, 3] * RS^t[3, 3] + C_3d * F_3d * I[3, 3]
+    // ρ_3d = √(|S^2[3, 3]| / |S^2[3, 3] + C_3d * F_3d * I[3, 3]|)
     // 
     // S is diagonal
 
@@ -157,37 +220,130 @@ fn main(
         rotation_matrix[1] * scaling[1],
         rotation_matrix[2] * scaling[2],
     );
-    let covariance_3d = rotation_scaling * transpose(rotation_scaling);
+    let filter_3d = arguments.filter_3d_scale * filters_3d[index];
+    let covariance_3d =
+        rotation_scaling * transpose(rotation_scaling) + mat3x3<f32>(
+            filter_3d, 0.0, 0.0,
+            0.0, filter_3d, 0.0,
+            0.0, 0.0, filter_3d,
+        );
+    let scaling_p2 = scaling * scaling;
+    var opacity_compensation_3d = 1.0;
+    if filter_3d != 0.0 {
+        let ratio = scaling_p2 / (scaling_p2 + filter_3d);
+        opacity_compensation_3d = sqrt(ratio.x * ratio.y * ratio.z);
+    }
 
     // Projecting the 3D covariance matrix into 2D covariance matrix
     // 
+    // K[2, 2] = F[2, 2] * Jd[2, 2]
+    // J[2, 3] = K[2, 2] * [[1, 0, -Pn.x], [0, 1, -Pn.y]] / Pv.z
     // T[2, 3] = J[2, 3] * Rv[3, 3]
-    // Σ'[2, 2] (Symmetric) = T[2, 3] * Σ[3, 3] * T^t[3, 2] + F[2, 2]
+    // Σ'[2, 2] (Symmetric) = T[2, 3] * Σ[3, 3] * T^t[3, 2] + C_f * I[2, 2]
     //
-    // J = [[f.x / Pv.z, 0,          -f.x * Pv.x / Pv.z^2]
-    //      [0,          f.y / Pv.z, -f.y * Pv.y / Pv.z^2]]
-    // F = [[C_f, 0  ]
-    //      [0,   C_f]]
+    // J[2, 3] = F[2, 2] * ∂Pn/∂Pv[2, 3] for the equirectangular camera model
     //
-    // Pv.x and Pv.y are the clamped
+    // Pn.x and Pn.y are the clamped
 
     let depth = depths[index];
+    let is_equirectangular = arguments.camera_model == CAMERA_MODEL_EQUIRECTANGULAR;
+    let is_orthographic = arguments.camera_model == CAMERA_MODEL_ORTHOGRAPHIC;
+    let view_rotation = arguments.view_rotation;
+    let position_3d = vec_from_array_f32_3(positions_3d[index]);
+    // Pv[3]
+    let position_3d_in_view = view_rotation * position_3d + arguments.view_translation;
+    // ∂Pn/∂Pv[2, 3]
+    var equirectangular_projection = mat3x2<f32>();
+    if is_equirectangular {
+        equirectangular_projection = equirectangular_jacobian(position_3d_in_view);
+    }
     let focal_length = vec2<f32>(arguments.focal_length_x, arguments.focal_length_y);
-    let focal_length_normalized = focal_length / depth;
-    let view_bound = vec2<f32>(arguments.view_bound_x, arguments.view_bound_y);
-    let position_3d_in_normalized = positions_3d_in_normalized[index];
     // [Pv.x / Pv.z, Pv.y / Pv.z]
+    let position_3d_in_normalized = positions_3d_in_normalized[index];
     let position_3d_in_normalized_clamped = clamp(
         position_3d_in_normalized,
-        -view_bound,
-        view_bound,
+        vec2<f32>(arguments.view_bound_x_min, arguments.view_bound_y_min),
+        vec2<f32>(arguments.view_bound_x_max, arguments.view_bound_y_max),
     );
-    let view_rotation = arguments.view_rotation;
-    let projection_2d = mat3x2<f32>(
-        vec2<f32>(focal_length_normalized.x, 0.0),
-        vec2<f32>(0.0, focal_length_normalized.y),
-        -focal_length_normalized * position_3d_in_normalized_clamped,
-    ) * view_rotation;
+    var depth_projected = depth;
+    var position_3d_in_normalized_projected = position_3d_in_normalized_clamped;
+    if is_orthographic {
+        depth_projected = 1.0;
+        position_3d_in_normalized_projected = vec2<f32>();
+    }
+    let focal_length_normalized = focal_length / depth_projected;
+    let distortion = distort(position_3d_in_normalized_clamped);
+    let distortion_jacobian = distort_jacobian(distortion, position_3d_in_normalized_clamped);
+    let projection_2d_scaled = mat2x2<f32>(
+        focal_length_normalized * distortion_jacobian[0],
+        focal_length_normalized * distortion_jacobian[1],
+    );
+    var projection_2d_left = mat3x2<f32>(
+        projection_2d_scaled[0],
+        projection_2d_scaled[1],
+        -(projection_2d_scaled * position_3d_in_normalized_projected),
+    );
+    if is_equirectangular {
+        projection_2d_left = mat3x2<f32>(
+            focal_length * equirectangular_projection[0],
+            focal_length * equirectangular_projection[1],
+            focal_length * equirectangular_projection[2],
+        );
+    }
+    let projection_2d = projection_2d_left * view_rotation;
+
+    // Computing the opacity compensation of the 2D mip filter
+    // Σ'u[2, 2] (Symmetric) = T[2, 3] * Σ[3, 3] * T^t[3, 2]
+    // Σ'[2, 2] (Symmetric) = Σ'u[2, 2] + C_f * I[2, 2]
+    // ρ_2d = √(|Σ'u| / |Σ'|)
+    //
+    // ∂ρ_2d/∂Σ'u[2, 2] = (adj(Σ'u)[2, 2] - ρ_2d^2 * adj(Σ')[2, 2]) / |Σ'| / ρ_2d / 2
+
+    var opacity_compensation_2d = 1.0;
+    var opacity_compensation_2d_to_covariance_2d_grad = mat2x2<f32>();
+    if arguments.filter_mip_2d != 0u {
+        let covariance_2d_unfiltered =
+            projection_2d * covariance_3d * transpose(projection_2d);
+        let a = covariance_2d_unfiltered[0][0];
+        let b = covariance_2d_unfiltered[0][1];
+        let c = covariance_2d_unfiltered[1][1];
+        let a_filtered = a + arguments.filter_2d;
+        let c_filtered = c + arguments.filter_2d;
+        let covariance_2d_det = a_filtered * c_filtered - b * b;
+        let covariance_2d_ratio = (a * c - b * b) / covariance_2d_det;
+        if covariance_2d_ratio > 0.0 {
+            opacity_compensation_2d = sqrt(covariance_2d_ratio);
+            let k = 0.5 / opacity_compensation_2d / covariance_2d_det;
+            let b_grad = (covariance_2d_ratio - 1.0) * b * k;
+            opacity_compensation_2d_to_covariance_2d_grad = mat2x2<f32>(
+                (c - covariance_2d_ratio * c_filtered) * k, b_grad,
+                b_grad, (a - covariance_2d_ratio * a_filtered) * k,
+            );
+        } else {
+            opacity_compensation_2d = 0.0;
+        }
+    }
+
+    // Computing the gradients
+    //
+    // α[1] = sigmoid(α_inner[1]) * ρ_3d * ρ_2d
+    //
+    // ∂L/∂α_inner = ∂L/∂α * sigmoid'(α_inner) * ρ_3d * ρ_2d
+    // ∂L/∂ρ_3d = ∂L/∂α * sigmoid(α_inner) * ρ_2d
+    // ∂L/∂ρ_2d = ∂L/∂α * sigmoid(α_inner) * ρ_3d
+    // ∂L/∂Σ'u[2, 2] = ∂L/∂Σ'[2, 2] + ∂L/∂ρ_2d * ∂ρ_2d/∂Σ'u[2, 2]
+
+    let opacity_3d_grad = opacities_3d_grad[index];
+    let opacity = sigmoid_f32(opacities[index]);
+    let opacity_grad =
+        opacity_3d_grad * sigmoid_grad_f32(opacity) *
+        opacity_compensation_3d * opacity_compensation_2d;
+    let opacity_compensation_3d_grad =
+        opacity_3d_grad * opacity * opacity_compensation_2d;
+    let opacity_compensation_2d_grad =
+        opacity_3d_grad * opacity * opacity_compensation_3d;
+    covariance_2d_grad +=
+        opacity_compensation_2d_grad * opacity_compensation_2d_to_covariance_2d_grad;
 
     // Computing the gradients
     //
@@ -217,36 +373,59 @@ fn main(
 
     // Computing the gradients
     //
-    // ∂L/∂Pv[3] = [∂L/∂Pv.x, ∂L/∂Pv.y, ∂L/∂Pv.z]
-    //           = [〈∂L/∂J, ∂J/∂Pv.x〉,〈∂L/∂J, ∂J/∂Pv.y〉,〈∂L/∂J, ∂J/∂Pv.z〉]
-    // ∂J/∂Pv.x[2, 3] = [[0, 0, -f.x / Pv.z^2]
-    //                   [0, 0,  0           ]]
-    // ∂J/∂Pv.x[2, 3] = [[0, 0,  0           ]
-    //                   [0, 0, -f.y / Pv.z^2]]
-    // ∂J/∂Pv.z[2, 3] = [[-f.x / Pv.z^2,  0,            2 * f.x * Pv.x / Pv.z^3]
-    //                   [ 0,            -f.y / Pv.z^2, 2 * f.y * Pv.y / Pv.z^3]]
+    // ∂L/∂Jd[2, 2] = F[2, 2] * ∂L/∂J[2, 3] * [[1, 0, -Pn.x], [0, 1, -Pn.y]]^t / Pv.z
+    // ∂L/∂Pn[2] = -K^t[2, 2] * ∂L/∂J[2, 3].2 / Pv.z +〈∂L/∂Jd, ∂Jd/∂Pn〉
+    // ∂L/∂Pv[3] = [∂L/∂Pn.x / Pv.z,
+    //              ∂L/∂Pn.y / Pv.z,
+    //              -〈∂L/∂J, J〉/ Pv.z -〈∂L/∂Pn, Pn〉/ Pv.z]
 
     let is_position_3d_in_normalized_not_clamped = vec2<f32>(
         position_3d_in_normalized == position_3d_in_normalized_clamped
     );
-    // [f.x / Pv.z^2, f.y / Pv.z^2]
-    let focal_length_normalized_p2 = focal_length_normalized / depth;
-    // [f.x / Pv.z^2 * (∂L/∂J).2,0, f.y / Pv.z^2 * (∂L/∂J).2,1]
-    let focal_length_normalized_p2_projection_2d_left_grad_2 =
-        focal_length_normalized_p2 * projection_2d_left_grad[2];
-    // ∂L/∂Pv[3]
-    let position_3d_in_view_grad = vec3<f32>(
-        - is_position_3d_in_normalized_not_clamped.x *
-          focal_length_normalized_p2_projection_2d_left_grad_2.x,
-        - is_position_3d_in_normalized_not_clamped.y *
-          focal_length_normalized_p2_projection_2d_left_grad_2.y,
-        - focal_length_normalized_p2.x * projection_2d_left_grad[0][0]
-        - focal_length_normalized_p2.y * projection_2d_left_grad[1][1]
-        + 2 * dot(
-            position_3d_in_normalized_clamped,
-            focal_length_normalized_p2_projection_2d_left_grad_2,
+    // ∂L/∂Jd[2, 2]
+    let distortion_jacobian_grad = mat2x2<f32>(
+        focal_length_normalized * (
+            projection_2d_left_grad[0] -
+            position_3d_in_normalized_clamped.x * projection_2d_left_grad[2]
+        ),
+        focal_length_normalized * (
+            projection_2d_left_grad[1] -
+            position_3d_in_normalized_clamped.y * projection_2d_left_grad[2]
         ),
     );
+    // ∂L/∂Pn[2]
+    let position_3d_in_normalized_grad =
+        distort_jacobian_grad(
+            distortion,
+            position_3d_in_normalized_clamped,
+            distortion_jacobian_grad,
+        ) - projection_2d_left_grad[2] * projection_2d_scaled;
+    //〈∂L/∂J, J〉
+    let projection_2d_left_dot_grad =
+        dot(projection_2d_left[0], projection_2d_left_grad[0]) +
+        dot(projection_2d_left[1], projection_2d_left_grad[1]) +
+        dot(projection_2d_left[2], projection_2d_left_grad[2]);
+    // ∂L/∂Pv[3]
+    //
+    // J is constant for the orthographic camera model
+    var position_3d_in_view_grad = vec3<f32>(
+        is_position_3d_in_normalized_not_clamped * position_3d_in_normalized_grad,
+        - projection_2d_left_dot_grad
+        - dot(position_3d_in_normalized_clamped, position_3d_in_normalized_grad),
+    ) / depth;
+    if is_orthographic {
+        position_3d_in_view_grad = vec3<f32>();
+    }
+    if is_equirectangular {
+        position_3d_in_view_grad = equirectangular_jacobian_grad(
+            position_3d_in_view,
+            mat3x2<f32>(
+                focal_length * projection_2d_left_grad[0],
+                focal_length * projection_2d_left_grad[1],
+                focal_length * projection_2d_left_grad[2],
+            ),
+        );
+    }
 
     // Computing the gradients
     //
@@ -293,12 +472,24 @@ fn main(
         rotation_scaling_grad[2] * scaling[2],
     );
     // ∂L/∂S[3, 3] (Inner)
-    let scaling_grad = exp_grad_vec_f32_3(scaling) * vec3<f32>(
+    var scaling_grad = exp_grad_vec_f32_3(scaling) * vec3<f32>(
         dot(rotation_matrix[0], rotation_scaling_grad[0]),
         dot(rotation_matrix[1], rotation_scaling_grad[1]),
         dot(rotation_matrix[2], rotation_scaling_grad[2]),
     );
 
+    // Computing the gradients
+    //
+    // ρ_3d = √(Π(S^2) / Π(S^2 + C_3d * F_3d))
+    //
+    // ∂L/∂S[3] (Inner) += ∂L/∂ρ_3d * ρ_3d * C_3d * F_3d / (S^2[3] + C_3d * F_3d)
+
+    if filter_3d != 0.0 {
+        scaling_grad +=
+            opacity_compensation_3d_grad * opacity_compensation_3d * filter_3d /
+            (scaling_p2 + filter_3d);
+    }
+
     // Computing the gradients
     //
     // ∂L/∂Q[4] = [∂L/∂Q.x, ∂L/∂Q.y, ∂L/∂Q.z, ∂L/∂Q.w]
@@ -343,23 +534,50 @@ fn main(
 
     // Computing the gradients
     //
-    // ∂L/∂Pw[1, 3] = ∂L/∂Pv'[1, 2] * ∂Pv'/∂Pv[2, 3] * ∂Pv/∂Pw[3, 3]
+    // ∂L/∂Pw[1, 3] = ∂L/∂Pv'[1, 2] * ∂Pv'/∂Pn[2, 2] * ∂Pn/∂Pv[2, 3] * ∂Pv/∂Pw[3, 3]
     // ∂Pv/∂Pw[3, 3] = Rv
-    // ∂Pv'/∂Pv[2, 3] = [[f.x / Pv.z, 0,          -f.x * Pv.x / Pv.z^2]
-    //                   [0,          f.y / Pv.z, -f.y * Pv.y / Pv.z^2]]
+    // ∂Pn/∂Pv[2, 3] = [[1 / Pv.z, 0,        -Pv.x / Pv.z^2]
+    //                  [0,        1 / Pv.z, -Pv.y / Pv.z^2]]
 
     // ∂L/∂Pv'[1, 2]
     let position_2d_grad = positions_2d_grad[index];
-    // ∂Pv'/∂Pv[2, 3]
-    let position_2d_to_3d_in_view_grad = mat3x2<f32>(
-        vec2<f32>(focal_length_normalized.x, 0.0),
-        vec2<f32>(0.0, focal_length_normalized.y),
-        -focal_length_normalized * position_3d_in_normalized,
-    );
+    // ∂L/∂Pn[1, 2] = ∂L/∂Pv'[1, 2] * F[2, 2] * Jd[2, 2]
+    let position_2d_to_normalized_grad =
+        position_2d_grad * focal_length *
+        distort_jacobian(distortion, position_3d_in_normalized);
+    // ∂L/∂Pv[1, 3]
+    //
+    // ∂Pn/∂Pv[2, 3] = [[1, 0, 0], [0, 1, 0]] for the orthographic camera model
+    var position_2d_to_3d_in_view_grad = vec3<f32>(
+        position_2d_to_normalized_grad,
+        -dot(position_2d_to_normalized_grad, position_3d_in_normalized),
+    ) / depth;
+    if is_orthographic {
+        position_2d_to_3d_in_view_grad = vec3<f32>(position_2d_to_normalized_grad, 0.0);
+    }
+    if is_equirectangular {
+        position_2d_to_3d_in_view_grad =
+            position_2d_to_normalized_grad * equirectangular_projection;
+    }
+    // ∂L/∂Pw[1, 3]
+    position_3d_grad += position_2d_to_3d_in_view_grad * view_rotation;
+
+    // Computing the gradients
+    //
+    // ∂L/∂Pw[1, 3] = ∂L/∂D * ∂D/∂Pw[1, 3]
+    // ∂D/∂Pw[1, 3] = [0, 0, 1] * Rv[3, 3]
+    //
+    // ∂D/∂Pw[1, 3] = Pv^t[1, 3] / |Pv| * Rv[3, 3] for the equirectangular camera model
+
+    // ∂L/∂D
+    let depth_grad = depths_grad[index];
+    // ∂L/∂Pv[1, 3]
+    var depth_to_3d_in_view_grad = vec3<f32>(0.0, 0.0, depth_grad);
+    if is_equirectangular {
+        depth_to_3d_in_view_grad = depth_grad * position_3d_in_view / depth;
+    }
     // ∂L/∂Pw[1, 3]
-    position_3d_grad +=
-        position_2d_grad * position_2d_to_3d_in_view_grad *
-        view_rotation;
+    position_3d_grad += depth_to_3d_in_view_grad * view_rotation;
 
     // Computing the norm of 2D positions gradient
     // |∂L/∂Pv'[1, 2] * [I_x / 2, I_y / 2]|
@@ -369,12 +587,26 @@ fn main(
         vec2<f32>(arguments.image_size_half_x, arguments.image_size_half_y)
     );
 
+    // Computing the norm of 2D positions absolute gradient
+    // ||∂L/∂Pv'|[1, 2] * [I_x / 2, I_y / 2]|
+    //
+    // It is zero unless the absolute gradient is accumulated in rasterization
+
+    let position_2d_grad_abs_norm = length(
+        positions_2d_grad_abs[index] *
+        vec2<f32>(arguments.image_size_half_x, arguments.image_size_half_y)
+    );
+
     // Computing the view direction in world space
     // Ov[3] = Pw[3] - V[3]
     // Dv[3] = Ov[3] / |Ov|
+    //
+    // Ov[3] = Rv^t[3, 3] * [0, 0, 1] for the orthographic camera model
 
-    let position_3d = vec_from_array_f32_3(positions_3d[index]);
-    let view_offset = position_3d - arguments.view_position;
+    var view_offset = position_3d - arguments.view_position;
+    if is_orthographic {
+        view_offset = vec3<f32>(0.0, 0.0, 1.0) * view_rotation;
+    }
     let view_direction = normalize(view_offset);
     var vd = vec3<f32>();
     var vd_x = vec3<f32>();
@@ -516,8 +748,62 @@ fn main(
 
     // ∂L/∂Dv[1, 3]
     let view_direction_grad = color_rgb_3d_grad * color_rgb_3d_to_view_direction_grad;
+    // ∂L/∂Ov[1, 3]
+    let view_offset_grad = view_direction_grad * normalize_grad_vec_f32_3(view_offset);
     // ∂L/∂Pw[1, 3]
-    position_3d_grad += view_direction_grad * normalize_grad_vec_f32_3(view_offset);
+    //
+    // Dv is constant for the orthographic camera model
+    if !is_orthographic {
+        position_3d_grad += view_offset_grad;
+    }
+
+    // Computing the gradients of the view transform in tangent space
+    //
+    // Tv'[4, 4] = exp(ξ) * Tv[4, 4], where ξ[6] = [ρ[3], φ[3]]
+    // Pv'[3] = Pv[3] + ρ[3] + φ[3] × Pv[3]
+    // T'[2, 3] = J[2, 3] * (I + [φ]x)[3, 3] * Rv[3, 3]
+    // V'[3] = V[3] - Rv^t[3, 3] * ρ[3]
+    //
+    // ∂L/∂ρ[3] = ∂L/∂Pv[3] + Rv[3, 3] * ∂L/∂Ov[3]
+    // ∂L/∂φ[3] = Pv[3] × ∂L/∂Pv[3] + Σ(∂L/∂J[2, 3] × J[2, 3])
+    //
+    // ∂L/∂ρ[3] = ∂L/∂Pv[3] for the orthographic camera model
+    // ∂L/∂φ[3] += (Rv[3, 3] * ∂L/∂Ov[3]) × [0, 0, 1] for the orthographic camera model
+
+    // ∂L/∂Pv[3]
+    let position_3d_in_view_grad_total =
+        position_3d_in_view_grad +
+        position_2d_to_3d_in_view_grad +
+        depth_to_3d_in_view_grad;
+    // Rv[3, 3] * ∂L/∂Ov[3]
+    let view_offset_grad_in_view = view_rotation * view_offset_grad;
+    // J^t[3, 2]
+    let projection_2d_left_t = transpose(projection_2d_left);
+    // ∂L/∂J^t[3, 2]
+    let projection_2d_left_grad_t = transpose(projection_2d_left_grad);
+    var view_translation_grad = position_3d_in_view_grad_total + view_offset_grad_in_view;
+    var view_rotation_grad =
+        cross(position_3d_in_view, position_3d_in_view_grad_total) +
+        cross(projection_2d_left_grad_t[0], projection_2d_left_t[0]) +
+        cross(projection_2d_left_grad_t[1], projection_2d_left_t[1]);
+    if is_orthographic {
+        view_translation_grad = position_3d_in_view_grad_total;
+        view_rotation_grad += cross(view_offset_grad_in_view, vec3<f32>(0.0, 0.0, 1.0));
+    }
+
+    // Computing the gradients of the focal lengths
+    //
+    // ∂L/∂F[2] = ∂L/∂Pv'[2] * Pn'[2] +〈∂L/∂J[2, 3], J[2, 3]〉[2] / F[2]
+    //
+    // The wrapping of the equirectangular camera model is ignored
+
+    let position_3d_in_distorted = distort_position(distortion, position_3d_in_normalized);
+    let focal_length_grad =
+        position_2d_grad * position_3d_in_distorted +
+        vec2<f32>(
+            dot(projection_2d_left_grad_t[0], projection_2d_left_t[0]),
+            dot(projection_2d_left_grad_t[1], projection_2d_left_t[1]),
+        ) / focal_length;
 
     // Specifying the results
 
@@ -540,6 +826,12 @@ fn main(
         array_from_vec_f32_3(color_sh_grad[14]),
         array_from_vec_f32_3(color_sh_grad[15]),
     );
+    // [P, 2]
+    focal_lengths_grad[index] = focal_length_grad;
+    // [P, 1]
+    opacities_grad[index] = opacity_grad;
+    // [P]
+    positions_2d_grad_abs_norm[index] = position_2d_grad_abs_norm;
     // [P]
     positions_2d_grad_norm[index] = position_2d_grad_norm;
     // [P, 3]
@@ -548,6 +840,15 @@ fn main(
     rotations_grad[index] = rotation_grad;
     // [P, 3]
     scalings_grad[index] = array_from_vec_f32_3(scaling_grad);
+    // [P, 6]
+    view_transforms_grad[index] = array<f32, 6>(
+        view_translation_grad[0],
+        view_translation_grad[1],
+        view_translation_grad[2],
+        view_rotation_grad[0],
+        view_rotation_grad[1],
+        view_rotation_grad[2],
+    );
 }
 
 fn array_from_mat_f32_3x3(m: mat3x3<f32>) -> array<f32, 9> {
@@ -562,6 +863,187 @@ fn array_from_vec_f32_3(v: vec3<f32>) -> array<f32, 3> {
     return array<f32, 3>(v[0], v[1], v[2]);
 }
 
+// Evaluating the lens distortion at the normalized position Pn[2]
+fn distort(position: vec2<f32>) -> Distortion {
+    let k1 = arguments.distortion[0];
+    let k2 = arguments.distortion[1];
+    let k3 = arguments.distortion[2];
+    let k4 = arguments.distortion[3];
+    let r2 = dot(position, position);
+
+    switch arguments.camera_model {
+        // ρ = (1 + k1 * r^2 + k2 * r^4 + k3 * r^6) / (1 + k4 * r^2)
+        case CAMERA_MODEL_OPENCV: {
+            let numerator = 1.0 + r2 * (k1 + r2 * (k2 + r2 * k3));
+            let numerator_grad = k1 + r2 * (2.0 * k2 + r2 * 3.0 * k3);
+            let numerator_grad_2 = 2.0 * k2 + r2 * 6.0 * k3;
+            let denominator = 1.0 + r2 * k4;
+            let radial = numerator / denominator;
+            let radial_grad = (numerator_grad - radial * k4) / denominator;
+            let radial_grad_2 = (numerator_grad_2 - 2.0 * radial_grad * k4) / denominator;
+            return Distortion(
+                radial,
+                radial_grad,
+                radial_grad_2,
+                vec2<f32>(arguments.distortion[4], arguments.distortion[5]),
+            );
+        }
+        // ρ = θ_d(atan(r)) / r
+        //
+        // It is expanded into the series of r^2 around zero.
+        case CAMERA_MODEL_FISHEYE: {
+            if r2 < FISHEYE_SERIES_BOUND {
+                let c_1 = k1 - 1.0 / 3.0;
+                let c_2 = 0.2 - k1 + k2;
+                let c_3 = -1.0 / 7.0 + 14.0 / 15.0 * k1 - 5.0 / 3.0 * k2 + k3;
+                return Distortion(
+                    1.0 + r2 * (c_1 + r2 * (c_2 + r2 * c_3)),
+                    c_1 + r2 * (2.0 * c_2 + r2 * 3.0 * c_3),
+                    2.0 * c_2 + r2 * 6.0 * c_3,
+                    vec2<f32>(),
+                );
+            }
+            let r = sqrt(r2);
+            let angle = atan(r);
+            let angle2 = angle * angle;
+            let angle_distorted =
+                angle * (1.0 + angle2 * (k1 + angle2 * (k2 + angle2 * (k3 + angle2 * k4))));
+            let angle_distorted_grad =
+                1.0 + angle2 * (3.0 * k1 + angle2 * (5.0 * k2 + angle2 * (7.0 * k3 + angle2 * 9.0 * k4)));
+            let angle_distorted_grad_2 =
+                angle * (6.0 * k1 + angle2 * (20.0 * k2 + angle2 * (42.0 * k3 + angle2 * 72.0 * k4)));
+            // h = θ_d(atan(r)), h' = ∂h/∂r, h'' = ∂^2h/∂r^2
+            let angle_r = 1.0 / (1.0 + r2);
+            let h = angle_distorted;
+            let h_1 = angle_distorted_grad * angle_r;
+            let h_2 = (angle_distorted_grad_2 - 2.0 * r * angle_distorted_grad) * angle_r * angle_r;
+            // ρ = h / r, ∂ρ/∂r = (h' - ρ) / r
+            let radial = h / r;
+            let radial_grad_r = (h_1 - radial) / r;
+            return Distortion(
+                radial,
+                radial_grad_r / (2.0 * r),
+                (h_2 - 3.0 * radial_grad_r) / (4.0 * r2 * r),
+                vec2<f32>(),
+            );
+        }
+        default: {
+            return Distortion(1.0, 0.0, 0.0, vec2<f32>());
+        }
+    }
+}
+
+// Jd[2, 2] (Symmetric) = ∂Pn'/∂Pn = ρ * I + 2 * ∂ρ/∂r^2 * Pn * Pn^t + ∂Tn/∂Pn
+fn distort_jacobian(distortion: Distortion, position: vec2<f32>) -> mat2x2<f32> {
+    let x = position.x;
+    let y = position.y;
+    let p1 = distortion.tangential.x;
+    let p2 = distortion.tangential.y;
+    let xy = 2.0 * distortion.radial_grad * x * y + 2.0 * p1 * x + 2.0 * p2 * y;
+    return mat2x2<f32>(
+        distortion.radial + 2.0 * distortion.radial_grad * x * x + 2.0 * p1 * y + 6.0 * p2 * x,
+        xy,
+        xy,
+        distortion.radial + 2.0 * distortion.radial_grad * y * y + 6.0 * p1 * y + 2.0 * p2 * x,
+    );
+}
+
+// ∂L/∂Pn[2] =〈∂L/∂Jd[2, 2], ∂Jd/∂Pn[2, 2, 2]〉
+fn distort_jacobian_grad(
+    distortion: Distortion,
+    position: vec2<f32>,
+    jacobian_grad: mat2x2<f32>,
+) -> vec2<f32> {
+    let m = jacobian_grad;
+    let p1 = distortion.tangential.x;
+    let p2 = distortion.tangential.y;
+    let m_p = m * position;
+    let m_t_p = position * m;
+    let m_off = m[0][1] + m[1][0];
+    let radial_common =
+        2.0 * distortion.radial_grad * (m[0][0] + m[1][1]) +
+        4.0 * distortion.radial_grad_2 * dot(position, m_p);
+    return radial_common * position +
+        2.0 * distortion.radial_grad * (m_p + m_t_p) +
+        vec2<f32>(
+            6.0 * p2 * m[0][0] + 2.0 * p1 * m_off + 2.0 * p2 * m[1][1],
+            2.0 * p1 * m[0][0] + 2.0 * p2 * m_off + 6.0 * p1 * m[1][1],
+        );
+}
+
+// Pn'[2] = Pn[2] * ρ + [2 * p1 * x * y + p2 * (r^2 + 2 * x^2),
+//                       p1 * (r^2 + 2 * y^2) + 2 * p2 * x * y]
+fn distort_position(distortion: Distortion, position: vec2<f32>) -> vec2<f32> {
+    let x = position.x;
+    let y = position.y;
+    let p1 = distortion.tangential.x;
+    let p2 = distortion.tangential.y;
+    let r2 = dot(position, position);
+    return position * distortion.radial + vec2<f32>(
+        2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+        p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+    );
+}
+
+// ∂Pn/∂Pv[2, 3] of the equirectangular projection
+// ∂Pn/∂Pv[2, 3] = [[Pv.z / ρ^2,           0,          -Pv.x / ρ^2          ]
+//                  [-Pv.x * Pv.y / r^2 ρ, ρ / r^2,    -Pv.z * Pv.y / r^2 ρ ]]
+// ρ = √(Pv.x^2 + Pv.z^2)
+// r = |Pv|
+fn equirectangular_jacobian(position: vec3<f32>) -> mat3x2<f32> {
+    let x = position.x;
+    let y = position.y;
+    let z = position.z;
+    let rho2 = x * x + z * z;
+    let rho = sqrt(rho2);
+    let r2 = rho2 + y * y;
+    let r2_rho = r2 * rho;
+    return mat3x2<f32>(
+        z / rho2, -x * y / r2_rho,
+        0.0, rho / r2,
+        -x / rho2, -z * y / r2_rho,
+    );
+}
+
+// ∂L/∂Pv[3] =〈∂L/∂J[2, 3], ∂J/∂Pv[2, 3, 3]〉
+// of the equirectangular projection J = ∂Pn/∂Pv
+fn equirectangular_jacobian_grad(
+    position: vec3<f32>,
+    jacobian_grad: mat3x2<f32>,
+) -> vec3<f32> {
+    let x = position.x;
+    let y = position.y;
+    let z = position.z;
+    let g_0 = vec3<f32>(jacobian_grad[0][0], jacobian_grad[1][0], jacobian_grad[2][0]);
+    let g_1 = vec3<f32>(jacobian_grad[0][1], jacobian_grad[1][1], jacobian_grad[2][1]);
+    let rho2 = x * x + z * z;
+    let rho = sqrt(rho2);
+    let r2 = rho2 + y * y;
+
+    // s = (∂L/∂J.0,0 * Pv.z - ∂L/∂J.0,2 * Pv.x) / ρ^2
+    let s = (g_0[0] * z - g_0[2] * x) / rho2;
+    // t = (-∂L/∂J.1,0 * Pv.x * Pv.y + ∂L/∂J.1,1 * ρ^2 - ∂L/∂J.1,2 * Pv.z * Pv.y) / w
+    // w = r^2 * ρ
+    let w = r2 * rho;
+    let t = (-g_1[0] * x * y + g_1[1] * rho2 - g_1[2] * z * y) / w;
+    let w_grad = vec3<f32>(
+        2.0 * x * rho + r2 * x / rho,
+        2.0 * y * rho,
+        2.0 * z * rho + r2 * z / rho,
+    );
+    return vec3<f32>(
+        (-g_0[2] - 2.0 * x * s) / rho2,
+        0.0,
+        (g_0[0] - 2.0 * z * s) / rho2,
+    ) + (
+        vec3<f32>(
+            -g_1[0] * y + 2.0 * g_1[1] * x,
+            -g_1[0] * x - g_1[2] * z,
+            2.0 * g_1[1] * z - g_1[2] * y,
+        ) - t * w_grad
+    ) / w;
+}
+
 // y = e^x
 // dy/dx = e^x = y
 fn exp_grad_vec_f32_3(y: vec3<f32>) -> vec3<f32> {
@@ -617,6 +1099,19 @@ fn normalize_grad_vec_f32_4(x: vec4<f32>) -> mat4x4<f32> {
     ) * pow(x_dot, -1.5);
 }
 
+// y = e^x / (1 + e^x)
+fn sigmoid_f32(x: f32) -> f32 {
+    let x_exp = exp(x);
+    return x_exp / (1.0 + x_exp);
+}
+
+// dy/dx = dy/d(e^x) * de^x/dx
+//       = (1 + e^x)^-2 * e^x
+//       = y * (1 - y)
+fn sigmoid_grad_f32(y: f32) -> f32 {
+    return y * (1.0 - y);
+}
+
 fn vec_from_array_f32_3(a: array<f32, 3>) -> vec3<f32> {
     return vec3<f32>(a[0], a[1], a[2]);
 }
@@ -9,8 +9,18 @@ use bytemuck::{bytes_of, Pod, Zeroable};
 #[repr(C, align(16))]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Arguments {
+    /// `(0: Pinhole, 1: OpenCV, 2: Fisheye)`
+    pub camera_model: u32,
     /// `(0 ~ 3)`
     pub colors_sh_degree_max: u32,
+    /// `[k1, k2, k3, k4, p1, p2]`
+    pub distortion: [f32; 6],
+    /// `C_f`
+    pub filter_2d: f32,
+    /// `C_3d`
+    pub filter_3d_scale: f32,
+    /// `(0: Low-pass filter, 1: Mip filter)`
+    pub filter_mip_2d: u32,
     /// `f_x <- I_x / tan(Fov_x / 2) / 2`
     pub focal_length_x: f32,
     /// `f_y <- I_y / tan(Fov_y / 2) / 2`
@@ -21,10 +31,14 @@ pub struct Arguments {
     pub image_size_half_y: f32,
     /// `P`
     pub point_count: u32,
-    /// `tan(Fov_x / 2) * (C_f + 1)`
-    pub view_bound_x: f32,
-    /// `tan(Fov_y / 2) * (C_f + 1)`
-    pub view_bound_y: f32,
+    /// `(I_x - C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_max: f32,
+    /// `-(C_x + I_x / 2 * C_f) / F_x`
+    pub view_bound_x_min: f32,
+    /// `(I_y - C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_max: f32,
+    /// `-(C_y + I_y / 2 * C_f) / F_y`
+    pub view_bound_y_min: f32,
     /// `[3]`
     pub view_position: [f32; 3],
     /// Padding
@@ -46,10 +60,20 @@ pub struct Inputs<R: JitRuntime> {
     pub conics_grad: JitTensor<R>,
     /// `[P]`
     pub depths: JitTensor<R>,
+    /// `[P]`
+    pub depths_grad: JitTensor<R>,
+    /// `[P, 1]`
+    pub filters_3d: JitTensor<R>,
     /// `[P, 3]`
     pub is_colors_rgb_3d_not_clamped: JitTensor<R>,
+    /// `[P, 1]` (Inner)
+    pub opacities: JitTensor<R>,
+    /// `[P]` (Outer)
+    pub opacities_3d_grad: JitTensor<R>,
     /// `[P, 2]`
     pub positions_2d_grad: JitTensor<R>,
+    /// `[P, 2]`
+    pub positions_2d_grad_abs: JitTensor<R>,
     /// `[P, 3]`
     pub positions_3d: JitTensor<R>,
     /// `[P, 2]`
@@ -69,6 +93,12 @@ pub struct Inputs<R: JitRuntime> {
 pub struct Outputs<R: JitRuntime> {
     /// `[P, M * 3]` <- `[P, M, 3]`
     pub colors_sh_grad: JitTensor<R>,
+    /// `[P, 2]`
+    pub focal_lengths_grad: JitTensor<R>,
+    /// `[P, 1]`
+    pub opacities_grad: JitTensor<R>,
+    /// `[P]`
+    pub positions_2d_grad_abs_norm: JitTensor<R>,
     /// `[P]`
     pub positions_2d_grad_norm: JitTensor<R>,
     /// `[P, 3]`
@@ -77,10 +107,10 @@ pub struct Outputs<R: JitRuntime> {
     pub rotations_grad: JitTensor<R>,
     /// `[P, 3]`
     pub scalings_grad: JitTensor<R>,
+    /// `[P, 6]`
+    pub view_transforms_grad: JitTensor<R>,
 }
 
-/// `C_f`
-pub const FILTER_LOW_PASS: f64 = 0.3;
 /// Group size.
 pub const GROUP_SIZE: u32 = 256;
 
@@ -101,6 +131,12 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     // [P, M * 3] <- [P, M, 3]
     let colors_sh_grad =
         JitBackend::<R, F, I, B>::float_zeros([point_count, 48].into(), device);
+    let focal_lengths_grad =
+        JitBackend::<R, F, I, B>::float_zeros([point_count, 2].into(), device);
+    let opacities_grad =
+        JitBackend::<R, F, I, B>::float_zeros([point_count, 1].into(), device);
+    let positions_2d_grad_abs_norm =
+        JitBackend::<R, F, I, B>::float_zeros([point_count].into(), device);
     let positions_2d_grad_norm =
         JitBackend::<R, F, I, B>::float_zeros([point_count].into(), device);
     let positions_3d_grad =
@@ -109,6 +145,8 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         JitBackend::<R, F, I, B>::float_zeros([point_count, 4].into(), device);
     let scalings_grad =
         JitBackend::<R, F, I, B>::float_zeros([point_count, 3].into(), device);
+    let view_transforms_grad =
+        JitBackend::<R, F, I, B>::float_zeros([point_count, 6].into(), device);
 
     client.execute(
         Box::new(SourceKernel::new(
@@ -127,8 +165,13 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             inputs.conics.handle.binding(),
             inputs.conics_grad.handle.binding(),
             inputs.depths.handle.binding(),
+            inputs.depths_grad.handle.binding(),
+            inputs.filters_3d.handle.binding(),
             inputs.is_colors_rgb_3d_not_clamped.handle.binding(),
+            inputs.opacities.handle.binding(),
+            inputs.opacities_3d_grad.handle.binding(),
             inputs.positions_2d_grad.handle.binding(),
+            inputs.positions_2d_grad_abs.handle.binding(),
             inputs.positions_3d.handle.binding(),
             inputs.positions_3d_in_normalized.handle.binding(),
             inputs.radii.handle.binding(),
@@ -136,18 +179,59 @@ pub fn main<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             inputs.rotations_matrix.handle.binding(),
             inputs.scalings.handle.binding(),
             colors_sh_grad.handle.to_owned().binding(),
+            focal_lengths_grad.handle.to_owned().binding(),
+            opacities_grad.handle.to_owned().binding(),
+            positions_2d_grad_abs_norm.handle.to_owned().binding(),
             positions_2d_grad_norm.handle.to_owned().binding(),
             positions_3d_grad.handle.to_owned().binding(),
             rotations_grad.handle.to_owned().binding(),
             scalings_grad.handle.to_owned().binding(),
+            view_transforms_grad.handle.to_owned().binding(),
         ],
     );
 
     Outputs {
         colors_sh_grad,
+        focal_lengths_grad,
+        opacities_grad,
+        positions_2d_grad_abs_norm,
         positions_2d_grad_norm,
         positions_3d_grad,
         rotations_grad,
         scalings_grad,
+        view_transforms_grad,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn grads_finite_difference_render_wgpu_autodiff() {
+        let scene = scene_smooth::<Autodiff<Wgpu>>(&Default::default());
+        for view in views_finite_difference() {
+            assert_render_grads_finite_difference(
+                &scene,
+                &view,
+                &Default::default(),
+                2e-2,
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn positions_2d_grad_abs_render_ndarray_and_wgpu() {
+        let scene = scene_random::<NdArray>(48, &Default::default());
+        let options = Gaussian3dRenderOptions::default().with_positions_2d_grad_abs(true);
+        assert_render_ndarray_and_wgpu(&scene, &VIEW_RANDOM, &options);
+    }
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn positions_2d_grad_abs_render_wgpu_autodiff() {
+        assert_positions_2d_grad_abs::<Wgpu>(&Default::default());
     }
 }
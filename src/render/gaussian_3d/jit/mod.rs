@@ -8,10 +8,11 @@ pub use crate::{
     backend::jit::{FloatElement, IntElement, JitBackend, JitRuntime},
     scene::gaussian_3d::SH_DEGREE_MAX,
 };
-pub use rank::TILE_COUNT_MAX;
-pub use rasterize::{TILE_SIZE_X, TILE_SIZE_Y};
-pub use transform::FILTER_LOW_PASS;
+pub use rank::{TILE_COUNT_MAX, TILE_COUNT_MAX_32_BIT};
+pub use rasterize::{CONTRIBUTOR_COUNT_MAX, TILE_SIZE_X, TILE_SIZE_Y};
+pub use transform::{FACTOR_RADIUS, FILTER_LOW_PASS, FILTER_MIP_2D, VIEW_BOUND_MARGIN};
 
+use burn::tensor::ops::FloatTensorOps;
 use burn_jit::kernel::into_contiguous;
 use kernel::*;
 
@@ -29,6 +30,10 @@ pub const PIXEL_COUNT_MAX: u32 = TILE_SIZE_X * TILE_SIZE_Y * TILE_COUNT_MAX;
 /// 4. Sort the points by its tile index and depth.
 /// 5. Segment the points by its tile index.
 /// 6. [Rasterize](rasterize::main) the points into the image.
+/// 7. [Rasterize](rasterize_features::main) the point features into the image
+///    if they are specified.
+/// 8. [Rasterize](rasterize_labels::main) the point labels into the image
+///    if they are specified.
 pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     mut input: forward::RenderInput<JitBackend<R, F, I, B>>,
     view: &View,
@@ -39,39 +44,110 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     // Specifying the arguments
 
+    let background_rgb = options.background_rgb;
+    let camera_model = view.camera_model.id();
     let colors_sh_degree_max = options.colors_sh_degree_max;
-    // tan(Fov_x / 2)
-    let field_of_view_x_half_tan = (view.field_of_view_x / 2.0).tan();
-    // tan(Fov_y / 2)
-    let field_of_view_y_half_tan = (view.field_of_view_y / 2.0).tan();
+    // K
+    let contributor_count = options.contributor_count;
+    // D_far
+    let depth_far = options.depth_far;
+    // D_near
+    let depth_near = options.depth_near;
+    // [k1, k2, k3, k4, p1, p2]
+    let distortion = view.camera_model.coefficients().map(|c| c as f32);
+    // k
+    let factor_radius = options.factor_radius;
+    // C_f
+    let filter_2d = if options.filter_mip_2d {
+        FILTER_MIP_2D as f32
+    } else {
+        options.filter_low_pass
+    };
+    // C_3d
+    let filter_3d_scale = options.filter_3d_scale;
+    // (0: Low-pass filter, 1: Mip filter)
+    let filter_mip_2d = options.filter_mip_2d as u32;
+    // (0: Disabled, 1: Enabled)
+    let positions_2d_grad_abs = options.positions_2d_grad_abs as u32;
     // I_x
     let image_size_x = view.image_width;
     // I_y
     let image_size_y = view.image_height;
     // F_x <- I_x / tan(Fov_x / 2) / 2
-    let focal_length_x = (image_size_x as f64 / field_of_view_x_half_tan / 2.0) as f32;
+    let focal_length_x = view.focal_length_x() as f32;
     // F_y <- I_y / tan(Fov_y / 2) / 2
-    let focal_length_y = (image_size_y as f64 / field_of_view_y_half_tan / 2.0) as f32;
+    let focal_length_y = view.focal_length_y() as f32;
     // I_x / 2
     let image_size_half_x = (image_size_x as f64 / 2.0) as f32;
     // I_y / 2
     let image_size_half_y = (image_size_y as f64 / 2.0) as f32;
-    // I_y * I_x
-    let pixel_count = image_size_x as usize * image_size_y as usize;
+    // C_x <- I_x / 2 + O_x
+    let principal_point_x = view.principal_point_x() as f32;
+    // C_y <- I_y / 2 + O_y
+    let principal_point_y = view.principal_point_y() as f32;
+    // [I_x0, I_y0, I_x', I_y']
+    let [region_offset_x, region_offset_y, region_size_x, region_size_y] =
+        options.region.unwrap_or([0, 0, image_size_x, image_size_y]);
+    // I_y' * I_x'
+    let pixel_count = region_size_x as usize * region_size_y as usize;
     // P
     let point_count = input.point_count as u32;
     // T_x
     let tile_size_x = TILE_SIZE_X;
     // T_y
     let tile_size_y = TILE_SIZE_Y;
-    // I_x / T_x
-    let tile_count_x = image_size_x.div_ceil(tile_size_x);
-    // I_y / T_y
-    let tile_count_y = image_size_y.div_ceil(tile_size_y);
-    // tan(Fov_x / 2) * (C_f + 1)
-    let view_bound_x = (field_of_view_x_half_tan * (FILTER_LOW_PASS + 1.0)) as f32;
-    // tan(Fov_y / 2) * (C_f + 1)
-    let view_bound_y = (field_of_view_y_half_tan * (FILTER_LOW_PASS + 1.0)) as f32;
+    // I_x0 / T_x
+    let tile_offset_x = region_offset_x / tile_size_x;
+    // I_y0 / T_y
+    let tile_offset_y = region_offset_y / tile_size_y;
+    // (I_x0 + I_x') / T_x - I_x0 / T_x
+    //
+    // The tiles are aligned to the image rather than the region.
+    let tile_count_x = region_offset_x
+        .saturating_add(region_size_x)
+        .div_ceil(tile_size_x)
+        - tile_offset_x;
+    // (I_y0 + I_y') / T_y - I_y0 / T_y
+    let tile_count_y = region_offset_y
+        .saturating_add(region_size_y)
+        .div_ceil(tile_size_y)
+        - tile_offset_y;
+    // (I_y / T_y) * (I_x / T_x)
+    let tile_count = image_size_x.div_ceil(tile_size_x) as usize
+        * image_size_y.div_ceil(tile_size_y) as usize;
+    // (1: 32-bit, 2: 64-bit)
+    //
+    // The 64-bit point orders are required if there are too many tiles.
+    // It depends on the tiles of the whole image rather than the region,
+    // so that the points are sorted identically in any region.
+    let point_order_word_count = if options.sort_key_bit_count == 32
+        && tile_count <= TILE_COUNT_MAX_32_BIT as usize
+    {
+        1
+    } else {
+        2
+    };
+    // (I_x - C_x + I_x / 2 * C_m) / F_x
+    // -(C_x + I_x / 2 * C_m) / F_x
+    // (I_y - C_y + I_y / 2 * C_m) / F_y
+    // -(C_y + I_y / 2 * C_m) / F_y
+    //
+    // The bounds are undistorted for the distorted camera models.
+    let [view_bound_x_max, view_bound_x_min, view_bound_y_max, view_bound_y_min] = view
+        .camera_model
+        .undistort_bounds([
+            (image_size_x as f64 - view.principal_point_x()
+                + image_size_x as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_x(),
+            -(view.principal_point_x() + image_size_x as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_x(),
+            (image_size_y as f64 - view.principal_point_y()
+                + image_size_y as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_y(),
+            -(view.principal_point_y() + image_size_y as f64 / 2.0 * VIEW_BOUND_MARGIN)
+                / view.focal_length_y(),
+        ])
+        .map(|bound| bound as f32);
     let view_position = view.view_position.map(|c| c as f32);
     let view_transform = view.view_transform.map(|c| c.map(|c| c as f32));
 
@@ -80,6 +156,41 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             colors_sh_degree_max,
         ));
     }
+    if contributor_count > CONTRIBUTOR_COUNT_MAX {
+        return Err(Error::InvalidContributorCount(contributor_count));
+    }
+    if !(depth_near > 0.0 && depth_near < depth_far && depth_far.is_finite()) {
+        return Err(Error::InvalidDepthRange(depth_near, depth_far));
+    }
+    if !(factor_radius > 0.0 && factor_radius.is_finite()) {
+        return Err(Error::InvalidFactorRadius(factor_radius));
+    }
+    if !(filter_3d_scale >= 0.0 && filter_3d_scale.is_finite()) {
+        return Err(Error::InvalidFilter3dScale(filter_3d_scale));
+    }
+    if !(options.filter_low_pass >= 0.0 && options.filter_low_pass.is_finite()) {
+        return Err(Error::InvalidFilterLowPass(options.filter_low_pass));
+    }
+    if !matches!(options.sort_key_bit_count, 32 | 64) {
+        return Err(Error::UnsupportedSortKeyBitCount(
+            options.sort_key_bit_count,
+        ));
+    }
+    if region_size_x == 0
+        || region_size_y == 0
+        || region_offset_x as u64 + region_size_x as u64 > image_size_x as u64
+        || region_offset_y as u64 + region_size_y as u64 > image_size_y as u64
+    {
+        return Err(Error::InvalidRegion(
+            [
+                region_offset_x,
+                region_offset_y,
+                region_size_x,
+                region_size_y,
+            ],
+            [image_size_x, image_size_y],
+        ));
+    }
     if pixel_count == 0 || pixel_count > PIXEL_COUNT_MAX as usize {
         return Err(Error::InvalidPixelCount(pixel_count));
     }
@@ -90,6 +201,9 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     // Specifying the inputs
 
     input.colors_sh = into_contiguous(input.colors_sh);
+    input.features = input.features.map(into_contiguous);
+    input.filters_3d = into_contiguous(input.filters_3d);
+    input.labels = input.labels.map(into_contiguous);
     input.opacities = into_contiguous(input.opacities);
     input.positions = into_contiguous(input.positions);
     input.rotations = into_contiguous(input.rotations);
@@ -99,16 +213,28 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     let outputs_transform = transform::main::<R, F, I, B>(
         transform::Arguments {
+            camera_model,
             colors_sh_degree_max,
+            depth_far,
+            depth_near,
+            distortion,
+            factor_radius,
+            filter_2d,
+            filter_3d_scale,
+            filter_mip_2d,
             focal_length_x,
             focal_length_y,
-            image_size_half_x,
-            image_size_half_y,
             point_count,
+            principal_point_x,
+            principal_point_y,
             tile_count_x: tile_count_x as i32,
             tile_count_y: tile_count_y as i32,
-            view_bound_x,
-            view_bound_y,
+            tile_offset_x: tile_offset_x as i32,
+            tile_offset_y: tile_offset_y as i32,
+            view_bound_x_max,
+            view_bound_x_min,
+            view_bound_y_max,
+            view_bound_y_min,
             view_position,
             view_transform,
             _padding_1: Default::default(),
@@ -116,6 +242,8 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
         },
         transform::Inputs {
             colors_sh: input.colors_sh.to_owned(),
+            filters_3d: input.filters_3d.to_owned(),
+            opacities: input.opacities.to_owned(),
             positions_3d: input.positions.to_owned(),
             rotations: input.rotations.to_owned(),
             scalings: input.scalings.to_owned(),
@@ -144,7 +272,10 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     let outputs_rank = rank::main::<R, F, I, B>(
         rank::Arguments {
+            depth_far,
+            depth_near,
             point_count,
+            point_order_word_count,
             tile_count_x,
         },
         rank::Inputs {
@@ -172,6 +303,7 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     let outputs_segment = segment::main::<R, F, I, B>(
         segment::Arguments {
+            point_order_word_count,
             tile_count_x,
             tile_count_y,
         },
@@ -187,15 +319,23 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
     let outputs_rasterize = rasterize::main::<R, F, I, B>(
         rasterize::Arguments {
+            background_rgb,
+            camera_model,
+            contributor_count,
             image_size_x,
             image_size_y,
+            region_offset_x,
+            region_offset_y,
+            region_size_x,
+            region_size_y,
             tile_count_x,
             tile_count_y,
         },
         rasterize::Inputs {
             colors_rgb_3d: outputs_transform.colors_rgb_3d.to_owned(),
             conics: outputs_transform.conics.to_owned(),
-            opacities_3d: input.opacities.to_owned(),
+            depths: outputs_transform.depths.to_owned(),
+            opacities_3d: outputs_transform.opacities_3d.to_owned(),
             point_indices: outputs_sort.values.to_owned(),
             positions_2d: outputs_transform.positions_2d.to_owned(),
             tile_point_ranges: outputs_segment.tile_point_ranges.to_owned(),
@@ -204,14 +344,102 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     #[cfg(all(debug_assertions, not(test)))]
     log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "rasterize");
 
+    // Rasterizing the point features into the image
+
+    let features_2d = input.features.to_owned().map(|features| {
+        // D
+        let feature_count = features.shape.dims[1] as u32;
+
+        rasterize_features::main::<R, F, I, B>(
+            rasterize_features::Arguments {
+                camera_model,
+                feature_count,
+                image_size_x,
+                image_size_y,
+                region_offset_x,
+                region_offset_y,
+                region_size_x,
+                region_size_y,
+                tile_count_x,
+                tile_count_y,
+            },
+            rasterize_features::Inputs {
+                conics: outputs_transform.conics.to_owned(),
+                features,
+                opacities_3d: outputs_transform.opacities_3d.to_owned(),
+                point_indices: outputs_sort.values.to_owned(),
+                point_rendered_counts: outputs_rasterize.point_rendered_counts.to_owned(),
+                positions_2d: outputs_transform.positions_2d.to_owned(),
+                tile_point_ranges: outputs_segment.tile_point_ranges.to_owned(),
+            },
+        )
+        .features_2d
+    });
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "rasterize_features");
+
+    // Rasterizing the point labels into the image
+
+    let (label_coverages_2d, labels_2d) = input
+        .labels
+        .map(|labels| {
+            let outputs_rasterize_labels = rasterize_labels::main::<R, F, I, B>(
+                rasterize_labels::Arguments {
+                    camera_model,
+                    image_size_x,
+                    image_size_y,
+                    label_count: input.label_count,
+                    region_offset_x,
+                    region_offset_y,
+                    region_size_x,
+                    region_size_y,
+                    tile_count_x,
+                    tile_count_y,
+                },
+                rasterize_labels::Inputs {
+                    conics: outputs_transform.conics.to_owned(),
+                    labels,
+                    opacities_3d: outputs_transform.opacities_3d.to_owned(),
+                    point_indices: outputs_sort.values.to_owned(),
+                    point_rendered_counts: outputs_rasterize
+                        .point_rendered_counts
+                        .to_owned(),
+                    positions_2d: outputs_transform.positions_2d.to_owned(),
+                    tile_point_ranges: outputs_segment.tile_point_ranges.to_owned(),
+                },
+            );
+            (
+                outputs_rasterize_labels.label_coverages_2d,
+                outputs_rasterize_labels.labels_2d,
+            )
+        })
+        .unzip();
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(target: "gausplat::renderer::gaussian_3d::forward", "rasterize_labels");
+
     Ok(forward::RenderOutput {
         colors_rgb_2d: outputs_rasterize.colors_rgb_2d,
+        contributor_indices_2d: outputs_rasterize.contributor_indices_2d,
+        contributor_weights_2d: outputs_rasterize.contributor_weights_2d,
+        depths_2d: outputs_rasterize.depths_2d,
+        depths_2d_median: outputs_rasterize.depths_2d_median,
+        features_2d,
+        label_coverages_2d,
+        labels_2d,
         state: backward::RenderInput {
+            background_rgb,
+            camera_model,
             colors_rgb_3d: outputs_transform.colors_rgb_3d,
             colors_sh: input.colors_sh,
             colors_sh_degree_max,
             conics: outputs_transform.conics,
             depths: outputs_transform.depths,
+            distortion,
+            features: input.features,
+            filter_2d,
+            filter_3d_scale,
+            filter_mip_2d,
+            filters_3d: input.filters_3d,
             focal_length_x,
             focal_length_y,
             image_size_half_x,
@@ -219,14 +447,20 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             image_size_x,
             image_size_y,
             is_colors_rgb_3d_not_clamped: outputs_transform.is_colors_rgb_3d_not_clamped,
-            opacities_3d: input.opacities,
+            opacities: input.opacities,
+            opacities_3d: outputs_transform.opacities_3d,
             point_count,
             point_indices: outputs_sort.values,
             point_rendered_counts: outputs_rasterize.point_rendered_counts,
             positions_2d: outputs_transform.positions_2d,
+            positions_2d_grad_abs,
             positions_3d: input.positions,
             positions_3d_in_normalized: outputs_transform.positions_3d_in_normalized,
             radii: outputs_transform.radii,
+            region_offset_x,
+            region_offset_y,
+            region_size_x,
+            region_size_y,
             rotations: input.rotations,
             rotations_matrix: outputs_transform.rotations_matrix,
             scalings: input.scalings,
@@ -234,8 +468,10 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             tile_count_y,
             tile_point_ranges: outputs_segment.tile_point_ranges,
             transmittances: outputs_rasterize.transmittances,
-            view_bound_x,
-            view_bound_y,
+            view_bound_x_max,
+            view_bound_x_min,
+            view_bound_y_max,
+            view_bound_y_min,
             view_position,
             view_transform,
         },
@@ -244,59 +480,144 @@ pub fn forward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
 
 /// Render the 3DGS scene (backward).
 ///
-/// It computes the gradients of `colors_rgb_2d` with respect to scene parameters.
+/// It computes the gradients of `alphas_2d`, `colors_rgb_2d`, `depths_2d`
+/// and `features_2d` with respect to scene parameters.
 ///
 /// The kernels are launched in the following order:
 /// 1. Compute the gradients for rasterization.
-/// 2. Compute the gradients for transformation.
+/// 2. Compute the gradients for rasterization of the features if they are specified.
+/// 3. Compute the gradients for transformation.
 pub fn backward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     state: backward::RenderInput<JitBackend<R, F, I, B>>,
+    mut alphas_2d_grad: JitTensor<R>,
     mut colors_rgb_2d_grad: JitTensor<R>,
+    mut depths_2d_grad: JitTensor<R>,
+    features_2d_grad: Option<JitTensor<R>>,
 ) -> backward::RenderOutput<JitBackend<R, F, I, B>> {
     #[cfg(all(debug_assertions, not(test)))]
     log::debug!(target: "gausplat::renderer::gaussian_3d::backward", "start");
 
     // Specifying the inputs
 
+    alphas_2d_grad = into_contiguous(alphas_2d_grad);
     colors_rgb_2d_grad = into_contiguous(colors_rgb_2d_grad);
+    depths_2d_grad = into_contiguous(depths_2d_grad);
 
     // Computing the gradients for rasterization
 
     let outputs_rasterize_backward = rasterize_backward::main::<R, F, I, B>(
         rasterize_backward::Arguments {
+            background_rgb: state.background_rgb,
+            camera_model: state.camera_model,
             image_size_x: state.image_size_x,
             image_size_y: state.image_size_y,
             point_count: state.point_count,
+            positions_2d_grad_abs: state.positions_2d_grad_abs,
+            region_offset_x: state.region_offset_x,
+            region_offset_y: state.region_offset_y,
+            region_size_x: state.region_size_x,
+            region_size_y: state.region_size_y,
             tile_count_x: state.tile_count_x,
             tile_count_y: state.tile_count_y,
         },
         rasterize_backward::Inputs {
+            alphas_2d_grad,
             colors_rgb_2d_grad,
             colors_rgb_3d: state.colors_rgb_3d,
             conics: state.conics.to_owned(),
-            opacities_3d: state.opacities_3d,
-            point_indices: state.point_indices,
-            point_rendered_counts: state.point_rendered_counts,
-            positions_2d: state.positions_2d,
-            tile_point_ranges: state.tile_point_ranges,
-            transmittances: state.transmittances,
+            depths: state.depths.to_owned(),
+            depths_2d_grad,
+            opacities_3d: state.opacities_3d.to_owned(),
+            point_indices: state.point_indices.to_owned(),
+            point_rendered_counts: state.point_rendered_counts.to_owned(),
+            positions_2d: state.positions_2d.to_owned(),
+            tile_point_ranges: state.tile_point_ranges.to_owned(),
+            transmittances: state.transmittances.to_owned(),
         },
     );
     #[cfg(all(debug_assertions, not(test)))]
     log::debug!(target: "gausplat::renderer::gaussian_3d::backward", "rasterize_backward");
 
+    // Computing the gradients for rasterization of the features
+    //
+    // The gradients of the geometry are accumulated
+    // onto those for rasterization of the colors.
+
+    let mut conics_grad = outputs_rasterize_backward.conics_grad;
+    let mut opacities_3d_grad = outputs_rasterize_backward.opacities_3d_grad;
+    let mut positions_2d_grad = outputs_rasterize_backward.positions_2d_grad;
+    let mut features_grad = None;
+    if let (Some(features), Some(features_2d_grad)) = (state.features, features_2d_grad) {
+        // D
+        let feature_count = features.shape.dims[1] as u32;
+
+        let outputs_rasterize_features_backward =
+            rasterize_features_backward::main::<R, F, I, B>(
+                rasterize_features_backward::Arguments {
+                    camera_model: state.camera_model,
+                    feature_count,
+                    image_size_x: state.image_size_x,
+                    image_size_y: state.image_size_y,
+                    point_count: state.point_count,
+                    region_offset_x: state.region_offset_x,
+                    region_offset_y: state.region_offset_y,
+                    region_size_x: state.region_size_x,
+                    region_size_y: state.region_size_y,
+                    tile_count_x: state.tile_count_x,
+                    tile_count_y: state.tile_count_y,
+                },
+                rasterize_features_backward::Inputs {
+                    conics: state.conics.to_owned(),
+                    features,
+                    features_2d_grad: into_contiguous(features_2d_grad),
+                    opacities_3d: state.opacities_3d,
+                    point_indices: state.point_indices,
+                    point_rendered_counts: state.point_rendered_counts,
+                    positions_2d: state.positions_2d,
+                    tile_point_ranges: state.tile_point_ranges,
+                    transmittances: state.transmittances,
+                },
+            );
+
+        conics_grad = JitBackend::<R, F, I, B>::float_add(
+            conics_grad,
+            outputs_rasterize_features_backward.conics_grad,
+        );
+        features_grad = Some(outputs_rasterize_features_backward.features_grad);
+        opacities_3d_grad = JitBackend::<R, F, I, B>::float_add(
+            opacities_3d_grad,
+            outputs_rasterize_features_backward.opacities_3d_grad,
+        );
+        positions_2d_grad = JitBackend::<R, F, I, B>::float_add(
+            positions_2d_grad,
+            outputs_rasterize_features_backward.positions_2d_grad,
+        );
+    }
+    #[cfg(all(debug_assertions, not(test)))]
+    log::debug!(
+        target: "gausplat::renderer::gaussian_3d::backward",
+        "rasterize_features_backward",
+    );
+
     // Computing the gradients for transformation
 
     let outputs_transform_backward = transform_backward::main::<R, F, I, B>(
         transform_backward::Arguments {
+            camera_model: state.camera_model,
             colors_sh_degree_max: state.colors_sh_degree_max,
+            distortion: state.distortion,
+            filter_2d: state.filter_2d,
+            filter_3d_scale: state.filter_3d_scale,
+            filter_mip_2d: state.filter_mip_2d,
             focal_length_x: state.focal_length_x,
             focal_length_y: state.focal_length_y,
             image_size_half_x: state.image_size_half_x,
             image_size_half_y: state.image_size_half_y,
             point_count: state.point_count,
-            view_bound_x: state.view_bound_x,
-            view_bound_y: state.view_bound_y,
+            view_bound_x_max: state.view_bound_x_max,
+            view_bound_x_min: state.view_bound_x_min,
+            view_bound_y_max: state.view_bound_y_max,
+            view_bound_y_min: state.view_bound_y_min,
             view_position: state.view_position,
             view_transform: state.view_transform,
             _padding_1: Default::default(),
@@ -305,10 +626,15 @@ pub fn backward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
             colors_rgb_3d_grad: outputs_rasterize_backward.colors_rgb_3d_grad,
             colors_sh: state.colors_sh,
             conics: state.conics,
-            conics_grad: outputs_rasterize_backward.conics_grad,
+            conics_grad,
             depths: state.depths,
+            depths_grad: outputs_rasterize_backward.depths_grad,
+            filters_3d: state.filters_3d,
             is_colors_rgb_3d_not_clamped: state.is_colors_rgb_3d_not_clamped,
-            positions_2d_grad: outputs_rasterize_backward.positions_2d_grad,
+            opacities: state.opacities,
+            opacities_3d_grad,
+            positions_2d_grad,
+            positions_2d_grad_abs: outputs_rasterize_backward.positions_2d_grad_abs,
             positions_3d: state.positions_3d,
             positions_3d_in_normalized: state.positions_3d_in_normalized,
             radii: state.radii,
@@ -320,12 +646,64 @@ pub fn backward<R: JitRuntime, F: FloatElement, I: IntElement, B: BoolElement>(
     #[cfg(all(debug_assertions, not(test)))]
     log::debug!(target: "gausplat::renderer::gaussian_3d::backward", "transform_backward");
 
+    // Reducing the gradients of the view over the points
+
+    // [2]
+    let focal_lengths_grad = JitBackend::<R, F, I, B>::float_reshape(
+        JitBackend::<R, F, I, B>::float_sum_dim(
+            outputs_transform_backward.focal_lengths_grad,
+            0,
+        ),
+        [2].into(),
+    );
+    // [6]
+    let view_transform_grad = JitBackend::<R, F, I, B>::float_reshape(
+        JitBackend::<R, F, I, B>::float_sum_dim(
+            outputs_transform_backward.view_transforms_grad,
+            0,
+        ),
+        [6].into(),
+    );
+
     backward::RenderOutput {
         colors_sh_grad: outputs_transform_backward.colors_sh_grad,
-        opacities_grad: outputs_rasterize_backward.opacities_3d_grad,
+        features_grad,
+        focal_lengths_grad,
+        opacities_grad: outputs_transform_backward.opacities_grad,
+        positions_2d_grad_abs_norm: (state.positions_2d_grad_abs != 0)
+            .then_some(outputs_transform_backward.positions_2d_grad_abs_norm),
         positions_2d_grad_norm: outputs_transform_backward.positions_2d_grad_norm,
         positions_grad: outputs_transform_backward.positions_3d_grad,
         rotations_grad: outputs_transform_backward.rotations_grad,
         scalings_grad: outputs_transform_backward.scalings_grad,
+        view_transform_grad,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn default_render_ndarray_and_wgpu() {
+        let scene = scene_random::<NdArray>(48, &Default::default());
+        assert_render_ndarray_and_wgpu(&scene, &VIEW_RANDOM, &Default::default());
+    }
+
+    #[test]
+    #[ignore = "It requires a GPU"]
+    fn sixstars_render_ndarray_and_wgpu() {
+        let target = scene_sixstars::<NdArray>(&Default::default())
+            .render(&VIEW, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .into_data();
+        let output = scene_sixstars::<Wgpu>(&Default::default())
+            .render(&VIEW, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .into_data();
+        output.assert_approx_eq(&target, 3);
     }
 }
@@ -3,8 +3,10 @@
 //! For more information, see:
 //! 1. [3DGS survey](https://arxiv.org/abs/2401.03890).
 //! 2. [JIT kernel API](jit::kernel).
+//! 3. [CPU kernel API](cpu::kernel).
 
 pub mod backward;
+pub mod cpu;
 pub mod forward;
 pub mod jit;
 
@@ -35,33 +37,179 @@ pub trait Gaussian3dRenderer<B: Backend>: 'static + Send + Sized + fmt::Debug {
     ///
     /// It computes the gradients from
     /// the [output in forward pass](forward::RenderOutput).
+    ///
+    /// The shapes of `alphas_2d_grad`, `colors_rgb_2d_grad`, `depths_2d_grad`
+    /// and `features_2d_grad` are `[I_y, I_x]`, `[I_y, I_x, 3]`, `[I_y, I_x]`
+    /// and `[I_y, I_x, D]`.
+    /// `features_2d_grad` should be specified if the features were rendered.
     fn render_backward(
         state: backward::RenderInput<B>,
+        alphas_2d_grad: B::FloatTensorPrimitive,
         colors_rgb_2d_grad: B::FloatTensorPrimitive,
+        depths_2d_grad: B::FloatTensorPrimitive,
+        features_2d_grad: Option<B::FloatTensorPrimitive>,
     ) -> backward::RenderOutput<B>;
 }
 
 /// 3DGS rendering options.
 #[derive(Config, Copy, Debug, PartialEq, Record)]
 pub struct Gaussian3dRenderOptions {
+    #[config(default = "true")]
+    /// Whether to transform the colors by the appearance of the view.
+    ///
+    /// The [appearance transforms](crate::scene::gaussian_3d::Gaussian3dScene::appearances)
+    /// are applied if the scene has them.
+    /// It can be disabled to render the colors without compensation,
+    /// e.g., for the views not seen in training.
+    pub appearance: bool,
+    #[config(default = "None")]
+    /// The view ID of the appearance to transform the colors by.
+    ///
+    /// It renders any view with the appearance of a reference view.
+    /// The default is the [`View::view_id`] of the rendered view.
+    pub appearance_view_id: Option<u32>,
+    #[config(default = "[0.0, 0.0, 0.0]")]
+    /// The background color in RGB space.
+    ///
+    /// It is composited using the final transmittance of each pixel.
+    /// The default is black.
+    pub background_rgb: [f32; 3],
     #[config(default = "SH_DEGREE_MAX")]
     /// The maximum degree of color in SH space.
     ///
     /// It should be no more than [`SH_DEGREE_MAX`].
     pub colors_sh_degree_max: u32,
+    #[config(default = "0")]
+    /// The count of top contributors to record for each pixel.
+    ///
+    /// The indices and the weights of the points with the highest blending weights
+    /// are recorded in [`contributor_indices_2d`](Gaussian3dRenderOutput::contributor_indices_2d)
+    /// and [`contributor_weights_2d`](Gaussian3dRenderOutput::contributor_weights_2d),
+    /// e.g., for picking the points in a pixel.
+    /// It should be no more than [`CONTRIBUTOR_COUNT_MAX`](jit::CONTRIBUTOR_COUNT_MAX),
+    /// and they are not recorded if it is zero.
+    pub contributor_count: u32,
+    #[config(default = "16384.0")]
+    /// The far clip plane of depth.
+    ///
+    /// The points at or beyond it are culled.
+    /// The depth order for sorting is normalized within the near and far clip planes,
+    /// so a tight range improves the sorting precision.
+    pub depth_far: f32,
+    #[config(default = "0.25")]
+    /// The near clip plane of depth.
+    ///
+    /// The points before it are culled.
+    /// It should be positive and less than [`depth_far`](Self::depth_far).
+    pub depth_near: f32,
+    #[config(default = "jit::FACTOR_RADIUS as f32")]
+    /// The factor of the radius to the standard deviation of each point.
+    ///
+    /// The points are bounded by the radius in screen space.
+    /// A smaller factor touches fewer tiles but truncates the tails of the points.
+    /// It should be positive, and the default is [`FACTOR_RADIUS`](jit::FACTOR_RADIUS),
+    /// which covers 99.73% of the mass.
+    pub factor_radius: f32,
+    #[config(default = "0.0")]
+    /// The variance scale of the 3D smoothing filter.
+    ///
+    /// Each point is smoothed by an isotropic Gaussian whose variance is the scale
+    /// times its [3D filter](crate::scene::gaussian_3d::Gaussian3dScene::filters_3d),
+    /// and its opacity is compensated for the enlarged volume.
+    /// It removes the high frequencies that the training views cannot sample.
+    /// It is `0.2` in Mip-Splatting, and the filter is disabled if it is zero
+    /// or the 3D filters are absent.
+    pub filter_3d_scale: f32,
+    #[config(default = "jit::FILTER_LOW_PASS as f32")]
+    /// The variance of the low-pass filter in pixels.
+    ///
+    /// It dilates the 2D covariance of each point to cover at least a pixel.
+    /// It should be non-negative, and the default is
+    /// [`FILTER_LOW_PASS`](jit::FILTER_LOW_PASS).
+    /// It is ignored if [`filter_mip_2d`](Self::filter_mip_2d) is enabled.
+    pub filter_low_pass: f32,
+    #[config(default = "false")]
+    /// Whether to use the 2D mip filter instead of the low-pass filter.
+    ///
+    /// The [low-pass filter](Self::filter_low_pass) dilates the 2D covariance,
+    /// which erodes or aliases the points rendered at another resolution.
+    /// The mip filter approximates a box filter of one pixel by
+    /// [`FILTER_MIP_2D`](jit::FILTER_MIP_2D) and compensates the opacity instead.
+    pub filter_mip_2d: bool,
+    #[config(default = "false")]
+    /// Whether to accumulate the absolute gradients of the 2D positions.
+    ///
+    /// The gradients of the 2D positions cancel out between the pixels
+    /// when a large point covers the regions of opposite errors,
+    /// so such a point is rarely densified and blurs the details.
+    /// If it is enabled, the absolute gradients of each pixel are also accumulated
+    /// as in AbsGS, whose norm is the gradient of
+    /// [`positions_2d_grad_abs_norm_ref`](Gaussian3dRenderOutputAutodiff::positions_2d_grad_abs_norm_ref).
+    pub positions_2d_grad_abs: bool,
+    #[config(default = "None")]
+    /// The region of the image to render, which is `[x, y, width, height]` in pixels.
+    ///
+    /// The outputs are cropped to the region,
+    /// whose pixel count should be no more than [`PIXEL_COUNT_MAX`](jit::PIXEL_COUNT_MAX).
+    /// Only the tiles intersecting the region are binned and rasterized,
+    /// and the gradients flow from the region only.
+    /// The default is the whole image.
+    ///
+    /// The pixels are identical to those rendered for the whole image.
+    /// It is faster if the region is aligned to
+    /// [`TILE_SIZE_X`](jit::TILE_SIZE_X) and [`TILE_SIZE_Y`](jit::TILE_SIZE_Y).
+    pub region: Option<[u32; 4]>,
+    #[config(default = "32")]
+    /// The bit count of sort keys, which is either 32 or 64.
+    ///
+    /// The 32-bit keys pack the tile index and the depth into 16 bits each,
+    /// which are fast to sort but quantize the depth coarsely.
+    /// The 64-bit keys keep the full precision of depth at the cost of sorting time.
+    /// They are always used if the tile count exceeds
+    /// [`TILE_COUNT_MAX_32_BIT`](jit::TILE_COUNT_MAX_32_BIT).
+    pub sort_key_bit_count: u32,
 }
 
 /// 3DGS rendering output.
 #[derive(Clone)]
 pub struct Gaussian3dRenderOutput<B: Backend> {
+    /// `[I_y, I_x]`
+    pub alphas_2d: Tensor<B, 2>,
     /// `[I_y, I_x, 3]`
     pub colors_rgb_2d: Tensor<B, 3>,
-    // TODO: THM
+    /// `[I_y, I_x, K]`
+    ///
+    /// The indices of the top contributors of each pixel,
+    /// sorted by their weights in descending order.
+    /// The absent ones are
+    /// [`CONTRIBUTOR_INDEX_NONE`](jit::kernel::rasterize::CONTRIBUTOR_INDEX_NONE).
+    pub contributor_indices_2d: Option<Tensor<B, 3, Int>>,
+    /// `[I_y, I_x, K]`
+    ///
+    /// The blending weights of the top contributors of each pixel.
+    pub contributor_weights_2d: Option<Tensor<B, 3>>,
+    /// `[I_y, I_x]`
+    pub depths_2d: Tensor<B, 2>,
+    /// `[I_y, I_x]`
+    pub depths_2d_median: Tensor<B, 2>,
+    /// `[I_y, I_x, D]`
+    pub features_2d: Option<Tensor<B, 3>>,
+    /// `[I_y, I_x, L]`
+    pub label_coverages_2d: Option<Tensor<B, 3>>,
+    /// `[I_y, I_x]`
+    pub labels_2d: Option<Tensor<B, 2, Int>>,
 }
 
 /// 3DGS rendering output (autodiff enabled).
 #[derive(Clone)]
 pub struct Gaussian3dRenderOutputAutodiff<AB: AutodiffBackend> {
+    /// 2D alphas (Accumulated opacities).
+    ///
+    /// The shape is `[I_y, I_x]`.
+    ///
+    /// It is the coverage of the rendered image, i.e., `1 - T`,
+    /// where `T` is the final transmittance of each pixel.
+    pub alphas_2d: Tensor<AB, 2>,
     /// 2D Colors in RGB space.
     ///
     /// The shape is `[I_y, I_x, 3]`.
@@ -70,6 +218,107 @@ pub struct Gaussian3dRenderOutputAutodiff<AB: AutodiffBackend> {
     ///
     /// It is the rendered image.
     pub colors_rgb_2d: Tensor<AB, 3>,
+    /// 2D indices of the top contributors.
+    ///
+    /// The shape is `[I_y, I_x, K]`.
+    /// - `K`: [Contributor count](Gaussian3dRenderOptions::contributor_count).
+    ///
+    /// They are the indices of the points with the highest blending weights
+    /// in each pixel, sorted by the weights in descending order.
+    /// The absent ones are
+    /// [`CONTRIBUTOR_INDEX_NONE`](jit::kernel::rasterize::CONTRIBUTOR_INDEX_NONE).
+    /// It is `None` if `K` is zero.
+    pub contributor_indices_2d: Option<Tensor<AB::InnerBackend, 3, Int>>,
+    /// 2D weights of the top contributors.
+    ///
+    /// The shape is `[I_y, I_x, K]`.
+    ///
+    /// They are the blending weights of
+    /// [`contributor_indices_2d`](Self::contributor_indices_2d),
+    /// which are zero for the absent ones.
+    /// It is `None` if `K` is zero.
+    ///
+    /// It is not differentiable.
+    pub contributor_weights_2d: Option<Tensor<AB, 3>>,
+    /// 2D depths in view space.
+    ///
+    /// The shape is `[I_y, I_x]`.
+    ///
+    /// It is the alpha-blended depth map onto a zero background,
+    /// i.e., the expected depth weighted by the accumulated opacity.
+    ///
+    /// It is differentiable with respect to the 3D positions and the opacities.
+    pub depths_2d: Tensor<AB, 2>,
+    /// 2D median depths in view space.
+    ///
+    /// The shape is `[I_y, I_x]`.
+    ///
+    /// It is the depth of the point where the transmittance falls below one half,
+    /// or zero where it never does.
+    ///
+    /// It is not differentiable.
+    pub depths_2d_median: Tensor<AB, 2>,
+    /// 2D features.
+    ///
+    /// The shape is `[I_y, I_x, D]`.
+    /// - `D`: Feature count.
+    ///
+    /// It is the alpha-blended
+    /// [point features](crate::scene::gaussian_3d::Gaussian3dScene::features)
+    /// onto a zero background, using the same weights as the colors.
+    /// It is `None` if the scene has no features.
+    ///
+    /// It is differentiable with respect to the features and the geometry of the points.
+    pub features_2d: Option<Tensor<AB, 3>>,
+    /// Its gradient is the gradient of the focal lengths.
+    ///
+    /// The shape is `[2]`, and so is the gradient shape,
+    /// which is `[f_x, f_y]` in pixels.
+    ///
+    /// It can be used to refine the intrinsics with
+    /// [`View::set_intrinsics`].
+    pub focal_lengths_grad_ref: Tensor<AB, 1>,
+    /// 2D label coverages.
+    ///
+    /// The shape is `[I_y, I_x, L]`.
+    /// - `L`: Label count.
+    ///
+    /// It is the accumulated blending weight of the points of each
+    /// [label](crate::scene::gaussian_3d::Gaussian3dScene::labels) in each pixel,
+    /// whose sum over the labels is no more than the 2D alpha.
+    /// It is `None` if the scene has no labels.
+    ///
+    /// It is not differentiable.
+    pub label_coverages_2d: Option<Tensor<AB, 3>>,
+    /// 2D labels.
+    ///
+    /// The shape is `[I_y, I_x]`.
+    ///
+    /// It is the label of the point with the highest blending weight in each pixel,
+    /// or [`LABEL_2D_NONE`](jit::kernel::rasterize_labels::LABEL_2D_NONE)
+    /// where no point is rendered.
+    /// It is `None` if the scene has no labels.
+    pub labels_2d: Option<Tensor<AB::InnerBackend, 2, Int>>,
+    /// Its gradient is the norm of the absolute gradients of the 2D positions.
+    ///
+    /// The gradient shape is `[P]`.
+    /// - `P`: Point count.
+    ///
+    /// It has no gradient unless
+    /// [`positions_2d_grad_abs`](Gaussian3dRenderOptions::positions_2d_grad_abs)
+    /// is enabled.
+    ///
+    /// ## Usage
+    ///
+    /// ```ignore
+    /// use burn::backend::autodiff::grads::Gradients;
+    ///
+    /// let mut grads: Gradients = todo!();
+    ///
+    /// let positions_2d_grad_abs_norm =
+    ///     positions_2d_grad_abs_norm_ref.grad_remove(&mut grads);
+    /// ```
+    pub positions_2d_grad_abs_norm_ref: Tensor<AB, 1>,
     /// Its gradient is the gradient norm of the 2D positions.
     ///
     /// The gradient shape is `[P]`.
@@ -91,6 +340,33 @@ pub struct Gaussian3dRenderOutputAutodiff<AB: AutodiffBackend> {
     /// The shape is `[P]`.
     /// - `P`: Point count.
     pub radii: Tensor<AB::InnerBackend, 1, Int>,
+    /// Its gradient is the gradient of the view transform in tangent space.
+    ///
+    /// The shape is `[6]`, and so is the gradient shape,
+    /// which is `[ρ[3], φ[3]]`
+    /// of the left perturbation `exp(ξ) * Tv`.
+    /// - `ρ`: Translation in view space.
+    /// - `φ`: Rotation vector in view space.
+    ///
+    /// It can be used to refine the pose with [`View::update_transform`].
+    ///
+    /// ## Usage
+    ///
+    /// ```ignore
+    /// use burn::{
+    ///     backend::autodiff::grads::Gradients, module::Param, optim::GradientsParams,
+    /// };
+    ///
+    /// let mut grads: Gradients = todo!();
+    /// let mut grads_params: GradientsParams = todo!();
+    /// // The learnable pose delta of the view. The shape is `[6]`.
+    /// let pose: Param<Tensor<AB, 1>> = todo!();
+    ///
+    /// if let Some(view_transform_grad) = view_transform_grad_ref.grad_remove(&mut grads) {
+    ///     grads_params.register(pose.id, view_transform_grad);
+    /// }
+    /// ```
+    pub view_transform_grad_ref: Tensor<AB, 1>,
 }
 
 impl Default for Gaussian3dRenderOptions {
@@ -100,6 +376,112 @@ impl Default for Gaussian3dRenderOptions {
     }
 }
 
+impl<B: Backend> Gaussian3dRenderOutput<B> {
+    /// Concatenate the outputs along the given dimension.
+    ///
+    /// The dimension is either `0` for `I_y` or `1` for `I_x`.
+    /// The optional outputs are concatenated only if all outputs have them.
+    pub fn cat(
+        outputs: Vec<Self>,
+        dim: usize,
+    ) -> Self {
+        let mut alphas_2d = Vec::with_capacity(outputs.len());
+        let mut colors_rgb_2d = Vec::with_capacity(outputs.len());
+        let mut contributor_indices_2d = Some(Vec::with_capacity(outputs.len()));
+        let mut contributor_weights_2d = Some(Vec::with_capacity(outputs.len()));
+        let mut depths_2d = Vec::with_capacity(outputs.len());
+        let mut depths_2d_median = Vec::with_capacity(outputs.len());
+        let mut features_2d = Some(Vec::with_capacity(outputs.len()));
+        let mut label_coverages_2d = Some(Vec::with_capacity(outputs.len()));
+        let mut labels_2d = Some(Vec::with_capacity(outputs.len()));
+        for output in outputs {
+            alphas_2d.push(output.alphas_2d);
+            colors_rgb_2d.push(output.colors_rgb_2d);
+            contributor_indices_2d = contributor_indices_2d
+                .zip(output.contributor_indices_2d)
+                .map(|(mut i, i_2d)| {
+                    i.push(i_2d);
+                    i
+                });
+            contributor_weights_2d = contributor_weights_2d
+                .zip(output.contributor_weights_2d)
+                .map(|(mut w, w_2d)| {
+                    w.push(w_2d);
+                    w
+                });
+            depths_2d.push(output.depths_2d);
+            depths_2d_median.push(output.depths_2d_median);
+            features_2d = features_2d.zip(output.features_2d).map(|(mut f, f_2d)| {
+                f.push(f_2d);
+                f
+            });
+            label_coverages_2d =
+                label_coverages_2d
+                    .zip(output.label_coverages_2d)
+                    .map(|(mut c, c_2d)| {
+                        c.push(c_2d);
+                        c
+                    });
+            labels_2d = labels_2d.zip(output.labels_2d).map(|(mut l, l_2d)| {
+                l.push(l_2d);
+                l
+            });
+        }
+
+        Self {
+            alphas_2d: Tensor::cat(alphas_2d, dim),
+            colors_rgb_2d: Tensor::cat(colors_rgb_2d, dim),
+            contributor_indices_2d: contributor_indices_2d
+                .map(|contributor_indices_2d| Tensor::cat(contributor_indices_2d, dim)),
+            contributor_weights_2d: contributor_weights_2d
+                .map(|contributor_weights_2d| Tensor::cat(contributor_weights_2d, dim)),
+            depths_2d: Tensor::cat(depths_2d, dim),
+            depths_2d_median: Tensor::cat(depths_2d_median, dim),
+            features_2d: features_2d.map(|features_2d| Tensor::cat(features_2d, dim)),
+            label_coverages_2d: label_coverages_2d
+                .map(|label_coverages_2d| Tensor::cat(label_coverages_2d, dim)),
+            labels_2d: labels_2d.map(|labels_2d| Tensor::cat(labels_2d, dim)),
+        }
+    }
+
+    /// Composite the 2D colors onto the background using the 2D alphas.
+    ///
+    /// The shape of `background_rgb_2d` should be broadcastable to `[I_y, I_x, 3]`,
+    /// e.g., `[1, 1, 3]` for a color or `[I_y, I_x, 3]` for an image.
+    ///
+    /// The scene should be rendered onto black, which is the default
+    /// [background color](Gaussian3dRenderOptions::background_rgb).
+    pub fn composite_background(
+        &self,
+        background_rgb_2d: Tensor<B, 3>,
+    ) -> Tensor<B, 3> {
+        let transmittances = self.alphas_2d.to_owned().neg().add_scalar(1.0);
+        self.colors_rgb_2d.to_owned()
+            + background_rgb_2d * transmittances.unsqueeze_dim(2)
+    }
+}
+
+impl<AB: AutodiffBackend> Gaussian3dRenderOutputAutodiff<AB> {
+    /// Composite the 2D colors onto the background using the 2D alphas.
+    ///
+    /// The shape of `background_rgb_2d` should be broadcastable to `[I_y, I_x, 3]`,
+    /// e.g., `[1, 1, 3]` for a color or `[I_y, I_x, 3]` for an image.
+    ///
+    /// The scene should be rendered onto black, which is the default
+    /// [background color](Gaussian3dRenderOptions::background_rgb).
+    ///
+    /// It is differentiable with respect to `background_rgb_2d`,
+    /// so the background can be learnable.
+    pub fn composite_background(
+        &self,
+        background_rgb_2d: Tensor<AB, 3>,
+    ) -> Tensor<AB, 3> {
+        let transmittances = self.alphas_2d.to_owned().neg().add_scalar(1.0);
+        self.colors_rgb_2d.to_owned()
+            + background_rgb_2d * transmittances.unsqueeze_dim(2)
+    }
+}
+
 #[cfg(not(test))]
 impl<B: Backend> fmt::Debug for Gaussian3dRenderOutput<B> {
     fn fmt(
@@ -107,7 +489,42 @@ impl<B: Backend> fmt::Debug for Gaussian3dRenderOutput<B> {
         f: &mut fmt::Formatter<'_>,
     ) -> fmt::Result {
         f.debug_struct(&format!("RenderOutput<{}>", B::name()))
+            .field("alphas_2d.dims()", &self.alphas_2d.dims())
             .field("colors_rgb_2d.dims()", &self.colors_rgb_2d.dims())
+            .field(
+                "contributor_indices_2d.dims()",
+                &self
+                    .contributor_indices_2d
+                    .as_ref()
+                    .map(|contributor_indices_2d| contributor_indices_2d.dims()),
+            )
+            .field(
+                "contributor_weights_2d.dims()",
+                &self
+                    .contributor_weights_2d
+                    .as_ref()
+                    .map(|contributor_weights_2d| contributor_weights_2d.dims()),
+            )
+            .field("depths_2d.dims()", &self.depths_2d.dims())
+            .field("depths_2d_median.dims()", &self.depths_2d_median.dims())
+            .field(
+                "features_2d.dims()",
+                &self
+                    .features_2d
+                    .as_ref()
+                    .map(|features_2d| features_2d.dims()),
+            )
+            .field(
+                "label_coverages_2d.dims()",
+                &self
+                    .label_coverages_2d
+                    .as_ref()
+                    .map(|label_coverages_2d| label_coverages_2d.dims()),
+            )
+            .field(
+                "labels_2d.dims()",
+                &self.labels_2d.as_ref().map(|labels_2d| labels_2d.dims()),
+            )
             .finish()
     }
 }
@@ -119,15 +536,59 @@ impl<AB: AutodiffBackend> fmt::Debug for Gaussian3dRenderOutputAutodiff<AB> {
         f: &mut fmt::Formatter<'_>,
     ) -> fmt::Result {
         let radii_dims = self.radii.dims();
+        let positions_2d_grad_abs_norm_dims = &radii_dims;
         let positions_2d_grad_norm_dims = &radii_dims;
+        let focal_lengths_grad_dims = [2];
+        let view_transform_grad_dims = [6];
 
         f.debug_struct(&format!("RenderOutputAutodiff<{}>", AB::name()))
+            .field("alphas_2d.dims()", &self.alphas_2d.dims())
             .field("colors_rgb_2d.dims()", &self.colors_rgb_2d.dims())
+            .field(
+                "contributor_indices_2d.dims()",
+                &self
+                    .contributor_indices_2d
+                    .as_ref()
+                    .map(|contributor_indices_2d| contributor_indices_2d.dims()),
+            )
+            .field(
+                "contributor_weights_2d.dims()",
+                &self
+                    .contributor_weights_2d
+                    .as_ref()
+                    .map(|contributor_weights_2d| contributor_weights_2d.dims()),
+            )
+            .field("depths_2d.dims()", &self.depths_2d.dims())
+            .field("depths_2d_median.dims()", &self.depths_2d_median.dims())
+            .field(
+                "features_2d.dims()",
+                &self
+                    .features_2d
+                    .as_ref()
+                    .map(|features_2d| features_2d.dims()),
+            )
+            .field("focal_lengths_grad.dims()", &focal_lengths_grad_dims)
+            .field(
+                "label_coverages_2d.dims()",
+                &self
+                    .label_coverages_2d
+                    .as_ref()
+                    .map(|label_coverages_2d| label_coverages_2d.dims()),
+            )
+            .field(
+                "labels_2d.dims()",
+                &self.labels_2d.as_ref().map(|labels_2d| labels_2d.dims()),
+            )
+            .field(
+                "positions_2d_grad_abs_norm.dims()",
+                &positions_2d_grad_abs_norm_dims,
+            )
             .field(
                 "positions_2d_grad_norm.dims()",
                 &positions_2d_grad_norm_dims,
             )
             .field("radii.dims()", &radii_dims)
+            .field("view_transform_grad.dims()", &view_transform_grad_dims)
             .finish()
     }
 }
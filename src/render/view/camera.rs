@@ -0,0 +1,461 @@
+//! Camera models.
+
+/// The camera model of a view.
+///
+/// The lens distortion is applied to the normalized position
+/// `Pn = [Pv.x / Pv.z, Pv.y / Pv.z]` before the pinhole intrinsics.
+/// The orthographic camera model has no perspective division,
+/// i.e., `Pn = [Pv.x, Pv.y]`.
+/// The equirectangular camera model maps the longitude and latitude to `Pn`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CameraModel {
+    /// Pinhole camera without distortion.
+    #[default]
+    Pinhole,
+    /// Camera with radial-tangential distortion.
+    ///
+    /// It is the rational model of OpenCV with `k5 = k6 = 0`.
+    /// COLMAP `OPENCV` camera is the case of `k3 = k4 = 0`.
+    ///
+    /// ```plaintext
+    /// r^2 = Pn.x^2 + Pn.y^2
+    /// ρ = (1 + k1 * r^2 + k2 * r^4 + k3 * r^6) / (1 + k4 * r^2)
+    /// Pn'.x = Pn.x * ρ + 2 * p1 * Pn.x * Pn.y + p2 * (r^2 + 2 * Pn.x^2)
+    /// Pn'.y = Pn.y * ρ + p1 * (r^2 + 2 * Pn.y^2) + 2 * p2 * Pn.x * Pn.y
+    /// ```
+    OpenCv {
+        /// Radial coefficient of `r^2`.
+        k1: f64,
+        /// Radial coefficient of `r^4`.
+        k2: f64,
+        /// Radial coefficient of `r^6`.
+        k3: f64,
+        /// Radial coefficient of `r^2` in the denominator.
+        k4: f64,
+        /// Tangential coefficient.
+        p1: f64,
+        /// Tangential coefficient.
+        p2: f64,
+    },
+    /// Camera with equidistant fisheye distortion.
+    ///
+    /// It is the fisheye model of OpenCV.
+    /// COLMAP `OPENCV_FISHEYE` camera is the same.
+    ///
+    /// ```plaintext
+    /// r = √(Pn.x^2 + Pn.y^2)
+    /// θ = atan(r)
+    /// θ_d = θ * (1 + k1 * θ^2 + k2 * θ^4 + k3 * θ^6 + k4 * θ^8)
+    /// Pn' = Pn * θ_d / r
+    /// ```
+    Fisheye {
+        /// Coefficient of `θ^2`.
+        k1: f64,
+        /// Coefficient of `θ^4`.
+        k2: f64,
+        /// Coefficient of `θ^6`.
+        k3: f64,
+        /// Coefficient of `θ^8`.
+        k4: f64,
+    },
+    /// Orthographic camera without distortion.
+    ///
+    /// The visible extent in view space is `I / F = 2 * tan(Fov / 2)`,
+    /// so the field of view only determines the scale.
+    ///
+    /// ```plaintext
+    /// Pn = [Pv.x, Pv.y]
+    /// ```
+    Orthographic,
+    /// Panoramic camera with equirectangular projection.
+    ///
+    /// The image covers 360° horizontally and 180° vertically,
+    /// so the field of view is ignored, i.e., `F_x = I_x / 2π` and `F_y = I_y / π`.
+    /// The depth is the distance to the view position instead of `Pv.z`.
+    ///
+    /// ```plaintext
+    /// Pn.x = atan2(Pv.x, Pv.z)
+    /// Pn.y = atan2(Pv.y, √(Pv.x^2 + Pv.z^2))
+    /// ```
+    Equirectangular,
+}
+
+impl CameraModel {
+    /// Maximum of the incident angle `θ` of fisheye cameras in radians.
+    pub const FISHEYE_ANGLE_MAX: f64 = 1.5;
+
+    /// The identifier of the camera model in the kernels.
+    ///
+    /// - `0`: [`Pinhole`](Self::Pinhole)
+    /// - `1`: [`OpenCv`](Self::OpenCv)
+    /// - `2`: [`Fisheye`](Self::Fisheye)
+    /// - `3`: [`Orthographic`](Self::Orthographic)
+    /// - `4`: [`Equirectangular`](Self::Equirectangular)
+    #[inline]
+    pub const fn id(&self) -> u32 {
+        match self {
+            Self::Pinhole => 0,
+            Self::OpenCv { .. } => 1,
+            Self::Fisheye { .. } => 2,
+            Self::Orthographic => 3,
+            Self::Equirectangular => 4,
+        }
+    }
+
+    /// The distortion coefficients `[k1, k2, k3, k4, p1, p2]`.
+    ///
+    /// The absent coefficients are zero.
+    #[inline]
+    pub const fn coefficients(&self) -> [f64; 6] {
+        match *self {
+            Self::Pinhole | Self::Orthographic | Self::Equirectangular => [0.0; 6],
+            Self::OpenCv {
+                k1,
+                k2,
+                k3,
+                k4,
+                p1,
+                p2,
+            } => [k1, k2, k3, k4, p1, p2],
+            Self::Fisheye { k1, k2, k3, k4 } => [k1, k2, k3, k4, 0.0, 0.0],
+        }
+    }
+
+    /// Return `true` if the camera model has distortion.
+    #[inline]
+    pub const fn is_distorted(&self) -> bool {
+        matches!(self, Self::OpenCv { .. } | Self::Fisheye { .. })
+    }
+
+    /// Return `true` if the camera model is equirectangular.
+    #[inline]
+    pub const fn is_equirectangular(&self) -> bool {
+        matches!(self, Self::Equirectangular)
+    }
+
+    /// Return `true` if the camera model is orthographic.
+    #[inline]
+    pub const fn is_orthographic(&self) -> bool {
+        matches!(self, Self::Orthographic)
+    }
+
+    /// Return `true` if the camera model has no distortion.
+    #[inline]
+    pub const fn is_pinhole(&self) -> bool {
+        matches!(self, Self::Pinhole)
+    }
+
+    /// Distort the normalized position `Pn`.
+    ///
+    /// It returns the distorted position `Pn'` and the Jacobian `∂Pn'/∂Pn`,
+    /// which is in **column-major order**, i.e., `J[col][row]`.
+    pub fn distort(
+        &self,
+        position: [f64; 2],
+    ) -> ([f64; 2], [[f64; 2]; 2]) {
+        let [x, y] = position;
+        let r2 = x * x + y * y;
+        match *self {
+            Self::Pinhole | Self::Orthographic | Self::Equirectangular => {
+                (position, [[1.0, 0.0], [0.0, 1.0]])
+            },
+            Self::OpenCv {
+                k1,
+                k2,
+                k3,
+                k4,
+                p1,
+                p2,
+            } => {
+                let numerator = 1.0 + r2 * (k1 + r2 * (k2 + r2 * k3));
+                let numerator_grad = k1 + r2 * (2.0 * k2 + r2 * 3.0 * k3);
+                let denominator = 1.0 + k4 * r2;
+                let rho = numerator / denominator;
+                let rho_grad = (numerator_grad - rho * k4) / denominator;
+                let distorted = [
+                    x * rho + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+                    y * rho + p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+                ];
+                let xy = 2.0 * rho_grad * x * y + 2.0 * p1 * x + 2.0 * p2 * y;
+                let jacobian = [
+                    [
+                        rho + 2.0 * rho_grad * x * x + 2.0 * p1 * y + 6.0 * p2 * x,
+                        xy,
+                    ],
+                    [
+                        xy,
+                        rho + 2.0 * rho_grad * y * y + 6.0 * p1 * y + 2.0 * p2 * x,
+                    ],
+                ];
+                (distorted, jacobian)
+            },
+            Self::Fisheye { k1, k2, k3, k4 } => {
+                let r = r2.sqrt();
+                if r < 1e-12 {
+                    return (position, [[1.0, 0.0], [0.0, 1.0]]);
+                }
+                let angle = r.atan();
+                let angle2 = angle * angle;
+                let angle_distorted = angle
+                    * (1.0 + angle2 * (k1 + angle2 * (k2 + angle2 * (k3 + angle2 * k4))));
+                let angle_distorted_grad = (1.0
+                    + angle2
+                        * (3.0 * k1
+                            + angle2
+                                * (5.0 * k2 + angle2 * (7.0 * k3 + angle2 * 9.0 * k4))))
+                    / (1.0 + r2);
+                let scale = angle_distorted / r;
+                let scale_grad = (angle_distorted_grad - scale) / r2;
+                let distorted = [x * scale, y * scale];
+                let xy = scale_grad * x * y;
+                let jacobian = [
+                    [scale + scale_grad * x * x, xy],
+                    [xy, scale + scale_grad * y * y],
+                ];
+                (distorted, jacobian)
+            },
+        }
+    }
+
+    /// Undistort the bounds of the distorted normalized positions.
+    ///
+    /// The bounds are `[x max, x min, y max, y min]`.
+    /// Each bound is the extreme of the undistorted positions on its edge,
+    /// which is sampled densely from corner to corner and at the axis.
+    pub fn undistort_bounds(
+        &self,
+        bounds_distorted: [f64; 4],
+    ) -> [f64; 4] {
+        const SAMPLE_COUNT: usize = 64;
+
+        if !self.is_distorted() {
+            return bounds_distorted;
+        }
+
+        let [x_max, x_min, y_max, y_min] = bounds_distorted;
+        // The samples from `min` to `max` inclusively, and zero
+        let samples = |max: f64, min: f64| {
+            (0..=SAMPLE_COUNT)
+                .map(move |i| {
+                    let t = i as f64 / SAMPLE_COUNT as f64;
+                    max * t + min * (1.0 - t)
+                })
+                .chain([0.0])
+        };
+        let bound_x =
+            |x: f64| samples(y_max, y_min).map(move |y| self.undistort([x, y])[0]);
+        let bound_y =
+            |y: f64| samples(x_max, x_min).map(move |x| self.undistort([x, y])[1]);
+        [
+            bound_x(x_max).fold(f64::NEG_INFINITY, f64::max),
+            bound_x(x_min).fold(f64::INFINITY, f64::min),
+            bound_y(y_max).fold(f64::NEG_INFINITY, f64::max),
+            bound_y(y_min).fold(f64::INFINITY, f64::min),
+        ]
+    }
+
+    /// Undistort the distorted normalized position `Pn'`.
+    ///
+    /// It returns the normalized position `Pn` using Newton's method.
+    /// The incident angle of fisheye cameras is clamped to
+    /// [`FISHEYE_ANGLE_MAX`](Self::FISHEYE_ANGLE_MAX).
+    pub fn undistort(
+        &self,
+        position_distorted: [f64; 2],
+    ) -> [f64; 2] {
+        const ITERATION_COUNT: usize = 20;
+
+        let mut position = match *self {
+            Self::Pinhole | Self::Orthographic | Self::Equirectangular => {
+                return position_distorted;
+            },
+            Self::OpenCv { .. } => position_distorted,
+            Self::Fisheye { .. } => {
+                let [x, y] = position_distorted;
+                let r = (x * x + y * y).sqrt();
+                if r < 1e-12 {
+                    return position_distorted;
+                }
+                let scale = r.min(Self::FISHEYE_ANGLE_MAX).tan() / r;
+                [x * scale, y * scale]
+            },
+        };
+
+        for _ in 0..ITERATION_COUNT {
+            let (distorted, [[j00, j01], [j10, j11]]) = self.distort(position);
+            let residual = [
+                distorted[0] - position_distorted[0],
+                distorted[1] - position_distorted[1],
+            ];
+            let determinant = j00 * j11 - j10 * j01;
+            if determinant.abs() < 1e-12 {
+                break;
+            }
+            position[0] -= (j11 * residual[0] - j10 * residual[1]) / determinant;
+            position[1] -= (j00 * residual[1] - j01 * residual[0]) / determinant;
+        }
+
+        if let Self::Fisheye { .. } = self {
+            let [x, y] = position;
+            let r = (x * x + y * y).sqrt();
+            let r_max = Self::FISHEYE_ANGLE_MAX.tan();
+            if r > r_max {
+                position = [x * r_max / r, y * r_max / r];
+            }
+        }
+
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn distort_and_undistort() {
+        use super::*;
+
+        let models = [
+            CameraModel::Pinhole,
+            CameraModel::Orthographic,
+            CameraModel::Equirectangular,
+            CameraModel::OpenCv {
+                k1: -0.12,
+                k2: 0.05,
+                k3: -0.01,
+                k4: 0.02,
+                p1: 0.001,
+                p2: -0.002,
+            },
+            CameraModel::Fisheye {
+                k1: 0.03,
+                k2: -0.01,
+                k3: 0.002,
+                k4: -0.0005,
+            },
+        ];
+        let target = [0.31, -0.42];
+
+        for model in models {
+            let (distorted, _) = model.distort(target);
+            let output = model.undistort(distorted);
+            assert!((output[0] - target[0]).abs() < 1e-9, "{model:?}");
+            assert!((output[1] - target[1]).abs() < 1e-9, "{model:?}");
+        }
+    }
+
+    #[test]
+    fn distort_jacobian() {
+        use super::*;
+
+        let models = [
+            CameraModel::OpenCv {
+                k1: -0.12,
+                k2: 0.05,
+                k3: -0.01,
+                k4: 0.02,
+                p1: 0.001,
+                p2: -0.002,
+            },
+            CameraModel::Fisheye {
+                k1: 0.03,
+                k2: -0.01,
+                k3: 0.002,
+                k4: -0.0005,
+            },
+        ];
+        let position = [0.31, -0.42];
+        let epsilon = 1e-6;
+
+        for model in models {
+            let (_, output) = model.distort(position);
+            for axis in 0..2 {
+                let mut position_next = position;
+                let mut position_prev = position;
+                position_next[axis] += epsilon;
+                position_prev[axis] -= epsilon;
+                let next = model.distort(position_next).0;
+                let prev = model.distort(position_prev).0;
+                for row in 0..2 {
+                    let target = (next[row] - prev[row]) / (2.0 * epsilon);
+                    assert!((output[axis][row] - target).abs() < 1e-6, "{model:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn undistort_bounds() {
+        use super::*;
+
+        let bounds = [0.6, -0.5, 0.4, -0.3];
+
+        let model = CameraModel::Pinhole;
+        assert_eq!(model.undistort_bounds(bounds), bounds);
+
+        // Barrel distortion
+        let model = CameraModel::OpenCv {
+            k1: -0.2,
+            k2: 0.0,
+            k3: 0.0,
+            k4: 0.0,
+            p1: 0.0,
+            p2: 0.0,
+        };
+        let output = model.undistort_bounds(bounds);
+        let corner = model.undistort([bounds[0], bounds[2]]);
+        assert_eq!(output[0], corner[0]);
+        assert_eq!(output[2], corner[1]);
+        assert!(output[0] > model.undistort([bounds[0], 0.0])[0]);
+        assert!(output[1] < bounds[1]);
+        assert!(output[3] < bounds[3]);
+
+        // Large distortion of both signs, where the extremes may lie inside the edges
+        fn scan(f: impl Fn(f64) -> f64) -> impl Iterator<Item = f64> {
+            (0..=1000).map(move |i| f(i as f64 / 1e3))
+        }
+        let bounds = [0.5, -0.4, 0.3, -0.25];
+        let lerp = |max: f64, min: f64, t: f64| max * t + min * (1.0 - t);
+        for (k1, k2) in [(-0.3, 0.0), (0.4, 0.0), (0.4, -0.4)] {
+            let model = CameraModel::OpenCv {
+                k1,
+                k2,
+                k3: 0.0,
+                k4: 0.0,
+                p1: 0.0,
+                p2: 0.0,
+            };
+            let output = model.undistort_bounds(bounds);
+
+            // The extremes on the edges scanned exhaustively
+            let target = [
+                scan(|t| model.undistort([bounds[0], lerp(bounds[2], bounds[3], t)])[0])
+                    .fold(f64::NEG_INFINITY, f64::max),
+                scan(|t| model.undistort([bounds[1], lerp(bounds[2], bounds[3], t)])[0])
+                    .fold(f64::INFINITY, f64::min),
+                scan(|t| model.undistort([lerp(bounds[0], bounds[1], t), bounds[2]])[1])
+                    .fold(f64::NEG_INFINITY, f64::max),
+                scan(|t| model.undistort([lerp(bounds[0], bounds[1], t), bounds[3]])[1])
+                    .fold(f64::INFINITY, f64::min),
+            ];
+            for (o, t) in output.into_iter().zip(target) {
+                assert!((o - t).abs() < 1e-4, "{model:?}: {output:?}, {target:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn undistort_fisheye_angle_max() {
+        use super::*;
+
+        let model = CameraModel::Fisheye {
+            k1: 0.0,
+            k2: 0.0,
+            k3: 0.0,
+            k4: 0.0,
+        };
+        let output = model.undistort([2.0, 0.0]);
+        let target = [CameraModel::FISHEYE_ANGLE_MAX.tan(), 0.0];
+        assert!((output[0] - target[0]).abs() < 1e-9);
+        assert_eq!(output[1], target[1]);
+    }
+}
@@ -1,12 +1,16 @@
 //! View module.
 
+pub mod camera;
 pub mod views;
 
+pub use camera::*;
 pub use views::*;
 
 /// A view in 3D space.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct View {
+    /// The camera model, which specifies the projection and the lens distortion.
+    pub camera_model: CameraModel,
     /// The horizontal field of view in radians.
     pub field_of_view_x: f64,
     /// The vertical field of view in radians.
@@ -15,6 +19,10 @@ pub struct View {
     pub image_height: u32,
     /// Image width.
     pub image_width: u32,
+    /// The horizontal offset of the principal point from the image center in pixels.
+    pub principal_point_offset_x: f64,
+    /// The vertical offset of the principal point from the image center in pixels.
+    pub principal_point_offset_y: f64,
     /// View ID.
     pub view_id: u32,
     /// View position in world space.
@@ -52,6 +60,130 @@ impl View {
             [t[0][0], t[0][1], t[0][2], 1.0],
         ]
     }
+
+    /// Update the view transform by a delta in tangent space.
+    ///
+    /// `delta` is `[ρ[3], φ[3]]`, which updates `Tv` to `[exp(φ) | ρ] * Tv`.
+    /// - `ρ`: Translation in view space.
+    /// - `φ`: Rotation vector in view space.
+    ///
+    /// It applies the gradient of
+    /// [`view_transform_grad_ref`](super::gaussian_3d::Gaussian3dRenderOutputAutodiff::view_transform_grad_ref)
+    /// to the pose, and the view position is updated accordingly.
+    pub fn update_transform(
+        &mut self,
+        delta: &[f64; 6],
+    ) -> &mut Self {
+        let [rho_x, rho_y, rho_z, phi_x, phi_y, phi_z] = *delta;
+        let translation = [rho_x, rho_y, rho_z];
+
+        // exp(φ)[3, 3] = I + sin(θ) / θ * [φ]x + (1 - cos(θ)) / θ^2 * [φ]x^2
+        // θ = |φ|
+        let angle = (phi_x * phi_x + phi_y * phi_y + phi_z * phi_z).sqrt();
+        let (a, b) = if angle < 1e-8 {
+            (1.0, 0.5)
+        } else {
+            (angle.sin() / angle, (1.0 - angle.cos()) / (angle * angle))
+        };
+        // [φ]x (Row-major)
+        let k = [
+            [0.0, -phi_z, phi_y],
+            [phi_z, 0.0, -phi_x],
+            [-phi_y, phi_x, 0.0],
+        ];
+        // exp(φ) (Row-major)
+        let rotation: [[f64; 3]; 3] = std::array::from_fn(|row| {
+            std::array::from_fn(|col| {
+                let k_2 = (0..3).map(|i| k[row][i] * k[i][col]).sum::<f64>();
+                let identity = if row == col { 1.0 } else { 0.0 };
+                identity + a * k[row][col] + b * k_2
+            })
+        });
+
+        // Tv'[4, 4] = [exp(φ) | ρ] * Tv[4, 4]
+        let m = self.view_transform;
+        self.view_transform = std::array::from_fn(|col| {
+            std::array::from_fn(|row| {
+                if row == 3 {
+                    return m[col][3];
+                }
+                let offset = if col == 3 { translation[row] } else { 0.0 };
+                (0..3).map(|i| rotation[row][i] * m[col][i]).sum::<f64>() + offset
+            })
+        });
+
+        // V[3] = -Rv^t[3, 3] * Tv[3]
+        let m = self.view_transform;
+        self.view_position = std::array::from_fn(|i| {
+            -(0..3).map(|row| m[i][row] * m[3][row]).sum::<f64>()
+        });
+
+        self
+    }
+}
+
+/// Pinhole intrinsics.
+impl View {
+    /// Return the horizontal focal length in pixels.
+    ///
+    /// `F_x = I_x / tan(Fov_x / 2) / 2`
+    ///
+    /// It is `I_x / 2π` for [`CameraModel::Equirectangular`].
+    #[inline]
+    pub fn focal_length_x(&self) -> f64 {
+        if self.camera_model.is_equirectangular() {
+            return self.image_width as f64 / std::f64::consts::TAU;
+        }
+        self.image_width as f64 / (self.field_of_view_x / 2.0).tan() / 2.0
+    }
+
+    /// Return the vertical focal length in pixels.
+    ///
+    /// `F_y = I_y / tan(Fov_y / 2) / 2`
+    ///
+    /// It is `I_y / π` for [`CameraModel::Equirectangular`].
+    #[inline]
+    pub fn focal_length_y(&self) -> f64 {
+        if self.camera_model.is_equirectangular() {
+            return self.image_height as f64 / std::f64::consts::PI;
+        }
+        self.image_height as f64 / (self.field_of_view_y / 2.0).tan() / 2.0
+    }
+
+    /// Return the horizontal principal point in pixels.
+    ///
+    /// `C_x = I_x / 2 + O_x`
+    #[inline]
+    pub fn principal_point_x(&self) -> f64 {
+        self.image_width as f64 / 2.0 + self.principal_point_offset_x
+    }
+
+    /// Return the vertical principal point in pixels.
+    ///
+    /// `C_y = I_y / 2 + O_y`
+    #[inline]
+    pub fn principal_point_y(&self) -> f64 {
+        self.image_height as f64 / 2.0 + self.principal_point_offset_y
+    }
+
+    /// Set the pinhole intrinsics in pixels, e.g., `fx, fy, cx, cy` of COLMAP cameras.
+    ///
+    /// The image size should be specified before.
+    pub fn set_intrinsics(
+        &mut self,
+        focal_length_x: f64,
+        focal_length_y: f64,
+        principal_point_x: f64,
+        principal_point_y: f64,
+    ) -> &mut Self {
+        let image_size_x = self.image_width as f64;
+        let image_size_y = self.image_height as f64;
+        self.field_of_view_x = 2.0 * (image_size_x / focal_length_x / 2.0).atan();
+        self.field_of_view_y = 2.0 * (image_size_y / focal_length_y / 2.0).atan();
+        self.principal_point_offset_x = principal_point_x - image_size_x / 2.0;
+        self.principal_point_offset_y = principal_point_y - image_size_y / 2.0;
+        self
+    }
 }
 
 /// Dimension operations
@@ -68,6 +200,8 @@ impl View {
         to: u32,
     ) -> &mut Self {
         let ratio = self.aspect_ratio();
+        let image_width = self.image_width as f64;
+        let image_height = self.image_height as f64;
         if ratio > 1.0 {
             self.image_width = to;
             self.image_height = (to as f32 / ratio).ceil() as u32;
@@ -75,6 +209,8 @@ impl View {
             self.image_width = (to as f32 * ratio).ceil() as u32;
             self.image_height = to;
         }
+        self.principal_point_offset_x *= self.image_width as f64 / image_width;
+        self.principal_point_offset_y *= self.image_height as f64 / image_height;
         self
     }
 }
@@ -126,6 +262,68 @@ mod tests {
         assert_eq!(output, target);
     }
 
+    #[test]
+    fn update_transform() {
+        use super::*;
+
+        let rotation = [
+            [0.9870946659543874, 0.011754269038001336, 0.1597058471183149],
+            [
+                -0.000481623211642526,
+                0.9975159094549839,
+                -0.07043989227191047,
+            ],
+            [-0.1601370927782764, 0.0694539238889973, 0.9846482945564589],
+        ];
+        let translation = [0.129242027423, 0.0, -0.3424233862];
+        let target = View {
+            view_transform: View::transform(&rotation, &translation),
+            ..Default::default()
+        };
+
+        let mut output = target;
+        output.update_transform(&[0.0; 6]);
+        for (o, t) in output
+            .view_transform
+            .iter()
+            .flatten()
+            .zip(target.view_transform.iter().flatten())
+        {
+            assert!((o - t).abs() < 1e-12);
+        }
+
+        let mut output = target;
+        output.update_transform(&[0.1, -0.2, 0.3, 0.0, 0.0, 0.0]);
+        assert_eq!(output.view_transform[0], target.view_transform[0]);
+        assert!((output.view_transform[3][0] - 0.229242027423).abs() < 1e-12);
+        assert!((output.view_transform[3][1] - -0.2).abs() < 1e-12);
+        assert!((output.view_transform[3][2] - -0.0424233862).abs() < 1e-12);
+
+        let mut output = View {
+            view_transform: View::transform(
+                &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+                &[0.0, 0.0, 2.0],
+            ),
+            ..Default::default()
+        };
+        output.update_transform(&[0.0, 0.0, 0.0, 0.0, 0.0, std::f64::consts::FRAC_PI_2]);
+        let target = [
+            [0.0, 1.0, 0.0, 0.0],
+            [-1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 2.0, 1.0],
+        ];
+        for (o, t) in output
+            .view_transform
+            .iter()
+            .flatten()
+            .zip(target.iter().flatten())
+        {
+            assert!((o - t).abs() < 1e-12);
+        }
+        assert!((output.view_position[2] - -2.0).abs() < 1e-12);
+    }
+
     #[test]
     fn resize_max() {
         use super::*;
@@ -147,5 +345,35 @@ mod tests {
         view.resize_max(1080);
         assert_eq!(view.image_width, 720);
         assert_eq!(view.image_height, 1080);
+
+        let mut view = View {
+            image_width: 2000,
+            image_height: 1000,
+            principal_point_offset_x: 20.0,
+            principal_point_offset_y: -10.0,
+            ..Default::default()
+        };
+        view.resize_max(1000);
+        assert_eq!(view.principal_point_offset_x, 10.0);
+        assert_eq!(view.principal_point_offset_y, -5.0);
+    }
+
+    #[test]
+    fn set_intrinsics() {
+        use super::*;
+
+        let mut view = View {
+            image_width: 1000,
+            image_height: 600,
+            ..Default::default()
+        };
+        view.set_intrinsics(800.0, 750.0, 510.0, 290.0);
+
+        assert!((view.focal_length_x() - 800.0).abs() < 1e-9);
+        assert!((view.focal_length_y() - 750.0).abs() < 1e-9);
+        assert_eq!(view.principal_point_x(), 510.0);
+        assert_eq!(view.principal_point_y(), 290.0);
+        assert_eq!(view.principal_point_offset_x, 10.0);
+        assert_eq!(view.principal_point_offset_y, -10.0);
     }
 }
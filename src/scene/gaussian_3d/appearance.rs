@@ -0,0 +1,223 @@
+//! 3DGS appearance compensation implementation.
+
+pub use super::*;
+
+/// Appearance initializers
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Initialize [`Gaussian3dScene::appearances`] for the given view count.
+    ///
+    /// The transforms are learnable and start from the identity,
+    /// so the rendered colors are unchanged until they are learned.
+    /// The view IDs should be less than `view_count`.
+    pub fn init_appearances(
+        &mut self,
+        view_count: usize,
+    ) -> &mut Self {
+        const IDENTITY: [f32; 12] =
+            [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+
+        let device = &self.device();
+        // [V, 3, 4]
+        let appearances = Tensor::<B, 3>::from_data(
+            TensorData::new(IDENTITY.repeat(view_count), [view_count, 3, 4]),
+            device,
+        )
+        .set_require_grad(true);
+
+        #[cfg(all(debug_assertions, not(test)))]
+        log::debug!(
+            target: "gausplat::renderer::gaussian_3d::scene",
+            "init_appearances",
+        );
+
+        self.set_inner_appearances(appearances)
+    }
+}
+
+/// Appearance transformers
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Check if the shape of [`Gaussian3dScene::appearances`] is `[V, 3, 4]`
+    /// and the view ID to transform is less than `V`.
+    pub(crate) fn check_appearances(
+        &self,
+        view: &render::View,
+        options: &Gaussian3dRenderOptions,
+    ) -> Result<(), Error> {
+        let appearances = match &self.appearances {
+            Some(appearances) if options.appearance => appearances,
+            _ => return Ok(()),
+        };
+
+        let dims = appearances.dims();
+        let view_count = dims[0];
+        let dims_target = [view_count, 3, 4];
+        if dims != dims_target {
+            return Err(Error::MismatchedTensorShape(
+                dims.into(),
+                dims_target.into(),
+            ));
+        }
+
+        let view_id = options.appearance_view_id.unwrap_or(view.view_id);
+        if view_id as usize >= view_count {
+            return Err(Error::InvalidAppearanceViewId(view_id, view_count));
+        }
+
+        Ok(())
+    }
+
+    /// Transform the rendered colors by the appearance of the view.
+    ///
+    /// The shape of `colors_rgb_2d` is `[I_y, I_x, 3]`.
+    /// It should be checked by [`Self::check_appearances`] previously.
+    pub(crate) fn transform_appearance(
+        &self,
+        colors_rgb_2d: Tensor<B, 3>,
+        view: &render::View,
+        options: &Gaussian3dRenderOptions,
+    ) -> Tensor<B, 3> {
+        let appearances = match &self.appearances {
+            Some(appearances) if options.appearance => appearances.val(),
+            _ => return colors_rgb_2d,
+        };
+
+        let [image_size_y, image_size_x, _] = colors_rgb_2d.dims();
+        let view_id = options.appearance_view_id.unwrap_or(view.view_id) as usize;
+
+        // [3, 4]
+        let appearance = appearances
+            .slice([view_id..view_id + 1, 0..3, 0..4])
+            .squeeze::<2>(0);
+        // [3, 3]
+        let matrix = appearance.to_owned().slice([0..3, 0..3]);
+        // [1, 3]
+        let bias = appearance.slice([0..3, 3..4]).transpose();
+
+        // [I_y, I_x, 3] <- [I_y * I_x, 3] * [3, 3] + [1, 3]
+        colors_rgb_2d
+            .reshape([image_size_y * image_size_x, 3])
+            .matmul(matrix.transpose())
+            .add(bias)
+            .reshape([image_size_y, image_size_x, 3])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::gaussian_3d::test_util::*;
+
+    #[test]
+    fn appearance_render_ndarray() {
+        let device = Default::default();
+        let mut scene = scene_from_points::<NdArray>(
+            &[
+                ([1.0, 0.5, 0.0], [0.1, -0.1, 4.0], 0.6, 0.2),
+                ([0.0, 0.5, 1.0], [-0.5, 0.3, 3.0], 0.4, 0.3),
+            ],
+            &device,
+        );
+
+        let target = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .colors_rgb_2d;
+
+        // The appearances start from the identity.
+        scene.init_appearances(2);
+        let output = scene
+            .render(&VIEW_IDENTITY, &Default::default())
+            .unwrap()
+            .colors_rgb_2d;
+        output
+            .into_data()
+            .assert_eq(&target.to_owned().into_data(), true);
+
+        // [A | b] = [0.5 * I | 0.1]
+        let appearance = Tensor::<NdArray, 3>::from_data(
+            [[
+                [0.5, 0.0, 0.0, 0.1],
+                [0.0, 0.5, 0.0, 0.1],
+                [0.0, 0.0, 0.5, 0.1],
+            ]],
+            &device,
+        );
+        let appearances = scene.appearances.as_ref().unwrap().val();
+        scene.set_inner_appearances(
+            appearances.slice_assign([1..2, 0..3, 0..4], appearance),
+        );
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default().with_appearance_view_id(Some(1)),
+            )
+            .unwrap()
+            .colors_rgb_2d;
+        output.into_data().assert_approx_eq(
+            &target
+                .to_owned()
+                .mul_scalar(0.5)
+                .add_scalar(0.1)
+                .into_data(),
+            6,
+        );
+
+        let output = scene
+            .render(
+                &VIEW_IDENTITY,
+                &Gaussian3dRenderOptions::default()
+                    .with_appearance(false)
+                    .with_appearance_view_id(Some(1)),
+            )
+            .unwrap()
+            .colors_rgb_2d;
+        output.into_data().assert_eq(&target.into_data(), true);
+
+        let output = scene.render(
+            &VIEW_IDENTITY,
+            &Gaussian3dRenderOptions::default().with_appearance_view_id(Some(2)),
+        );
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn appearance_render_ndarray_autodiff() {
+        let device = Default::default();
+        let mut scene = scene_from_points::<Autodiff<NdArray>>(
+            &[
+                ([1.0, 0.5, 0.0], [0.3, 0.2, 4.0], 0.7, 0.25),
+                ([0.2, 0.8, 0.4], [-0.3, -0.2, 5.0], 0.5, 0.4),
+            ],
+            &device,
+        );
+        scene.init_appearances(2);
+
+        let view = render::View {
+            view_id: 1,
+            ..VIEW_IDENTITY
+        };
+        let mut grads = scene
+            .render(&view, &Default::default())
+            .unwrap()
+            .colors_rgb_2d
+            .sum()
+            .backward();
+
+        let appearances_grad = scene
+            .appearances
+            .as_ref()
+            .unwrap()
+            .grad_remove(&mut grads)
+            .unwrap();
+        assert_eq!(appearances_grad.dims(), [2, 3, 4]);
+        let appearance_grad_other = appearances_grad.to_owned().slice([0..1, 0..3, 0..4]);
+        assert_eq!(appearance_grad_other.abs().sum().into_scalar(), 0.0);
+        // The gradient of the bias is the pixel count.
+        let bias_grad = appearances_grad.slice([1..2, 0..3, 3..4]);
+        bias_grad
+            .into_data()
+            .assert_approx_eq(&TensorData::new(vec![1024.0; 3], [1, 3, 1]), 3);
+        let colors_sh_grad = scene.colors_sh.grad_remove(&mut grads).unwrap();
+        assert!(colors_sh_grad.abs().sum().into_scalar() > 0.0);
+    }
+}
@@ -0,0 +1,1055 @@
+//! 3DGS adaptive density control implementation.
+
+pub use super::*;
+
+use burn::{config::Config, tensor::Bool};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use std::iter;
+
+/// 3DGS densification options.
+#[derive(Config, Copy, Debug, PartialEq)]
+pub struct Gaussian3dDensifyOptions {
+    /// The extent of the scene.
+    ///
+    /// It is the radius of the sphere enclosing the view positions,
+    /// which measures the sizes of the points.
+    pub extent: f64,
+    #[config(default = "0.0002")]
+    /// The threshold of the 2D position gradient norms.
+    ///
+    /// The points whose gradient norms are no less than it are densified.
+    pub grad_threshold: f64,
+    #[config(default = "false")]
+    /// Whether to threshold the norms of the absolute 2D position gradients.
+    ///
+    /// It selects the sums that [`Gaussian3dScene::densify_by_stats`] thresholds on.
+    /// The absolute gradients do not cancel out, so their norms are larger
+    /// and the [threshold](Self::grad_threshold) should be raised accordingly,
+    /// e.g., to `0.0008` as in AbsGS.
+    pub positions_2d_grad_abs: bool,
+    #[config(default = "0.01")]
+    /// The ratio of the largest scaling to the [extent](Self::extent).
+    ///
+    /// The points no larger than it are cloned, and the others are split.
+    pub scaling_ratio: f64,
+    #[config(default = "SEED")]
+    /// The seed to sample the positions of the split points.
+    pub seed: u64,
+    #[config(default = "2")]
+    /// The count of the points split from each point.
+    pub split_count: u32,
+    #[config(default = "1.6")]
+    /// The factor dividing the scalings of the split points.
+    pub split_scaling_factor: f64,
+}
+
+/// 3DGS densification output.
+#[derive(Clone, Debug)]
+pub struct Gaussian3dDensifyOutput<B: Backend> {
+    /// Count of the cloned points.
+    ///
+    /// It is `C`.
+    pub cloned_count: usize,
+    /// Source indices of the points.
+    ///
+    /// The shape is `[P']`, where `P' = P - S + C + S * N`.
+    /// - `N` is [`Gaussian3dDensifyOptions::split_count`].
+    ///
+    /// Each is the index of the point before densification.
+    /// The points are ordered as the kept points, the cloned points
+    /// and the split points, so the indices can remap the states of the points,
+    /// e.g., those of the optimizer.
+    pub indices: Tensor<B, 1, Int>,
+    /// Count of the kept points.
+    ///
+    /// It is `P - S`.
+    /// The points before it are unchanged, and the others are new.
+    pub kept_count: usize,
+    /// Count of the points split from.
+    ///
+    /// It is `S`, and the points are removed.
+    pub split_count: usize,
+}
+
+/// 3DGS density statistics.
+///
+/// They are accumulated from the outputs of the autodiff renders
+/// for the adaptive density control, e.g., for [`Gaussian3dScene::densify_by_stats`]
+/// and [`Gaussian3dScene::prune_by_radii`].
+#[derive(Clone, Debug)]
+pub struct Gaussian3dDensityStats<B: Backend> {
+    /// The sums of the absolute 2D position gradient norms.
+    ///
+    /// The shape is `[P]`.
+    ///
+    /// They are absent until the renders with
+    /// [`positions_2d_grad_abs`](Gaussian3dRenderOptions::positions_2d_grad_abs)
+    /// enabled are accumulated.
+    pub positions_2d_grad_abs_norms_sum: Option<Tensor<B, 1>>,
+    /// The sums of the 2D position gradient norms.
+    ///
+    /// The shape is `[P]`.
+    pub positions_2d_grad_norms_sum: Tensor<B, 1>,
+    /// The maximums of the visible radii.
+    ///
+    /// The shape is `[P]`.
+    pub radii_max: Tensor<B, 1, Int>,
+    /// The counts of the renders where the points are visible.
+    ///
+    /// The shape is `[P]`.
+    pub visible_counts: Tensor<B, 1, Int>,
+}
+
+impl<B: Backend> Gaussian3dDensityStats<B> {
+    /// Zeroed statistics of `point_count` points.
+    pub fn new(
+        point_count: usize,
+        device: &B::Device,
+    ) -> Self {
+        Self {
+            positions_2d_grad_abs_norms_sum: None,
+            positions_2d_grad_norms_sum: Tensor::zeros([point_count], device),
+            radii_max: Tensor::zeros([point_count], device),
+            visible_counts: Tensor::zeros([point_count], device),
+        }
+    }
+
+    /// Accumulate the statistics of a render.
+    ///
+    /// The shapes of `positions_2d_grad_abs_norm`, `positions_2d_grad_norm`
+    /// and `radii` are `[P]`.
+    /// The points are visible if their radii are positive.
+    ///
+    /// The sums of the absolute gradient norms start from zero
+    /// when `positions_2d_grad_abs_norm` is first given.
+    pub fn accumulate(
+        &mut self,
+        positions_2d_grad_abs_norm: Option<Tensor<B, 1>>,
+        positions_2d_grad_norm: Tensor<B, 1>,
+        radii: Tensor<B, 1, Int>,
+    ) -> Result<&mut Self, Error> {
+        let dims_target = [self.point_count()];
+        for dims in positions_2d_grad_abs_norm
+            .iter()
+            .map(|grad_abs_norm| grad_abs_norm.dims())
+            .chain([positions_2d_grad_norm.dims(), radii.dims()])
+        {
+            if dims != dims_target {
+                return Err(Error::MismatchedTensorShape(
+                    dims.into(),
+                    dims_target.into(),
+                ));
+            }
+        }
+
+        if let Some(positions_2d_grad_abs_norm) = positions_2d_grad_abs_norm {
+            let sum = self
+                .positions_2d_grad_abs_norms_sum
+                .take()
+                .unwrap_or_else(|| {
+                    Tensor::zeros(dims_target, &positions_2d_grad_abs_norm.device())
+                });
+            self.positions_2d_grad_abs_norms_sum = Some(sum + positions_2d_grad_abs_norm);
+        }
+        self.positions_2d_grad_norms_sum =
+            self.positions_2d_grad_norms_sum.to_owned() + positions_2d_grad_norm;
+        self.visible_counts =
+            self.visible_counts.to_owned() + radii.to_owned().greater_elem(0).int();
+        self.radii_max = self.radii_max.to_owned().max_pair(radii);
+
+        Ok(self)
+    }
+
+    /// Accumulate the statistics of the autodiff render output.
+    ///
+    /// The gradient of
+    /// [`positions_2d_grad_norm_ref`](Gaussian3dRenderOutputAutodiff::positions_2d_grad_norm_ref)
+    /// is removed from `grads`, and it is zero if absent.
+    /// So is the gradient of
+    /// [`positions_2d_grad_abs_norm_ref`](Gaussian3dRenderOutputAutodiff::positions_2d_grad_abs_norm_ref),
+    /// which is skipped if absent.
+    pub fn accumulate_output<AB: AutodiffBackend<InnerBackend = B>>(
+        &mut self,
+        output: &Gaussian3dRenderOutputAutodiff<AB>,
+        grads: &mut AB::Gradients,
+    ) -> Result<&mut Self, Error> {
+        let radii = output.radii.to_owned();
+        let positions_2d_grad_norm = output
+            .positions_2d_grad_norm_ref
+            .grad_remove(grads)
+            .unwrap_or_else(|| Tensor::zeros(radii.dims(), &radii.device()));
+        let positions_2d_grad_abs_norm =
+            output.positions_2d_grad_abs_norm_ref.grad_remove(grads);
+
+        self.accumulate(positions_2d_grad_abs_norm, positions_2d_grad_norm, radii)
+    }
+
+    /// The means of the absolute 2D position gradient norms over the visible renders.
+    ///
+    /// The shape is `[P]`.
+    /// They are zero for the points never visible,
+    /// and absent if [the sums](Self::positions_2d_grad_abs_norms_sum) are absent.
+    pub fn positions_2d_grad_abs_norms_mean(&self) -> Option<Tensor<B, 1>> {
+        self.positions_2d_grad_abs_norms_sum
+            .to_owned()
+            .map(|sum| sum / self.visible_counts.to_owned().clamp_min(1).float())
+    }
+
+    /// The means of the 2D position gradient norms over the visible renders.
+    ///
+    /// The shape is `[P]`.
+    /// They are zero for the points never visible.
+    pub fn positions_2d_grad_norms_mean(&self) -> Tensor<B, 1> {
+        self.positions_2d_grad_norms_sum.to_owned()
+            / self.visible_counts.to_owned().clamp_min(1).float()
+    }
+
+    /// Number of points.
+    #[inline]
+    pub fn point_count(&self) -> usize {
+        self.visible_counts.dims()[0]
+    }
+
+    /// Remap the statistics after the points change.
+    ///
+    /// The statistics of the appended points start from zero.
+    pub fn remap(
+        &mut self,
+        remap: &Gaussian3dPointRemap<B>,
+    ) -> &mut Self {
+        let appended_count = remap.appended_count;
+        let indices = remap.indices.to_owned();
+
+        self.positions_2d_grad_abs_norms_sum = self
+            .positions_2d_grad_abs_norms_sum
+            .take()
+            .map(|sum| optimizer::remap_points(sum, indices.to_owned(), appended_count));
+        self.positions_2d_grad_norms_sum = optimizer::remap_points(
+            self.positions_2d_grad_norms_sum.to_owned(),
+            indices.to_owned(),
+            appended_count,
+        );
+        self.radii_max = optimizer::remap_points(
+            self.radii_max.to_owned(),
+            indices.to_owned(),
+            appended_count,
+        );
+        self.visible_counts = optimizer::remap_points(
+            self.visible_counts.to_owned(),
+            indices,
+            appended_count,
+        );
+
+        self
+    }
+
+    /// Reset the statistics to zero.
+    ///
+    /// The sums of the absolute gradient norms are kept present if they are.
+    pub fn reset(&mut self) -> &mut Self {
+        let point_count = self.point_count();
+        let device = &self.visible_counts.device();
+        let has_grad_abs = self.positions_2d_grad_abs_norms_sum.is_some();
+
+        *self = Self::new(point_count, device);
+        if has_grad_abs {
+            self.positions_2d_grad_abs_norms_sum =
+                Some(Tensor::zeros([point_count], device));
+        }
+        self
+    }
+}
+
+/// Densifiers
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Densify the points by the given 2D position gradient norms.
+    ///
+    /// The shape of `positions_2d_grad_norms` is `[P]`.
+    /// They should be accumulated from
+    /// [`positions_2d_grad_norm_ref`](Gaussian3dRenderOutputAutodiff::positions_2d_grad_norm_ref)
+    /// and averaged over the views.
+    ///
+    /// The points whose gradient norms reach the
+    /// [threshold](Gaussian3dDensifyOptions::grad_threshold) are densified
+    /// as the adaptive density control of 3DGS:
+    /// 1. The small points are cloned.
+    /// 2. The large points are split into smaller ones,
+    ///    whose positions are sampled from the 3D Gaussians.
+    ///
+    /// The [3D filters](Gaussian3dScene::filters_3d) should be updated afterwards.
+    pub fn densify(
+        &mut self,
+        positions_2d_grad_norms: Tensor<B, 1>,
+        options: &Gaussian3dDensifyOptions,
+    ) -> Result<Gaussian3dDensifyOutput<B>, Error> {
+        // P
+        let point_count = self.point_count();
+        // N
+        let split_count_per_point = options.split_count as usize;
+
+        if !(options.extent.is_finite() && options.extent > 0.0) {
+            return Err(Error::InvalidExtent(options.extent));
+        }
+        if split_count_per_point == 0 {
+            return Err(Error::InvalidSplitCount(options.split_count));
+        }
+        if !(options.split_scaling_factor.is_finite()
+            && options.split_scaling_factor > 0.0)
+        {
+            return Err(Error::InvalidSplitScalingFactor(
+                options.split_scaling_factor,
+            ));
+        }
+
+        let dims = positions_2d_grad_norms.dims();
+        let dims_target = [point_count];
+        if dims != dims_target {
+            return Err(Error::MismatchedTensorShape(
+                dims.into(),
+                dims_target.into(),
+            ));
+        }
+
+        // Classifying the points by the gradient norms and the largest scalings
+
+        let device = &self.device();
+        let grad_threshold = options.grad_threshold as f32;
+        let scaling_threshold = (options.extent * options.scaling_ratio) as f32;
+        let grad_norms = positions_2d_grad_norms
+            .into_data()
+            .convert::<f32>()
+            .into_vec::<f32>()
+            .unwrap();
+        let scalings_max = self
+            .get_scalings()
+            .detach()
+            .max_dim(1)
+            .into_data()
+            .convert::<f32>()
+            .into_vec::<f32>()
+            .unwrap();
+
+        let mut indices_kept = Vec::with_capacity(point_count);
+        let mut indices_cloned = vec![];
+        let mut indices_split = vec![];
+        for (index, (grad_norm, scaling_max)) in
+            grad_norms.into_iter().zip(scalings_max).enumerate()
+        {
+            let index = index as i32;
+            let is_densified = grad_norm >= grad_threshold;
+            if is_densified && scaling_max > scaling_threshold {
+                indices_split.push(index);
+            } else {
+                indices_kept.push(index);
+                if is_densified {
+                    indices_cloned.push(index);
+                }
+            }
+        }
+
+        // Gathering the points
+        //
+        // I[P'] = [I_kept[P - S], I_cloned[C], I_split[S] * N]
+
+        // C
+        let cloned_count = indices_cloned.len();
+        // P - S
+        let kept_count = indices_kept.len();
+        // S * N
+        let split_point_count = indices_split.len() * split_count_per_point;
+        // P'
+        let point_count_new = kept_count + cloned_count + split_point_count;
+
+        let mut indices = indices_kept;
+        indices.extend(indices_cloned);
+        indices.extend(
+            indices_split
+                .iter()
+                .flat_map(|&index| iter::repeat_n(index, split_count_per_point)),
+        );
+        let indices = Tensor::<B, 1, Int>::from_data(
+            TensorData::new(indices, [point_count_new]),
+            device,
+        );
+        self.gather_points(indices.to_owned());
+
+        // Sampling the positions and scaling down the split points
+        //
+        // P'[S * N, 3] = P[S * N, 3] + R[S * N, 3, 3] * (S[S * N, 3] ⊙ n[S * N, 3])
+        // S'[S * N, 3] = S[S * N, 3] / φ
+        // n ~ N(0, 1)
+
+        if split_point_count != 0 {
+            let range = point_count_new - split_point_count..point_count_new;
+            let positions = self
+                .get_positions()
+                .detach()
+                .slice([range.to_owned(), 0..3]);
+            let rotations = self
+                .get_rotations()
+                .detach()
+                .slice([range.to_owned(), 0..4]);
+            let scalings = self.get_scalings().detach().slice([range.to_owned(), 0..3]);
+
+            // [S * N, 3, 1]
+            let samples = Tensor::<B, 3>::from_data(
+                TensorData::new(
+                    StdRng::seed_from_u64(options.seed)
+                        .sample_iter::<f32, _>(StandardNormal)
+                        .take(split_point_count * 3)
+                        .collect(),
+                    [split_point_count, 3, 1],
+                ),
+                device,
+            );
+            // [S * N, 3, 3]
+            let rotation_matrices = {
+                let [x, y, z, w] = [0, 1, 2, 3].map(|i| {
+                    rotations.to_owned().slice([0..split_point_count, i..i + 1])
+                });
+                let [xx, yy, zz] = [&x, &y, &z].map(|v| v.to_owned() * v.to_owned());
+                let [xy, xz, yz] = [(&x, &y), (&x, &z), (&y, &z)]
+                    .map(|(u, v)| u.to_owned() * v.to_owned());
+                let [wx, wy, wz] = [&x, &y, &z].map(|v| w.to_owned() * v.to_owned());
+                Tensor::cat(
+                    vec![
+                        -(yy.to_owned() + zz.to_owned()) * 2.0 + 1.0,
+                        (xy.to_owned() - wz.to_owned()) * 2.0,
+                        (xz.to_owned() + wy.to_owned()) * 2.0,
+                        (xy + wz) * 2.0,
+                        -(xx.to_owned() + zz) * 2.0 + 1.0,
+                        (yz.to_owned() - wx.to_owned()) * 2.0,
+                        (xz - wy) * 2.0,
+                        (yz + wx) * 2.0,
+                        -(xx + yy) * 2.0 + 1.0,
+                    ],
+                    1,
+                )
+                .reshape([split_point_count, 3, 3])
+            };
+            let offsets = rotation_matrices
+                .matmul(
+                    scalings
+                        .to_owned()
+                        .reshape([split_point_count, 3, 1])
+                        .mul(samples),
+                )
+                .reshape([split_point_count, 3]);
+
+            let positions_inner = self.positions.val();
+            let is_require_grad = positions_inner.is_require_grad();
+            self.set_inner_positions(
+                positions_inner
+                    .detach()
+                    .slice_assign(
+                        [range.to_owned(), 0..3],
+                        Self::make_inner_positions(positions + offsets),
+                    )
+                    .set_require_grad(is_require_grad),
+            );
+
+            let scalings_inner = self.scalings.val();
+            let is_require_grad = scalings_inner.is_require_grad();
+            self.set_inner_scalings(
+                scalings_inner
+                    .detach()
+                    .slice_assign(
+                        [range, 0..3],
+                        Self::make_inner_scalings(
+                            scalings.div_scalar(options.split_scaling_factor),
+                        ),
+                    )
+                    .set_require_grad(is_require_grad),
+            );
+        }
+
+        #[cfg(all(debug_assertions, not(test)))]
+        log::debug!(
+            target: "gausplat::renderer::gaussian_3d::scene",
+            "densify > cloned_count ({cloned_count}), split_count ({})",
+            indices_split.len(),
+        );
+
+        Ok(Gaussian3dDensifyOutput {
+            cloned_count,
+            indices,
+            kept_count,
+            split_count: indices_split.len(),
+        })
+    }
+
+    /// Densify the points by the accumulated density statistics.
+    ///
+    /// It thresholds on the means of the absolute 2D position gradient norms if
+    /// [`positions_2d_grad_abs`](Gaussian3dDensifyOptions::positions_2d_grad_abs)
+    /// is enabled, or the means of the 2D position gradient norms otherwise.
+    /// See [`Self::densify`] for more details.
+    ///
+    /// The statistics can be on another backend, e.g., the inner backend of autodiff,
+    /// and they should be [remapped](Gaussian3dDensityStats::remap)
+    /// by the output afterwards.
+    pub fn densify_by_stats<SB: Backend>(
+        &mut self,
+        stats: &Gaussian3dDensityStats<SB>,
+        options: &Gaussian3dDensifyOptions,
+    ) -> Result<Gaussian3dDensifyOutput<B>, Error> {
+        let positions_2d_grad_norms = if options.positions_2d_grad_abs {
+            stats
+                .positions_2d_grad_abs_norms_mean()
+                .ok_or(Error::MissingPositions2dGradAbsNormsSum)?
+        } else {
+            stats.positions_2d_grad_norms_mean()
+        };
+        let positions_2d_grad_norms =
+            Tensor::from_data(positions_2d_grad_norms.into_data(), &self.device());
+
+        self.densify(positions_2d_grad_norms, options)
+    }
+
+    /// Gather the points by the given indices.
+    ///
+    /// The shape of `indices` is `[P']`, and each index should be less than `P`.
+    /// All the point properties are gathered, keeping their parameter IDs
+    /// and whether they require gradients.
+    /// The [appearances](Gaussian3dScene::appearances) are unchanged.
+    pub fn gather_points(
+        &mut self,
+        indices: Tensor<B, 1, Int>,
+    ) -> &mut Self {
+        fn gather<B: Backend, const D: usize>(
+            tensor: Tensor<B, D>,
+            indices: Tensor<B, 1, Int>,
+        ) -> Tensor<B, D> {
+            let is_require_grad = tensor.is_require_grad();
+            tensor
+                .detach()
+                .select(0, indices)
+                .set_require_grad(is_require_grad)
+        }
+
+        let colors_sh = gather(self.colors_sh.val(), indices.to_owned());
+        let features = self
+            .features
+            .as_ref()
+            .map(|features| gather(features.val(), indices.to_owned()));
+        let filters_3d = self
+            .filters_3d
+            .as_ref()
+            .map(|filters_3d| gather(filters_3d.to_owned(), indices.to_owned()));
+        let labels = self
+            .labels
+            .as_ref()
+            .map(|labels| labels.val().select(0, indices.to_owned()));
+        let opacities = gather(self.opacities.val(), indices.to_owned());
+        let positions = gather(self.positions.val(), indices.to_owned());
+        let rotations = gather(self.rotations.val(), indices.to_owned());
+        let scalings = gather(self.scalings.val(), indices);
+
+        self.set_inner_colors_sh(colors_sh);
+        if let Some(features) = features {
+            self.set_inner_features(features);
+        }
+        if let Some(filters_3d) = filters_3d {
+            self.set_inner_filters_3d(filters_3d);
+        }
+        if let Some(labels) = labels {
+            self.set_inner_labels(labels);
+        }
+        self.set_inner_opacities(opacities)
+            .set_inner_positions(positions)
+            .set_inner_rotations(rotations)
+            .set_inner_scalings(scalings)
+    }
+}
+
+/// Pruners
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Retain the points by the given mask.
+    ///
+    /// The shape of `mask` is `[P]`, where the points to keep are `true`.
+    /// It returns the indices of the kept points, whose shape is `[P']`.
+    ///
+    /// The points are compacted by [`Self::gather_points`],
+    /// so the parameter IDs are kept for the records of the optimizer.
+    pub fn retain(
+        &mut self,
+        mask: Tensor<B, 1, Bool>,
+    ) -> Result<Tensor<B, 1, Int>, Error> {
+        let dims = mask.dims();
+        let dims_target = [self.point_count()];
+        if dims != dims_target {
+            return Err(Error::MismatchedTensorShape(
+                dims.into(),
+                dims_target.into(),
+            ));
+        }
+
+        let device = &self.device();
+        let indices = mask
+            .into_data()
+            .convert::<bool>()
+            .into_vec::<bool>()
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, is_kept)| is_kept.then_some(index as i32))
+            .collect::<Vec<_>>();
+        // P'
+        let point_count_new = indices.len();
+        let indices = Tensor::<B, 1, Int>::from_data(
+            TensorData::new(indices, [point_count_new]),
+            device,
+        );
+        self.gather_points(indices.to_owned());
+
+        #[cfg(all(debug_assertions, not(test)))]
+        log::debug!(
+            target: "gausplat::renderer::gaussian_3d::scene",
+            "retain > point_count ({point_count_new})",
+        );
+
+        Ok(indices)
+    }
+
+    /// Prune the points whose [opacities](Self::get_opacities)
+    /// are less than `threshold`.
+    ///
+    /// It returns the indices of the kept points.
+    pub fn prune_by_opacities(
+        &mut self,
+        threshold: f64,
+    ) -> Tensor<B, 1, Int> {
+        let mask = self
+            .get_opacities()
+            .detach()
+            .squeeze::<1>(1)
+            .greater_equal_elem(threshold);
+        self.retain(mask).expect("The mask should match the points")
+    }
+
+    /// Prune the points whose visible radii are greater than `threshold`.
+    ///
+    /// The shape of `radii` is `[P]`, e.g., the maximum of
+    /// [`radii`](Gaussian3dRenderOutputAutodiff::radii) over the views.
+    /// It returns the indices of the kept points.
+    pub fn prune_by_radii(
+        &mut self,
+        radii: Tensor<B, 1, Int>,
+        threshold: u32,
+    ) -> Result<Tensor<B, 1, Int>, Error> {
+        self.retain(radii.lower_equal_elem(threshold))
+    }
+
+    /// Prune the points whose largest [scalings](Self::get_scalings)
+    /// are greater than `threshold`, i.e., the points too large in world space.
+    ///
+    /// It returns the indices of the kept points.
+    pub fn prune_by_scalings(
+        &mut self,
+        threshold: f64,
+    ) -> Tensor<B, 1, Int> {
+        let mask = self
+            .get_scalings()
+            .detach()
+            .max_dim(1)
+            .squeeze::<1>(1)
+            .lower_equal_elem(threshold);
+        self.retain(mask).expect("The mask should match the points")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn densify() {
+        use burn::backend::NdArray;
+
+        let device = Default::default();
+        let mut scene = Gaussian3dScene::<NdArray>::from_points(
+            vec![
+                Point {
+                    color_rgb: [1.0, 0.0, 0.0],
+                    position: [-0.5, 0.0, 4.0],
+                },
+                Point {
+                    color_rgb: [0.0, 1.0, 0.0],
+                    position: [0.5, 0.0, 4.0],
+                },
+                Point {
+                    color_rgb: [0.0, 0.0, 1.0],
+                    position: [0.0, 0.5, 4.0],
+                },
+            ],
+            &device,
+        );
+        scene
+            .set_scalings(Tensor::from_floats(
+                [[0.001, 0.001, 0.001], [0.1, 0.05, 0.02], [0.1, 0.1, 0.1]],
+                &device,
+            ))
+            .set_inner_labels(Tensor::from_ints([0, 1, 2], &device))
+            .init_features(4);
+
+        let options = Gaussian3dDensifyOptions::new(1.0);
+        let output = scene
+            .densify(Tensor::from_floats([1.0, 1.0, 0.0], &device), &options)
+            .unwrap();
+        assert_eq!(output.cloned_count, 1);
+        assert_eq!(output.kept_count, 2);
+        assert_eq!(output.split_count, 1);
+        assert_eq!(
+            output
+                .indices
+                .into_data()
+                .convert::<i64>()
+                .to_vec::<i64>()
+                .unwrap(),
+            [0, 2, 0, 1, 1]
+        );
+
+        assert_eq!(scene.point_count(), 5);
+        assert_eq!(scene.features.as_ref().unwrap().dims(), [5, 4]);
+        assert!(scene.filters_3d.is_none());
+        assert_eq!(
+            scene
+                .labels
+                .as_ref()
+                .unwrap()
+                .val()
+                .into_data()
+                .convert::<i64>()
+                .to_vec::<i64>()
+                .unwrap(),
+            [0, 2, 0, 1, 1]
+        );
+
+        // The cloned point is the same as the source point.
+        let positions = scene.get_positions();
+        positions
+            .to_owned()
+            .slice([2..3, 0..3])
+            .into_data()
+            .assert_approx_eq(&positions.to_owned().slice([0..1, 0..3]).into_data(), 6);
+
+        // The split points are scaled down and displaced.
+        scene
+            .get_scalings()
+            .slice([3..5, 0..3])
+            .into_data()
+            .assert_approx_eq(
+                &Tensor::<NdArray, 2>::from_floats(
+                    [[0.0625, 0.03125, 0.0125], [0.0625, 0.03125, 0.0125]],
+                    &device,
+                )
+                .into_data(),
+                6,
+            );
+        let positions_split = positions.slice([3..5, 0..3]);
+        assert!(
+            positions_split
+                .to_owned()
+                .sub(Tensor::from_floats([[0.5, 0.0, 4.0]], &device))
+                .abs()
+                .max()
+                .into_scalar()
+                > 0.0
+        );
+        assert!(positions_split
+            .to_owned()
+            .slice([0..1, 0..3])
+            .not_equal(positions_split.slice([1..2, 0..3]))
+            .any()
+            .into_scalar());
+
+        let output = scene.densify(Tensor::from_floats([1.0], &device), &options);
+        assert!(output.is_err());
+
+        let grad_norms = || Tensor::from_floats([0.0; 5], &device);
+        for extent in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+            let options = Gaussian3dDensifyOptions::new(extent);
+            let output = scene.densify(grad_norms(), &options);
+            assert!(matches!(output, Err(Error::InvalidExtent(_))), "{output:?}");
+        }
+        let options = Gaussian3dDensifyOptions::new(1.0).with_split_count(0);
+        let output = scene.densify(grad_norms(), &options);
+        assert!(
+            matches!(output, Err(Error::InvalidSplitCount(0))),
+            "{output:?}"
+        );
+        for split_scaling_factor in [0.0, -1.6, f64::NAN] {
+            let options = Gaussian3dDensifyOptions::new(1.0)
+                .with_split_scaling_factor(split_scaling_factor);
+            let output = scene.densify(grad_norms(), &options);
+            assert!(
+                matches!(output, Err(Error::InvalidSplitScalingFactor(_))),
+                "{output:?}"
+            );
+        }
+        assert_eq!(scene.point_count(), 5);
+    }
+
+    #[test]
+    fn density_stats() {
+        use burn::backend::NdArray;
+
+        let device = Default::default();
+        let to_vec =
+            |tensor: TensorData| tensor.convert::<f32>().to_vec::<f32>().unwrap();
+
+        let mut stats = Gaussian3dDensityStats::<NdArray>::new(3, &device);
+        stats
+            .accumulate(
+                None,
+                Tensor::from_floats([1.0, 0.0, 2.0], &device),
+                Tensor::from_ints([3, 0, 5], &device),
+            )
+            .unwrap()
+            .accumulate(
+                None,
+                Tensor::from_floats([1.0, 0.0, 0.0], &device),
+                Tensor::from_ints([4, 0, 1], &device),
+            )
+            .unwrap();
+        assert_eq!(
+            to_vec(stats.positions_2d_grad_norms_sum.to_owned().into_data()),
+            [2.0, 0.0, 2.0]
+        );
+        assert_eq!(
+            to_vec(stats.radii_max.to_owned().into_data()),
+            [4.0, 0.0, 5.0]
+        );
+        assert_eq!(
+            to_vec(stats.visible_counts.to_owned().into_data()),
+            [2.0, 0.0, 2.0]
+        );
+        assert_eq!(
+            to_vec(stats.positions_2d_grad_norms_mean().into_data()),
+            [1.0, 0.0, 1.0]
+        );
+
+        stats.remap(&Gaussian3dPointRemap {
+            appended_count: 1,
+            indices: Tensor::from_ints([2, 0], &device),
+        });
+        assert_eq!(stats.point_count(), 3);
+        assert_eq!(
+            to_vec(stats.radii_max.to_owned().into_data()),
+            [5.0, 4.0, 0.0]
+        );
+        assert_eq!(
+            to_vec(stats.positions_2d_grad_norms_mean().into_data()),
+            [1.0, 1.0, 0.0]
+        );
+
+        stats.reset();
+        assert_eq!(stats.point_count(), 3);
+        assert_eq!(
+            to_vec(stats.visible_counts.to_owned().into_data()),
+            [0.0, 0.0, 0.0]
+        );
+
+        let result = stats.accumulate(
+            None,
+            Tensor::from_floats([1.0], &device),
+            Tensor::from_ints([1], &device),
+        );
+        assert!(result.is_err());
+        assert!(stats.positions_2d_grad_abs_norms_sum.is_none());
+    }
+
+    #[test]
+    fn density_stats_abs() {
+        use burn::backend::NdArray;
+
+        let device = Default::default();
+        let to_vec =
+            |tensor: TensorData| tensor.convert::<f32>().to_vec::<f32>().unwrap();
+
+        let mut scene = Gaussian3dScene::<NdArray>::from_points(
+            vec![
+                Point {
+                    color_rgb: [1.0, 0.5, 0.0],
+                    position: [0.0, 0.0, 0.0],
+                },
+                Point {
+                    color_rgb: [0.0, 0.5, 1.0],
+                    position: [1.0, 0.0, 0.0],
+                },
+            ],
+            &device,
+        );
+        let options = Gaussian3dDensifyOptions::new(1.0).with_grad_threshold(1.0);
+
+        let mut stats = Gaussian3dDensityStats::<NdArray>::new(2, &device);
+        assert!(stats.positions_2d_grad_abs_norms_mean().is_none());
+        let result = scene
+            .to_owned()
+            .densify_by_stats(&stats, &options.with_positions_2d_grad_abs(true));
+        assert!(matches!(
+            result,
+            Err(Error::MissingPositions2dGradAbsNormsSum)
+        ));
+
+        stats
+            .accumulate(
+                Some(Tensor::from_floats([2.0, 3.0], &device)),
+                Tensor::from_floats([0.5, 1.0], &device),
+                Tensor::from_ints([1, 1], &device),
+            )
+            .unwrap()
+            .accumulate(
+                Some(Tensor::from_floats([0.0, 1.0], &device)),
+                Tensor::from_floats([0.5, 0.0], &device),
+                Tensor::from_ints([0, 1], &device),
+            )
+            .unwrap();
+        assert_eq!(
+            to_vec(
+                stats
+                    .positions_2d_grad_abs_norms_mean()
+                    .unwrap()
+                    .into_data()
+            ),
+            [2.0, 2.0]
+        );
+        assert_eq!(
+            to_vec(stats.positions_2d_grad_norms_mean().into_data()),
+            [1.0, 0.5]
+        );
+
+        // The plain means select the first point, and the absolute ones select both.
+        let output = scene.to_owned().densify_by_stats(&stats, &options).unwrap();
+        assert_eq!(output.cloned_count + output.split_count, 1);
+        let output = scene
+            .densify_by_stats(&stats, &options.with_positions_2d_grad_abs(true))
+            .unwrap();
+        assert_eq!(output.cloned_count + output.split_count, 2);
+
+        stats.remap(&Gaussian3dPointRemap {
+            appended_count: 1,
+            indices: Tensor::from_ints([1], &device),
+        });
+        assert_eq!(
+            to_vec(
+                stats
+                    .positions_2d_grad_abs_norms_sum
+                    .to_owned()
+                    .unwrap()
+                    .into_data()
+            ),
+            [4.0, 0.0]
+        );
+
+        stats.reset();
+        assert_eq!(
+            to_vec(
+                stats
+                    .positions_2d_grad_abs_norms_sum
+                    .to_owned()
+                    .unwrap()
+                    .into_data()
+            ),
+            [0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn prune() {
+        use burn::backend::NdArray;
+
+        let device = Default::default();
+        let mut scene = Gaussian3dScene::<NdArray>::from_points(
+            vec![
+                Point {
+                    color_rgb: [1.0, 0.0, 0.0],
+                    position: [-0.5, 0.0, 4.0],
+                },
+                Point {
+                    color_rgb: [0.0, 1.0, 0.0],
+                    position: [0.5, 0.0, 4.0],
+                },
+                Point {
+                    color_rgb: [0.0, 0.0, 1.0],
+                    position: [0.0, 0.5, 4.0],
+                },
+                Point {
+                    color_rgb: [1.0, 1.0, 1.0],
+                    position: [0.0, -0.5, 4.0],
+                },
+            ],
+            &device,
+        );
+        scene
+            .set_opacities(Tensor::from_floats([[0.001], [0.5], [0.5], [0.5]], &device))
+            .set_scalings(Tensor::from_floats(
+                [
+                    [0.01, 0.01, 0.01],
+                    [0.01, 0.01, 0.01],
+                    [0.01, 1.0, 0.01],
+                    [0.01, 0.01, 0.01],
+                ],
+                &device,
+            ))
+            .set_inner_filters_3d(Tensor::from_floats(
+                [[1.0], [2.0], [3.0], [4.0]],
+                &device,
+            ))
+            .set_inner_labels(Tensor::from_ints([0, 1, 2, 3], &device));
+        let ids = [
+            scene.colors_sh.id.to_owned(),
+            scene.opacities.id.to_owned(),
+            scene.positions.id.to_owned(),
+            scene.rotations.id.to_owned(),
+            scene.scalings.id.to_owned(),
+        ];
+        let to_vec = |indices: Tensor<NdArray, 1, Int>| {
+            indices
+                .into_data()
+                .convert::<i64>()
+                .to_vec::<i64>()
+                .unwrap()
+        };
+
+        let indices = scene.prune_by_opacities(0.005);
+        assert_eq!(to_vec(indices), [1, 2, 3]);
+        assert_eq!(scene.point_count(), 3);
+
+        let indices = scene.prune_by_scalings(0.5);
+        assert_eq!(to_vec(indices), [0, 2]);
+        assert_eq!(scene.point_count(), 2);
+
+        let indices = scene
+            .prune_by_radii(Tensor::from_ints([100, 1], &device), 20)
+            .unwrap();
+        assert_eq!(to_vec(indices), [1]);
+        assert_eq!(scene.point_count(), 1);
+        scene
+            .filters_3d
+            .to_owned()
+            .unwrap()
+            .into_data()
+            .assert_eq(&TensorData::from([[4.0_f32]]), true);
+        assert_eq!(to_vec(scene.labels.as_ref().unwrap().val()), [3]);
+        scene
+            .get_positions()
+            .into_data()
+            .assert_approx_eq(&TensorData::from([[0.0_f32, -0.5, 4.0]]), 6);
+
+        assert_eq!(
+            [
+                scene.colors_sh.id.to_owned(),
+                scene.opacities.id.to_owned(),
+                scene.positions.id.to_owned(),
+                scene.rotations.id.to_owned(),
+                scene.scalings.id.to_owned(),
+            ],
+            ids
+        );
+
+        let indices = scene.retain(Tensor::from_bools([true, false], &device));
+        assert!(indices.is_err());
+    }
+}
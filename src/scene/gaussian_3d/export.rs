@@ -8,6 +8,11 @@ use std::io::{BufWriter, Write};
 /// Scene exporters
 impl<B: Backend> Gaussian3dScene<B> {
     /// Export the scene in the 3DGS PLY format.
+    ///
+    /// The [labels](Gaussian3dScene::labels) are exported
+    /// as the vertex property `label` if specified.
+    /// The [3D filters](Gaussian3dScene::filters_3d) are not exported,
+    /// so they should be updated again after importing.
     pub fn encode_polygon(
         &self,
         writer: &mut impl Write,
@@ -59,13 +64,35 @@ impl<B: Backend> Gaussian3dScene<B> {
         )
         .into_data();
 
-        let mut header = POLYGON_HEADER_3DGS.to_owned();
+        let mut header = match &self.labels {
+            Some(_) => POLYGON_HEADER_3DGS_LABELED.to_owned(),
+            None => POLYGON_HEADER_3DGS.to_owned(),
+        };
         // NOTE: The data format is set to binary native-endian.
         header.format = polygon::Format::binary_native_endian();
         header.get_mut("vertex").unwrap().count = point_count;
         header.encode(writer)?;
 
-        writer.write_all(data.as_bytes())?;
+        match &self.labels {
+            None => writer.write_all(data.as_bytes())?,
+            Some(labels) => {
+                // [P]
+                let labels = labels.val().into_data().convert::<i32>();
+
+                // [P, 62 + 1]
+                //
+                // The label is appended to each vertex.
+                let vertex_size = 62 * data.dtype.size();
+                for (vertex, label) in data
+                    .as_bytes()
+                    .chunks_exact(vertex_size)
+                    .zip(labels.as_bytes().chunks_exact(size_of::<i32>()))
+                {
+                    writer.write_all(vertex)?;
+                    writer.write_all(label)?;
+                }
+            },
+        }
 
         Ok(())
     }
@@ -0,0 +1,52 @@
+//! 3DGS point feature implementation.
+
+pub use super::*;
+
+/// Feature initializers
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Initialize [`Gaussian3dScene::features`] for the given feature count.
+    ///
+    /// The features are learnable and start from zero.
+    /// They are rendered alongside the colors, e.g., for distilling the
+    /// semantic features of the views into the points.
+    pub fn init_features(
+        &mut self,
+        feature_count: usize,
+    ) -> &mut Self {
+        let device = &self.device();
+        // [P, D]
+        let features = Tensor::<B, 2>::zeros([self.point_count(), feature_count], device)
+            .set_require_grad(true);
+
+        #[cfg(all(debug_assertions, not(test)))]
+        log::debug!(
+            target: "gausplat::renderer::gaussian_3d::scene",
+            "init_features",
+        );
+
+        self.set_inner_features(features)
+    }
+}
+
+/// Feature checkers
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Check if the shape of [`Gaussian3dScene::features`] is `[P, D]`
+    /// and `D` is positive.
+    pub(crate) fn check_features(&self) -> Result<(), Error> {
+        let features = match &self.features {
+            Some(features) => features,
+            None => return Ok(()),
+        };
+
+        let dims = features.dims();
+        let dims_target = [self.point_count(), dims[1].max(1)];
+        if dims != dims_target {
+            return Err(Error::MismatchedTensorShape(
+                dims.into(),
+                dims_target.into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -12,6 +12,9 @@ use std::{
 /// Scene importers
 impl<B: Backend> Gaussian3dScene<B> {
     /// Import the scene in the 3DGS PLY format.
+    ///
+    /// The [labels](Gaussian3dScene::labels) are imported
+    /// if the vertices have the property `label`.
     pub fn decode_polygon(
         reader: &mut impl Read,
         device: &B::Device,
@@ -19,7 +22,8 @@ impl<B: Backend> Gaussian3dScene<B> {
         let reader = &mut BufReader::new(reader);
 
         let header = polygon::Header::decode(reader)?;
-        if !header.is_same_order(&POLYGON_HEADER_3DGS) {
+        let is_labeled = header.is_same_order(&POLYGON_HEADER_3DGS_LABELED);
+        if !is_labeled && !header.is_same_order(&POLYGON_HEADER_3DGS) {
             return Err(Error::MismatchedPolygonHeader3DGS(header.into()));
         }
         let payload = polygon::Payload::decode_with(reader, &header)?;
@@ -71,6 +75,13 @@ impl<B: Backend> Gaussian3dScene<B> {
             device,
         );
 
+        // [P]
+        let labels = is_labeled.then(|| {
+            let bytes = take(object.elem_prop_mut("vertex", "label").unwrap().data);
+            let data = TensorData::from_bytes(bytes, [point_count], DType::I32);
+            Tensor::<B, 1, Int>::from_data(data.convert::<B::IntElem>(), device)
+        });
+
         let mut scene = Self::default();
         scene
             .set_inner_colors_sh(colors_sh)
@@ -78,6 +89,9 @@ impl<B: Backend> Gaussian3dScene<B> {
             .set_inner_positions(positions)
             .set_inner_rotations(rotations)
             .set_inner_scalings(scalings);
+        if let Some(labels) = labels {
+            scene.set_inner_labels(labels);
+        }
 
         #[cfg(all(debug_assertions, not(test)))]
         log::debug!(
@@ -249,7 +263,12 @@ impl<B: Backend> Gaussian3dScene<B> {
         );
 
         Self {
+            appearances: None,
             colors_sh,
+            features: None,
+            filters_3d: None,
+            label_count: 0,
+            labels: None,
             opacities,
             positions,
             rotations,
@@ -403,4 +422,90 @@ mod tests {
         scene.encode_polygon(&mut output).unwrap();
         assert_eq!(output, target);
     }
+
+    #[test]
+    fn decode_and_encode_polygon_filtered() {
+        use super::super::*;
+        use burn::backend::NdArray;
+        use std::io::Cursor;
+
+        type B = NdArray<f32>;
+
+        let device = Default::default();
+        let source =
+            include_bytes!("../../../examples/data/3dgs-ply/sixstars.3dgs.ply").to_vec();
+        let view = render::View {
+            field_of_view_x: 1.0,
+            field_of_view_y: 1.0,
+            image_height: 32,
+            image_width: 32,
+            view_position: [0.0, 0.0, -4.0],
+            view_transform: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 4.0, 1.0],
+            ],
+            ..Default::default()
+        };
+
+        let mut scene =
+            Gaussian3dScene::<B>::decode_polygon(&mut Cursor::new(source), &device)
+                .unwrap();
+        assert!(scene.filters_3d.is_none());
+
+        scene.update_filters_3d([&view], &Default::default());
+        let target = scene.filters_3d.to_owned().unwrap();
+
+        let mut source = vec![];
+        scene.encode_polygon(&mut source).unwrap();
+
+        let mut scene =
+            Gaussian3dScene::<B>::decode_polygon(&mut Cursor::new(source), &device)
+                .unwrap();
+        assert!(scene.filters_3d.is_none());
+
+        scene.update_filters_3d([&view], &Default::default());
+        let output = scene.filters_3d.to_owned().unwrap();
+        output.into_data().assert_eq(&target.into_data(), true);
+    }
+
+    #[test]
+    fn decode_and_encode_polygon_labeled() {
+        use super::super::*;
+        use burn::backend::NdArray;
+        use std::io::Cursor;
+
+        type B = NdArray<f32>;
+
+        let device = Default::default();
+        let source =
+            include_bytes!("../../../examples/data/3dgs-ply/sixstars.3dgs.ply").to_vec();
+
+        let mut scene =
+            Gaussian3dScene::<B>::decode_polygon(&mut Cursor::new(source), &device)
+                .unwrap();
+        assert!(scene.labels.is_none());
+
+        let labels = Tensor::<B, 1, Int>::arange(-1..17, &device);
+        scene.set_inner_labels(labels.to_owned());
+
+        let mut source = vec![];
+        scene.encode_polygon(&mut source).unwrap();
+
+        let scene = Gaussian3dScene::<B>::decode_polygon(
+            &mut Cursor::new(source.to_owned()),
+            &device,
+        )
+        .unwrap();
+
+        let target = labels;
+        let output = scene.labels.as_ref().unwrap().val();
+        output.into_data().assert_eq(&target.into_data(), true);
+
+        let target = source;
+        let mut output = vec![];
+        scene.encode_polygon(&mut output).unwrap();
+        assert_eq!(output, target);
+    }
 }
@@ -0,0 +1,41 @@
+//! 3DGS point label implementation.
+
+pub use super::*;
+
+/// Label checkers
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Check if the shape of [`Gaussian3dScene::labels`] is `[P]`.
+    pub(crate) fn check_labels(&self) -> Result<(), Error> {
+        let labels = match &self.labels {
+            Some(labels) => labels,
+            None => return Ok(()),
+        };
+
+        let dims = labels.dims();
+        let dims_target = [self.point_count()];
+        if dims != dims_target {
+            return Err(Error::MismatchedTensorShape(
+                dims.into(),
+                dims_target.into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Label count.
+    ///
+    /// It is `L`, which is one more than the largest label, and at least one.
+    /// It is zero if [`Gaussian3dScene::labels`] is absent.
+    ///
+    /// It is cached when the labels are set by
+    /// [`Gaussian3dScene::set_inner_labels`], so it costs no device readback.
+    #[inline]
+    pub fn label_count(&self) -> usize {
+        if self.labels.is_some() {
+            self.label_count
+        } else {
+            0
+        }
+    }
+}
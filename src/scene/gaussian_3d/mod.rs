@@ -1,8 +1,15 @@
 //! 3DGS scene representation.
 
+pub mod appearance;
+pub mod density;
 pub mod export;
+pub mod feature;
 pub mod import;
+pub mod label;
+pub mod optimizer;
 pub mod property;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 pub use super::point::*;
 pub use crate::spherical_harmonics::{SH_COUNT_MAX, SH_DEGREE_MAX};
@@ -13,8 +20,12 @@ pub use crate::{
 };
 pub use burn::{
     module::{AutodiffModule, Module, Param},
-    tensor::{Tensor, TensorData},
+    tensor::{Int, Tensor, TensorData},
+};
+pub use density::{
+    Gaussian3dDensifyOptions, Gaussian3dDensifyOutput, Gaussian3dDensityStats,
 };
+pub use optimizer::{Gaussian3dPointRemap, PointOptimizerState};
 pub use render::{
     Gaussian3dRenderOptions, Gaussian3dRenderOutput, Gaussian3dRenderOutputAutodiff,
     Gaussian3dRenderer,
@@ -50,9 +61,37 @@ pub static POLYGON_HEADER_3DGS: LazyLock<polygon::Header> = LazyLock::new(|| {
         .unwrap()
 });
 
+/// A polygon file header for 3DGS with point labels.
+///
+/// It is [`POLYGON_HEADER_3DGS`] with an extra vertex property `label`,
+/// which is [`Gaussian3dScene::labels`].
+pub static POLYGON_HEADER_3DGS_LABELED: LazyLock<polygon::Header> = LazyLock::new(|| {
+    include_str!("header.3dgs.ply")
+        .replace(
+            "element vertex",
+            "comment label      := Label that is -1 if unlabeled.\nelement vertex",
+        )
+        .replace("end_header", "property int label\nend_header")
+        .parse::<polygon::Header>()
+        .unwrap()
+});
+
 /// 3DGS representation.
 #[derive(Module)]
 pub struct Gaussian3dScene<B: Backend> {
+    /// Appearance transforms. (Inner value)
+    ///
+    /// The shape is `[V, 3, 4]`.
+    /// - `V` is the view count, and the views are indexed by
+    ///   [`View::view_id`](render::View::view_id).
+    ///
+    /// Each is an affine transform `[A | b]` of the rendered colors in RGB space,
+    /// i.e., `C' = A * C + b`.
+    /// It compensates the exposure and white balance of the view,
+    /// which the points would otherwise absorb.
+    /// They are absent by default and should be initialized by
+    /// [`Self::init_appearances`].
+    pub appearances: Option<Param<Tensor<B, 3>>>,
     /// Colors in SH space. (Inner value)
     ///
     /// The shape is `[P, M * 3]`, which derives from `[P, M, 3]`.
@@ -61,6 +100,46 @@ pub struct Gaussian3dScene<B: Backend> {
     ///
     /// It is represented as orthonormalized spherical harmonic with RGB channels.
     pub colors_sh: Param<Tensor<B, 2>>,
+    /// Point features. (Inner value)
+    ///
+    /// The shape is `[P, D]`.
+    /// - `D` is the feature count, which is arbitrary.
+    ///
+    /// They are rendered into
+    /// [`features_2d`](Gaussian3dRenderOutputAutodiff::features_2d)
+    /// with the same blending weights as the colors,
+    /// e.g., for distilling the semantic features of the views.
+    /// They are absent by default and should be initialized by
+    /// [`Self::init_features`].
+    pub features: Option<Param<Tensor<B, 2>>>,
+    /// 3D filters.
+    ///
+    /// The shape is `[P, 1]`.
+    ///
+    /// They are the variances of the 3D smoothing filter,
+    /// i.e., the squared sampling intervals of the points in the views.
+    /// They are not learnable, so they are constant in the module
+    /// and should be updated by [`Self::update_filters_3d`] after the points change.
+    /// They are absent by default, and not persisted in the record or the polygon file,
+    /// so the 3D filter is disabled until they are updated.
+    pub filters_3d: Option<Tensor<B, 2>>,
+    /// Label count.
+    ///
+    /// It is cached by [`Self::set_inner_labels`] for [`Self::label_count()`],
+    /// so that rendering does not read the labels back from the device.
+    label_count: usize,
+    /// Point labels.
+    ///
+    /// The shape is `[P]`.
+    ///
+    /// They are the semantic or instance IDs of the points,
+    /// where `-1` is unlabeled.
+    /// They are rendered into
+    /// [`labels_2d`](Gaussian3dRenderOutputAutodiff::labels_2d) and
+    /// [`label_coverages_2d`](Gaussian3dRenderOutputAutodiff::label_coverages_2d).
+    /// They are not learnable and are absent by default,
+    /// which should be set by [`Self::set_inner_labels`].
+    pub labels: Option<Param<Tensor<B, 1, Int>>>,
     /// Opacities. (Inner value)
     ///
     /// The shape is `[P, 1]`.
@@ -91,10 +170,16 @@ struct Gaussian3dRenderBackwardOp<B: Backend, R: Gaussian3dRenderer<B>> {
 /// 3DGS render backward state.
 #[derive(Clone, Debug)]
 pub struct Gaussian3dRenderBackwardState<B: Backend> {
+    /// The gradient of the focal lengths.
+    pub focal_lengths_grad_ref_id: NodeID,
     /// Inner state.
     pub inner: render::backward::RenderInput<B>,
+    /// The norm of the absolute gradients of the 2D positions.
+    pub positions_2d_grad_abs_norm_ref_id: NodeID,
     /// The gradient norm of the 2D positions.
     pub positions_2d_grad_norm_ref_id: NodeID,
+    /// The gradient of the view transform in tangent space.
+    pub view_transform_grad_ref_id: NodeID,
 }
 
 impl<
@@ -117,9 +202,20 @@ impl<
     #[inline]
     fn render_backward(
         state: render::backward::RenderInput<JitBackend<R, F, I, B>>,
+        alphas_2d_grad: <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
         colors_rgb_2d_grad: <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
+        depths_2d_grad: <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
+        features_2d_grad: Option<
+            <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
+        >,
     ) -> render::backward::RenderOutput<JitBackend<R, F, I, B>> {
-        render::jit::backward(state, colors_rgb_2d_grad)
+        render::jit::backward(
+            state,
+            alphas_2d_grad,
+            colors_rgb_2d_grad,
+            depths_2d_grad,
+            features_2d_grad,
+        )
     }
 }
 
@@ -144,9 +240,87 @@ impl<
     #[inline]
     fn render_backward(
         state: render::backward::RenderInput<JitBackend<R, F, I, B>>,
+        alphas_2d_grad: <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
         colors_rgb_2d_grad: <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
+        depths_2d_grad: <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
+        features_2d_grad: Option<
+            <JitBackend<R, F, I, B> as Backend>::FloatTensorPrimitive,
+        >,
     ) -> render::backward::RenderOutput<JitBackend<R, F, I, B>> {
-        render::jit::backward(state, colors_rgb_2d_grad)
+        render::jit::backward(
+            state,
+            alphas_2d_grad,
+            colors_rgb_2d_grad,
+            depths_2d_grad,
+            features_2d_grad,
+        )
+    }
+}
+
+impl<
+        F: ndarray::FloatNdArrayElement,
+        I: ndarray::IntNdArrayElement,
+        Q: ndarray::QuantElement,
+    > Gaussian3dRenderer<NdArray<F, I, Q>> for Gaussian3dScene<NdArray<F, I, Q>>
+{
+    #[inline]
+    fn render_forward(
+        input: render::forward::RenderInput<NdArray<F, I, Q>>,
+        view: &render::View,
+        options: &render::Gaussian3dRenderOptions,
+    ) -> Result<render::forward::RenderOutput<NdArray<F, I, Q>>, Error> {
+        render::cpu::forward(input, view, options)
+    }
+
+    #[inline]
+    fn render_backward(
+        state: render::backward::RenderInput<NdArray<F, I, Q>>,
+        alphas_2d_grad: <NdArray<F, I, Q> as Backend>::FloatTensorPrimitive,
+        colors_rgb_2d_grad: <NdArray<F, I, Q> as Backend>::FloatTensorPrimitive,
+        depths_2d_grad: <NdArray<F, I, Q> as Backend>::FloatTensorPrimitive,
+        features_2d_grad: Option<<NdArray<F, I, Q> as Backend>::FloatTensorPrimitive>,
+    ) -> render::backward::RenderOutput<NdArray<F, I, Q>> {
+        render::cpu::backward(
+            state,
+            alphas_2d_grad,
+            colors_rgb_2d_grad,
+            depths_2d_grad,
+            features_2d_grad,
+        )
+    }
+}
+
+impl<
+        F: ndarray::FloatNdArrayElement,
+        I: ndarray::IntNdArrayElement,
+        Q: ndarray::QuantElement,
+    > Gaussian3dRenderer<NdArray<F, I, Q>>
+    for Gaussian3dScene<Autodiff<NdArray<F, I, Q>>>
+{
+    #[inline]
+    fn render_forward(
+        input: render::forward::RenderInput<NdArray<F, I, Q>>,
+        view: &render::View,
+        options: &render::Gaussian3dRenderOptions,
+    ) -> Result<render::forward::RenderOutput<NdArray<F, I, Q>>, Error> {
+        render::cpu::forward(input, view, options)
+    }
+
+    #[inline]
+    fn render_backward(
+        state: render::backward::RenderInput<NdArray<F, I, Q>>,
+        alphas_2d_grad: <NdArray<F, I, Q> as Backend>::FloatTensorPrimitive,
+        colors_rgb_2d_grad: <NdArray<F, I, Q> as Backend>::FloatTensorPrimitive,
+        depths_2d_grad: <NdArray<F, I, Q> as Backend>::FloatTensorPrimitive,
+        features_2d_grad: Option<<NdArray<F, I, Q> as Backend>::FloatTensorPrimitive>,
+    ) -> render::backward::RenderOutput<NdArray<F, I, Q>> {
+        render::cpu::backward(
+            state,
+            alphas_2d_grad,
+            colors_rgb_2d_grad,
+            depths_2d_grad,
+            features_2d_grad,
+        )
     }
 }
 
@@ -156,7 +330,8 @@ where
 {
     /// Render the 3DGS scene.
     ///
-    /// It renders an image with the given [`view`](render::View).
+    /// It renders an image with the given [`view`](render::View),
+    /// or a [region](Gaussian3dRenderOptions::region) of it.
     pub fn render(
         &self,
         view: &render::View,
@@ -168,10 +343,25 @@ where
             "render > autodiff disabled",
         );
 
+        self.check_features()?;
+        self.check_filters_3d()?;
+        self.check_labels()?;
+        self.check_appearances(view, options)?;
+
         let input = render::forward::RenderInput {
             device: self.device(),
             point_count: self.point_count() as u64,
+            label_count: self.label_count() as u32,
             colors_sh: self.colors_sh.val().into_primitive().tensor(),
+            features: self
+                .features
+                .as_ref()
+                .map(|features| features.val().into_primitive().tensor()),
+            filters_3d: self.get_inner_filters_3d().into_primitive().tensor(),
+            labels: self
+                .labels
+                .as_ref()
+                .map(|labels| labels.val().into_primitive()),
             opacities: self.opacities.val().into_primitive().tensor(),
             positions: self.positions.val().into_primitive().tensor(),
             rotations: self.rotations.val().into_primitive().tensor(),
@@ -180,9 +370,173 @@ where
 
         let output = Self::render_forward(input, view, options)?;
 
-        let colors_rgb_2d = Tensor::new(TensorPrimitive::Float(output.colors_rgb_2d));
+        let alphas_2d =
+            Tensor::<B, 2>::new(TensorPrimitive::Float(output.state.transmittances))
+                .neg()
+                .add_scalar(1.0);
+        let colors_rgb_2d = self.transform_appearance(
+            Tensor::new(TensorPrimitive::Float(output.colors_rgb_2d)),
+            view,
+            options,
+        );
+        let contributor_indices_2d = output.contributor_indices_2d.map(Tensor::new);
+        let contributor_weights_2d =
+            output.contributor_weights_2d.map(|contributor_weights_2d| {
+                Tensor::new(TensorPrimitive::Float(contributor_weights_2d))
+            });
+        let depths_2d = Tensor::new(TensorPrimitive::Float(output.depths_2d));
+        let depths_2d_median =
+            Tensor::new(TensorPrimitive::Float(output.depths_2d_median));
+        let features_2d = output
+            .features_2d
+            .map(|features_2d| Tensor::new(TensorPrimitive::Float(features_2d)));
+        let label_coverages_2d = output.label_coverages_2d.map(|label_coverages_2d| {
+            Tensor::new(TensorPrimitive::Float(label_coverages_2d))
+        });
+        let labels_2d = output.labels_2d.map(Tensor::new);
+
+        Ok(Gaussian3dRenderOutput {
+            alphas_2d,
+            colors_rgb_2d,
+            contributor_indices_2d,
+            contributor_weights_2d,
+            depths_2d,
+            depths_2d_median,
+            features_2d,
+            label_coverages_2d,
+            labels_2d,
+        })
+    }
+
+    /// Render the 3DGS scene region by region.
+    ///
+    /// It splits the image of the given [`view`](render::View) into regions
+    /// of at most `region_size` (`[width, height]`) pixels,
+    /// which is rounded down to the tile size.
+    /// Each region is rendered and passed to `write`
+    /// along with its `[x, y, width, height]` in row-major order.
+    ///
+    /// It renders the [region](Gaussian3dRenderOptions::region) of `options`
+    /// if specified, or the whole image otherwise.
+    /// The image can be larger than [`PIXEL_COUNT_MAX`](render::jit::PIXEL_COUNT_MAX),
+    /// and its pixels are identical to those rendered in a single pass.
+    pub fn render_regions(
+        &self,
+        view: &render::View,
+        options: &Gaussian3dRenderOptions,
+        region_size: [u32; 2],
+        mut write: impl FnMut([u32; 4], Gaussian3dRenderOutput<B>) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let [image_offset_x, image_offset_y, image_size_x, image_size_y] = options
+            .region
+            .unwrap_or([0, 0, view.image_width, view.image_height]);
+        let region_size_x =
+            (region_size[0] / render::jit::TILE_SIZE_X).max(1) * render::jit::TILE_SIZE_X;
+        let region_size_y =
+            (region_size[1] / render::jit::TILE_SIZE_Y).max(1) * render::jit::TILE_SIZE_Y;
+
+        if image_size_x == 0 || image_size_y == 0 {
+            return Err(Error::InvalidRegion(
+                [image_offset_x, image_offset_y, image_size_x, image_size_y],
+                [view.image_width, view.image_height],
+            ));
+        }
+
+        for y in (0..image_size_y).step_by(region_size_y as usize) {
+            for x in (0..image_size_x).step_by(region_size_x as usize) {
+                let region = [
+                    image_offset_x + x,
+                    image_offset_y + y,
+                    region_size_x.min(image_size_x - x),
+                    region_size_y.min(image_size_y - y),
+                ];
+                let output = self.render(view, &options.with_region(Some(region)))?;
+                write(region, output)?;
+            }
+        }
 
-        Ok(Gaussian3dRenderOutput { colors_rgb_2d })
+        Ok(())
+    }
+
+    /// Render the 3DGS scene region by region and stitch them into one image.
+    ///
+    /// It is the same as [`Self::render_regions`],
+    /// but the outputs of all regions are concatenated.
+    pub fn render_stitched(
+        &self,
+        view: &render::View,
+        options: &Gaussian3dRenderOptions,
+        region_size: [u32; 2],
+    ) -> Result<Gaussian3dRenderOutput<B>, Error> {
+        let mut rows = vec![];
+        let mut row = vec![];
+        let mut row_offset_y = None;
+
+        self.render_regions(view, options, region_size, |region, output| {
+            if row_offset_y
+                .replace(region[1])
+                .is_some_and(|y| y != region[1])
+            {
+                rows.push(Gaussian3dRenderOutput::cat(std::mem::take(&mut row), 1));
+            }
+            row.push(output);
+            Ok(())
+        })?;
+        rows.push(Gaussian3dRenderOutput::cat(row, 1));
+
+        Ok(Gaussian3dRenderOutput::cat(rows, 0))
+    }
+
+    /// Pick the points contributing to a pixel.
+    ///
+    /// It renders only the pixel at `pixel` (`[x, y]`) of the given
+    /// [`view`](render::View), so the image is never read back.
+    /// It returns the indices and the blending weights of its top contributors,
+    /// which are sorted by the weights in descending order.
+    ///
+    /// The count of contributors is at most the
+    /// [contributor count](Gaussian3dRenderOptions::contributor_count) of `options`,
+    /// or [`CONTRIBUTOR_COUNT_MAX`](render::jit::CONTRIBUTOR_COUNT_MAX) if it is zero.
+    /// The [region](Gaussian3dRenderOptions::region) of `options` is ignored.
+    pub fn pick(
+        &self,
+        view: &render::View,
+        pixel: [u32; 2],
+        options: &Gaussian3dRenderOptions,
+    ) -> Result<Vec<(usize, f32)>, Error> {
+        let contributor_count = match options.contributor_count {
+            0 => render::jit::CONTRIBUTOR_COUNT_MAX,
+            contributor_count => contributor_count,
+        };
+        let output = self.render(
+            view,
+            &options
+                .with_contributor_count(contributor_count)
+                .with_region(Some([pixel[0], pixel[1], 1, 1])),
+        )?;
+
+        // NOTE: The contributors are recorded since the count is positive.
+        let contributor_indices = output
+            .contributor_indices_2d
+            .unwrap()
+            .into_data()
+            .convert::<i64>()
+            .into_vec::<i64>()
+            .unwrap();
+        let contributor_weights = output
+            .contributor_weights_2d
+            .unwrap()
+            .into_data()
+            .convert::<f32>()
+            .into_vec::<f32>()
+            .unwrap();
+
+        Ok(contributor_indices
+            .into_iter()
+            .zip(contributor_weights)
+            .filter(|(index, _)| *index >= 0)
+            .map(|(index, weight)| (index as usize, weight))
+            .collect())
     }
 }
 
@@ -192,15 +546,30 @@ where
 {
     /// Render the 3DGS scene with autodiff enabled.
     ///
-    /// It renders a learnable image with the given [`view`](render::View).
+    /// It renders a learnable image with the given [`view`](render::View),
+    /// or a [region](Gaussian3dRenderOptions::region) of it.
     #[must_use = "The gradients should be used"]
     pub fn render(
         &self,
         view: &render::View,
         options: &Gaussian3dRenderOptions,
     ) -> Result<Gaussian3dRenderOutputAutodiff<Autodiff<B>>, Error> {
+        self.check_features()?;
+        self.check_filters_3d()?;
+        self.check_labels()?;
+        self.check_appearances(view, options)?;
+
         let device = &self.device();
         let colors_sh = self.colors_sh.val().into_primitive().tensor();
+        let features = self
+            .features
+            .as_ref()
+            .map(|features| features.val().into_primitive().tensor());
+        let filters_3d = self.get_inner_filters_3d().into_primitive().tensor();
+        let labels = self
+            .labels
+            .as_ref()
+            .map(|labels| labels.val().inner().into_primitive());
         let opacities = self.opacities.val().into_primitive().tensor();
         let positions = self.positions.val().into_primitive().tensor();
         let rotations = self.rotations.val().into_primitive().tensor();
@@ -209,7 +578,13 @@ where
         let input = render::forward::RenderInput {
             device: device.to_owned(),
             point_count: self.point_count() as u64,
+            label_count: self.label_count() as u32,
             colors_sh: colors_sh.primitive,
+            features: features
+                .as_ref()
+                .map(|features| features.primitive.to_owned()),
+            filters_3d: filters_3d.primitive,
+            labels,
             opacities: opacities.primitive,
             positions: positions.primitive,
             rotations: rotations.primitive,
@@ -218,6 +593,70 @@ where
 
         let output = Self::render_forward(input, view, options)?;
 
+        // I_y'
+        let image_size_y = output.state.region_size_y as usize;
+        // I_x'
+        let image_size_x = output.state.region_size_x as usize;
+        // 3 + 1 + 1 (+ D)
+        let channel_count = 5 + output
+            .features_2d
+            .as_ref()
+            .map_or(0, |features_2d| B::float_shape(features_2d).dims[2]);
+        // [I_y', I_x', 3 + 1 + 1 (+ D)]
+        //
+        // The differentiable outputs are concatenated into one.
+        let outputs_2d = Tensor::<B, 3>::cat(
+            [
+                Tensor::new(TensorPrimitive::Float(output.colors_rgb_2d)),
+                Tensor::<B, 2>::new(TensorPrimitive::Float(output.depths_2d))
+                    .unsqueeze_dim(2),
+                Tensor::<B, 2>::new(TensorPrimitive::Float(
+                    output.state.transmittances.to_owned(),
+                ))
+                .neg()
+                .add_scalar(1.0)
+                .unsqueeze_dim(2),
+            ]
+            .into_iter()
+            .chain(
+                output
+                    .features_2d
+                    .map(|features_2d| Tensor::new(TensorPrimitive::Float(features_2d))),
+            )
+            .collect(),
+            2,
+        )
+        .into_primitive()
+        .tensor();
+        // It is untracked if the features are absent.
+        let features_node = features.map_or_else(
+            || {
+                Tensor::<Autodiff<B>, 1>::empty([1], device)
+                    .into_primitive()
+                    .tensor()
+                    .node
+            },
+            |features| features.node,
+        );
+
+        // It refers to the gradient of the focal lengths.
+        let focal_lengths_grad_ref =
+            Tensor::<Autodiff<B>, 1>::empty([2], device).set_require_grad(true);
+        let focal_lengths_grad_ref_id = focal_lengths_grad_ref
+            .to_owned()
+            .into_primitive()
+            .tensor()
+            .node
+            .id;
+        // It refers to the norm of the absolute gradients of the 2D positions.
+        let positions_2d_grad_abs_norm_ref =
+            Tensor::<Autodiff<B>, 1>::empty([1], device).set_require_grad(true);
+        let positions_2d_grad_abs_norm_ref_id = positions_2d_grad_abs_norm_ref
+            .to_owned()
+            .into_primitive()
+            .tensor()
+            .node
+            .id;
         // It refers to the gradient norm of the 2D positions.
         let positions_2d_grad_norm_ref =
             Tensor::<Autodiff<B>, 1>::empty([1], device).set_require_grad(true);
@@ -227,8 +666,31 @@ where
             .tensor()
             .node
             .id;
+        // It refers to the gradient of the view transform in tangent space.
+        let view_transform_grad_ref =
+            Tensor::<Autodiff<B>, 1>::empty([6], device).set_require_grad(true);
+        let view_transform_grad_ref_id = view_transform_grad_ref
+            .to_owned()
+            .into_primitive()
+            .tensor()
+            .node
+            .id;
+        let contributor_indices_2d = output.contributor_indices_2d.map(Tensor::new);
+        let contributor_weights_2d =
+            output.contributor_weights_2d.map(|contributor_weights_2d| {
+                Tensor::from_inner(Tensor::new(TensorPrimitive::Float(
+                    contributor_weights_2d,
+                )))
+            });
+        let depths_2d_median = Tensor::from_inner(Tensor::new(TensorPrimitive::Float(
+            output.depths_2d_median,
+        )));
+        let label_coverages_2d = output.label_coverages_2d.map(|label_coverages_2d| {
+            Tensor::from_inner(Tensor::new(TensorPrimitive::Float(label_coverages_2d)))
+        });
+        let labels_2d = output.labels_2d.map(Tensor::new);
         let radii = Tensor::new(output.state.radii.to_owned());
-        let colors_rgb_2d = Tensor::new(TensorPrimitive::Float(
+        let outputs_2d = Tensor::<Autodiff<B>, 3>::new(TensorPrimitive::Float(
             match Gaussian3dRenderBackwardOp::<B, Self>::default()
                 .prepare::<NoCheckpointing>([
                     colors_sh.node,
@@ -236,6 +698,7 @@ where
                     positions.node,
                     rotations.node,
                     scalings.node,
+                    features_node,
                 ])
                 .compute_bound()
                 .stateful()
@@ -249,10 +712,13 @@ where
 
                     prep.finish(
                         Gaussian3dRenderBackwardState {
+                            focal_lengths_grad_ref_id,
                             inner: output.state,
+                            positions_2d_grad_abs_norm_ref_id,
                             positions_2d_grad_norm_ref_id,
+                            view_transform_grad_ref_id,
                         },
-                        output.colors_rgb_2d,
+                        outputs_2d,
                     )
                 },
                 OpsKind::UnTracked(prep) => {
@@ -262,27 +728,56 @@ where
                         "render > autodiff untracked",
                     );
 
-                    prep.finish(output.colors_rgb_2d)
+                    prep.finish(outputs_2d)
                 },
             },
         ));
+        let colors_rgb_2d = self.transform_appearance(
+            outputs_2d
+                .to_owned()
+                .slice([0..image_size_y, 0..image_size_x, 0..3]),
+            view,
+            options,
+        );
+        let depths_2d = outputs_2d
+            .to_owned()
+            .slice([0..image_size_y, 0..image_size_x, 3..4])
+            .squeeze(2);
+        let alphas_2d = outputs_2d
+            .to_owned()
+            .slice([0..image_size_y, 0..image_size_x, 4..5])
+            .squeeze(2);
+        let features_2d = (channel_count > 5).then(|| {
+            outputs_2d.slice([0..image_size_y, 0..image_size_x, 5..channel_count])
+        });
 
         Ok(Gaussian3dRenderOutputAutodiff {
+            alphas_2d,
             colors_rgb_2d,
+            contributor_indices_2d,
+            contributor_weights_2d,
+            depths_2d,
+            depths_2d_median,
+            features_2d,
+            focal_lengths_grad_ref,
+            label_coverages_2d,
+            labels_2d,
+            positions_2d_grad_abs_norm_ref,
             positions_2d_grad_norm_ref,
             radii,
+            view_transform_grad_ref,
         })
     }
 }
 
-impl<B: Backend, R: Gaussian3dRenderer<B>> Backward<B, 5>
+impl<B: Backend, R: Gaussian3dRenderer<B>> Backward<B, 6>
     for Gaussian3dRenderBackwardOp<B, R>
 {
     type State = Gaussian3dRenderBackwardState<B>;
 
     fn backward(
         self,
-        ops: Ops<Self::State, 5>,
+        ops: Ops<Self::State, 6>,
         grads: &mut Gradients,
         _checkpointer: &mut Checkpointer,
     ) {
@@ -292,13 +787,46 @@ impl<B: Backend, R: Gaussian3dRenderer<B>> Backward<B, 5>
             "render > backward",
         );
 
-        let colors_rgb_2d_grad = grads.consume::<B>(&ops.node);
+        // [I_y, I_x, 3 + 1 + 1 (+ D)]
+        let outputs_2d_grad =
+            Tensor::<B, 3>::new(TensorPrimitive::Float(grads.consume::<B>(&ops.node)));
 
         if ops.parents.iter().all(Option::is_none) {
             return;
         }
 
-        let output = R::render_backward(ops.state.inner, colors_rgb_2d_grad);
+        let [image_size_y, image_size_x, channel_count] = outputs_2d_grad.dims();
+        let colors_rgb_2d_grad = outputs_2d_grad
+            .to_owned()
+            .slice([0..image_size_y, 0..image_size_x, 0..3])
+            .into_primitive()
+            .tensor();
+        let depths_2d_grad = outputs_2d_grad
+            .to_owned()
+            .slice([0..image_size_y, 0..image_size_x, 3..4])
+            .squeeze::<2>(2)
+            .into_primitive()
+            .tensor();
+        let alphas_2d_grad = outputs_2d_grad
+            .to_owned()
+            .slice([0..image_size_y, 0..image_size_x, 4..5])
+            .squeeze::<2>(2)
+            .into_primitive()
+            .tensor();
+        let features_2d_grad = (channel_count > 5).then(|| {
+            outputs_2d_grad
+                .slice([0..image_size_y, 0..image_size_x, 5..channel_count])
+                .into_primitive()
+                .tensor()
+        });
+
+        let output = R::render_backward(
+            ops.state.inner,
+            alphas_2d_grad,
+            colors_rgb_2d_grad,
+            depths_2d_grad,
+            features_2d_grad,
+        );
         if let Some(node) = &ops.parents[0] {
             grads.register::<B>(node.id, output.colors_sh_grad);
         }
@@ -314,12 +842,32 @@ impl<B: Backend, R: Gaussian3dRenderer<B>> Backward<B, 5>
         if let Some(node) = &ops.parents[4] {
             grads.register::<B>(node.id, output.scalings_grad);
         }
+        if let (Some(node), Some(features_grad)) = (&ops.parents[5], output.features_grad)
+        {
+            grads.register::<B>(node.id, features_grad);
+        }
 
-        // The gradient norm of the 2D positions will be obtained later.
+        // The gradient norms of the 2D positions will be obtained later.
+        if let Some(positions_2d_grad_abs_norm) = output.positions_2d_grad_abs_norm {
+            grads.register::<B>(
+                ops.state.positions_2d_grad_abs_norm_ref_id,
+                positions_2d_grad_abs_norm,
+            );
+        }
         grads.register::<B>(
             ops.state.positions_2d_grad_norm_ref_id,
             output.positions_2d_grad_norm,
         );
+
+        // The gradients of the view will be obtained later.
+        grads.register::<B>(
+            ops.state.focal_lengths_grad_ref_id,
+            output.focal_lengths_grad,
+        );
+        grads.register::<B>(
+            ops.state.view_transform_grad_ref_id,
+            output.view_transform_grad,
+        );
     }
 }
 
@@ -332,7 +880,26 @@ impl<B: Backend> fmt::Debug for Gaussian3dScene<B> {
             .field("device", &self.device())
             .field("point_count", &self.point_count())
             .field("size", &self.size_readable())
+            .field(
+                "appearances.dims()",
+                &self
+                    .appearances
+                    .as_ref()
+                    .map(|appearances| appearances.dims()),
+            )
             .field("colors_sh.dims()", &self.colors_sh.dims())
+            .field(
+                "features.dims()",
+                &self.features.as_ref().map(|features| features.dims()),
+            )
+            .field(
+                "filters_3d.dims()",
+                &self.filters_3d.as_ref().map(|filters_3d| filters_3d.dims()),
+            )
+            .field(
+                "labels.dims()",
+                &self.labels.as_ref().map(|labels| labels.dims()),
+            )
             .field("opacities.dims()", &self.opacities.dims())
             .field("positions.dims()", &self.positions.dims())
             .field("rotations.dims()", &self.rotations.dims())
@@ -350,22 +917,72 @@ impl<B: Backend> Default for Gaussian3dScene<B> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    const VIEW: render::View = render::View {
-        field_of_view_x: 1.39,
-        field_of_view_y: 0.88,
-        image_height: 600,
-        image_width: 900,
-        view_id: 0,
-        view_position: [1.86, 0.45, 2.92],
-        view_transform: [
-            [-0.99, 0.08, -0.10, 0.0],
-            [0.06, 0.99, 0.05, 0.000],
-            [0.10, 0.05, -0.99, 0.00],
-            [1.47, -0.69, 3.08, 1.00],
-        ],
-    };
+    use super::test_util::*;
+
+    #[test]
+    fn region_render_ndarray() {
+        let scene = scene_sixstars::<NdArray>(&Default::default());
+        let target = scene.render(&VIEW, &Default::default()).unwrap();
+
+        let mut regions = vec![];
+        scene
+            .render_regions(&VIEW, &Default::default(), [256, 160], |region, output| {
+                assert_eq!(
+                    output.colors_rgb_2d.dims(),
+                    [region[3] as usize, region[2] as usize, 3]
+                );
+                regions.push(region);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(regions.len(), 16);
+        assert_eq!(regions[3], [768, 0, 132, 160]);
+        assert_eq!(regions[15], [768, 480, 132, 120]);
+
+        let output = scene
+            .render_stitched(&VIEW, &Default::default(), [256, 160])
+            .unwrap();
+        output
+            .alphas_2d
+            .into_data()
+            .assert_eq(&target.alphas_2d.into_data(), true);
+        output
+            .colors_rgb_2d
+            .into_data()
+            .assert_eq(&target.colors_rgb_2d.to_owned().into_data(), true);
+        output
+            .depths_2d
+            .into_data()
+            .assert_eq(&target.depths_2d.into_data(), true);
+        output
+            .depths_2d_median
+            .into_data()
+            .assert_eq(&target.depths_2d_median.into_data(), true);
+
+        let output = scene
+            .render(
+                &VIEW,
+                &Gaussian3dRenderOptions::default().with_region(Some([123, 45, 67, 89])),
+            )
+            .unwrap()
+            .colors_rgb_2d;
+        assert_eq!(output.dims(), [89, 67, 3]);
+        output.into_data().assert_eq(
+            &target
+                .colors_rgb_2d
+                .slice([45..134, 123..190, 0..3])
+                .into_data(),
+            true,
+        );
+
+        for region in [[0, 0, 0, 16], [896, 0, 16, 16], [0, 592, 16, 16]] {
+            let output = scene.render(
+                &VIEW,
+                &Gaussian3dRenderOptions::default().with_region(Some(region)),
+            );
+            assert!(output.is_err());
+        }
+    }
 
     #[test]
     fn default_render_wgpu() {
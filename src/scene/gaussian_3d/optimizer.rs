@@ -0,0 +1,274 @@
+//! 3DGS optimizer record remapping implementation.
+
+pub use super::*;
+pub use burn::{
+    module::ParamId,
+    optim::{AdamState, AdaptorRecord, SimpleOptimizer},
+};
+
+use burn::tensor::{Element, Numeric};
+use std::collections::HashMap;
+
+/// Optimizer states of the points.
+///
+/// The tensors of the states are indexed by the points in the first dimension.
+pub trait PointOptimizerState<B: Backend>: Sized {
+    /// Remap the states of the points.
+    ///
+    /// The states are gathered by `indices`, whose shape is `[P'']`,
+    /// and then the zeroed states of `appended_count` points are appended.
+    fn remap_points(
+        self,
+        indices: Tensor<B, 1, Int>,
+        appended_count: usize,
+    ) -> Self;
+}
+
+/// Remapping of the points.
+///
+/// It can be converted from the kept indices returned by the pruners,
+/// e.g., [`Gaussian3dScene::retain`], or from [`Gaussian3dDensifyOutput`].
+#[derive(Clone, Debug)]
+pub struct Gaussian3dPointRemap<B: Backend> {
+    /// The count of the new points appended after the gathered points.
+    ///
+    /// Their states start from zero.
+    pub appended_count: usize,
+    /// The indices of the points to gather.
+    ///
+    /// The shape is `[P'']`.
+    pub indices: Tensor<B, 1, Int>,
+}
+
+/// Optimizer record remappers
+impl<AB: AutodiffBackend> Gaussian3dScene<AB> {
+    /// Remap the optimizer record after the points change.
+    ///
+    /// The states of [`Gaussian3dScene::colors_sh`], [`Gaussian3dScene::features`],
+    /// [`Gaussian3dScene::opacities`], [`Gaussian3dScene::positions`],
+    /// [`Gaussian3dScene::rotations`] and [`Gaussian3dScene::scalings`]
+    /// are remapped, so the optimizer keeps the momentum of the points
+    /// instead of being reset.
+    /// The other states are unchanged.
+    ///
+    /// The parameter IDs of the scene should be kept while changing the points,
+    /// which holds for [`Gaussian3dScene::densify`] and [`Gaussian3dScene::retain`].
+    pub fn remap_optimizer_record<O>(
+        &self,
+        mut record: HashMap<ParamId, AdaptorRecord<O, AB>>,
+        remap: &Gaussian3dPointRemap<AB>,
+    ) -> HashMap<ParamId, AdaptorRecord<O, AB>>
+    where
+        O: SimpleOptimizer<AB::InnerBackend>,
+        O::State<2>: PointOptimizerState<AB::InnerBackend>,
+    {
+        let remap = remap.inner();
+
+        [
+            Some(&self.colors_sh.id),
+            self.features.as_ref().map(|features| &features.id),
+            Some(&self.opacities.id),
+            Some(&self.positions.id),
+            Some(&self.rotations.id),
+            Some(&self.scalings.id),
+        ]
+        .into_iter()
+        .flatten()
+        .for_each(|id| {
+            if let Some(item) = record.remove(id) {
+                let state = item
+                    .into_state::<2>()
+                    .remap_points(remap.indices.to_owned(), remap.appended_count);
+                record.insert(id.to_owned(), AdaptorRecord::from_state(state));
+            }
+        });
+
+        #[cfg(all(debug_assertions, not(test)))]
+        log::debug!(
+            target: "gausplat::renderer::gaussian_3d::scene",
+            "remap_optimizer_record",
+        );
+
+        record
+    }
+}
+
+impl<B: Backend> PointOptimizerState<B> for AdamState<B, 2> {
+    fn remap_points(
+        mut self,
+        indices: Tensor<B, 1, Int>,
+        appended_count: usize,
+    ) -> Self {
+        self.momentum.moment_1 =
+            remap_points(self.momentum.moment_1, indices.to_owned(), appended_count);
+        self.momentum.moment_2 =
+            remap_points(self.momentum.moment_2, indices, appended_count);
+        self
+    }
+}
+
+impl<AB: AutodiffBackend> Gaussian3dPointRemap<AB> {
+    /// The remapping on the inner backend,
+    /// e.g., for the optimizer record or [`Gaussian3dDensityStats`].
+    #[inline]
+    pub fn inner(&self) -> Gaussian3dPointRemap<AB::InnerBackend> {
+        Gaussian3dPointRemap {
+            appended_count: self.appended_count,
+            indices: self.indices.to_owned().inner(),
+        }
+    }
+}
+
+impl<B: Backend> From<Tensor<B, 1, Int>> for Gaussian3dPointRemap<B> {
+    #[inline]
+    fn from(indices: Tensor<B, 1, Int>) -> Self {
+        Self {
+            appended_count: 0,
+            indices,
+        }
+    }
+}
+
+impl<B: Backend> From<Gaussian3dDensifyOutput<B>> for Gaussian3dPointRemap<B> {
+    #[inline]
+    fn from(output: Gaussian3dDensifyOutput<B>) -> Self {
+        let point_count = output.indices.dims()[0];
+        Self {
+            appended_count: point_count - output.kept_count,
+            indices: output.indices.slice([0..output.kept_count]),
+        }
+    }
+}
+
+/// Gathering the states of the points and appending the zeroed ones.
+pub(crate) fn remap_points<B: Backend, const D: usize, K: Numeric<B>>(
+    states: Tensor<B, D, K>,
+    indices: Tensor<B, 1, Int>,
+    appended_count: usize,
+) -> Tensor<B, D, K>
+where
+    K::Elem: Element,
+{
+    let device = &states.device();
+    let mut shape = states.dims();
+    shape[0] = appended_count;
+    let states = states.select(0, indices);
+    if appended_count == 0 {
+        return states;
+    }
+    Tensor::cat(vec![states, Tensor::zeros(shape, device)], 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remap_optimizer_record_adam() {
+        use burn::{
+            backend::NdArray,
+            optim::{AdamConfig, GradientsParams, Optimizer},
+        };
+
+        type AB = Autodiff<NdArray>;
+
+        let device = Default::default();
+        let mut scene = Gaussian3dScene::<AB>::from_points(
+            vec![
+                Point {
+                    color_rgb: [1.0, 0.0, 0.0],
+                    position: [-0.5, 0.0, 4.0],
+                },
+                Point {
+                    color_rgb: [0.0, 1.0, 0.0],
+                    position: [0.5, 0.0, 4.0],
+                },
+                Point {
+                    color_rgb: [0.0, 0.0, 1.0],
+                    position: [0.0, 0.5, 4.0],
+                },
+            ],
+            &device,
+        );
+        scene.set_opacities(Tensor::from_floats([[0.001], [0.5], [0.5]], &device));
+        let mut optimizer = AdamConfig::new().init::<AB, Gaussian3dScene<AB>>();
+
+        fn step<O: Optimizer<Gaussian3dScene<AB>, AB>>(
+            scene: Gaussian3dScene<AB>,
+            optimizer: &mut O,
+        ) -> Gaussian3dScene<AB> {
+            let loss = scene.colors_sh.val().sum()
+                + scene.opacities.val().sum()
+                + scene.positions.val().powf_scalar(2.0).sum()
+                + scene.rotations.val().sum()
+                + scene.scalings.val().sum();
+            let grads = GradientsParams::from_grads(loss.backward(), &scene);
+            optimizer.step(1e-3, scene, grads)
+        }
+
+        fn moment_1<O>(
+            record: &HashMap<ParamId, AdaptorRecord<O, AB>>,
+            id: &ParamId,
+        ) -> Tensor<NdArray, 2>
+        where
+            O: SimpleOptimizer<NdArray, State<2> = AdamState<NdArray, 2>>,
+        {
+            record
+                .get(id)
+                .unwrap()
+                .to_owned()
+                .into_state::<2>()
+                .momentum
+                .moment_1
+        }
+
+        scene = step(scene, &mut optimizer);
+        let record = optimizer.to_record();
+        let positions_moment_1 = moment_1(&record, &scene.positions.id);
+        assert_eq!(positions_moment_1.dims(), [3, 3]);
+
+        // Pruning
+        let indices = scene.prune_by_opacities(0.005);
+        let record = scene.remap_optimizer_record(record, &indices.into());
+        moment_1(&record, &scene.positions.id)
+            .into_data()
+            .assert_approx_eq(&positions_moment_1.slice([1..3, 0..3]).into_data(), 6);
+        assert_eq!(moment_1(&record, &scene.colors_sh.id).dims(), [2, 48]);
+        assert_eq!(moment_1(&record, &scene.opacities.id).dims(), [2, 1]);
+        assert_eq!(moment_1(&record, &scene.rotations.id).dims(), [2, 4]);
+        assert_eq!(moment_1(&record, &scene.scalings.id).dims(), [2, 3]);
+
+        optimizer = optimizer.load_record(record);
+        scene = step(scene, &mut optimizer);
+
+        // Densifying
+        let record = optimizer.to_record();
+        let positions_moment_1 = moment_1(&record, &scene.positions.id);
+        let output = scene
+            .densify(
+                Tensor::from_floats([1.0, 0.0], &device),
+                &Gaussian3dDensifyOptions::new(1e3),
+            )
+            .unwrap();
+        let record = scene.remap_optimizer_record(record, &output.into());
+        let positions_moment_1_remapped = moment_1(&record, &scene.positions.id);
+        assert_eq!(positions_moment_1_remapped.dims(), [3, 3]);
+        positions_moment_1_remapped
+            .to_owned()
+            .slice([0..2, 0..3])
+            .into_data()
+            .assert_approx_eq(&positions_moment_1.into_data(), 6);
+        assert_eq!(
+            positions_moment_1_remapped
+                .slice([2..3, 0..3])
+                .abs()
+                .sum()
+                .into_scalar(),
+            0.0
+        );
+
+        optimizer = optimizer.load_record(record);
+        scene = step(scene, &mut optimizer);
+        assert_eq!(scene.point_count(), 3);
+    }
+}
@@ -2,7 +2,7 @@
 
 pub use super::*;
 
-use burn::tensor::activation;
+use burn::tensor::{activation, ElementConversion, Int};
 use humansize::{format_size, BINARY};
 
 /// Outer property value getters
@@ -171,6 +171,21 @@ impl<B: Backend> Gaussian3dScene<B> {
 
 /// Inner property value setters
 impl<B: Backend> Gaussian3dScene<B> {
+    /// Setting inner values for [`Gaussian3dScene::appearances`]
+    #[inline]
+    pub fn set_inner_appearances(
+        &mut self,
+        appearances: Tensor<B, 3>,
+    ) -> &mut Self {
+        let id = self
+            .appearances
+            .as_ref()
+            .map(|appearances| appearances.id.to_owned())
+            .unwrap_or_default();
+        self.appearances = Some(Param::initialized(id, appearances));
+        self
+    }
+
     /// Setting inner values for [`Gaussian3dScene::colors_sh`]
     #[inline]
     pub fn set_inner_colors_sh(
@@ -181,6 +196,50 @@ impl<B: Backend> Gaussian3dScene<B> {
         self
     }
 
+    /// Setting inner values for [`Gaussian3dScene::features`]
+    #[inline]
+    pub fn set_inner_features(
+        &mut self,
+        features: Tensor<B, 2>,
+    ) -> &mut Self {
+        let id = self
+            .features
+            .as_ref()
+            .map(|features| features.id.to_owned())
+            .unwrap_or_default();
+        self.features = Some(Param::initialized(id, features));
+        self
+    }
+
+    /// Setting inner values for [`Gaussian3dScene::filters_3d`]
+    #[inline]
+    pub fn set_inner_filters_3d(
+        &mut self,
+        filters_3d: Tensor<B, 2>,
+    ) -> &mut Self {
+        self.filters_3d = Some(filters_3d.detach());
+        self
+    }
+
+    /// Setting inner values for [`Gaussian3dScene::labels`]
+    ///
+    /// It also updates [`Gaussian3dScene::label_count()`],
+    /// which reads the largest label back from the device.
+    pub fn set_inner_labels(
+        &mut self,
+        labels: Tensor<B, 1, Int>,
+    ) -> &mut Self {
+        let id = self
+            .labels
+            .as_ref()
+            .map(|labels| labels.id.to_owned())
+            .unwrap_or_default();
+        let label_max = labels.to_owned().max().into_scalar().elem::<i64>();
+        self.label_count = (label_max + 1).max(1) as usize;
+        self.labels = Some(Param::initialized(id, labels));
+        self
+    }
+
     /// Setting inner values for [`Gaussian3dScene::opacities`]
     #[inline]
     pub fn set_inner_opacities(
@@ -222,8 +281,178 @@ impl<B: Backend> Gaussian3dScene<B> {
     }
 }
 
+/// Filter updaters
+impl<B: Backend> Gaussian3dScene<B> {
+    /// Update [`Gaussian3dScene::filters_3d`] with the given views.
+    ///
+    /// The sampling rate of a point in a view is `F / D` pixels per unit length,
+    /// where `F` is the larger focal length and `D` is the depth.
+    /// The 3D filter of a point is the squared reciprocal of its maximum sampling rate
+    /// among the views where it lies within the clip planes of `options`
+    /// and the image with a margin of 15%.
+    ///
+    /// The points outside all the views take the largest 3D filter.
+    /// If no point lies within any view, the 3D filters are all zero,
+    /// which leaves the points unsmoothed.
+    pub fn update_filters_3d<'a>(
+        &mut self,
+        views: impl IntoIterator<Item = &'a render::View>,
+        options: &Gaussian3dRenderOptions,
+    ) -> &mut Self {
+        const MARGIN: f64 = 0.15;
+
+        let device = &self.device();
+        let point_count = self.point_count();
+        // [P, 3]
+        let positions = self.get_positions().detach();
+        // [P, 1]
+        let mut sampling_rates = Tensor::<B, 2>::zeros([point_count, 1], device);
+
+        for view in views {
+            let focal_length_x = view.focal_length_x();
+            let focal_length_y = view.focal_length_y();
+            let principal_point_x = view.principal_point_x();
+            let principal_point_y = view.principal_point_y();
+            let image_size_x = view.image_width as f64;
+            let image_size_y = view.image_height as f64;
+            let view_transform = view.view_transform;
+
+            // Pv[P, 3] = Pw[P, 3] * Rv^t[3, 3] + Tv^t[1, 3]
+
+            let view_rotation = Tensor::<B, 2>::from_data(
+                TensorData::new(
+                    view_transform[..3]
+                        .iter()
+                        .flat_map(|column| column[..3].iter().map(|&c| c as f32))
+                        .collect(),
+                    [3, 3],
+                ),
+                device,
+            );
+            let view_translation = Tensor::<B, 2>::from_data(
+                TensorData::new(
+                    view_transform[3][..3].iter().map(|&c| c as f32).collect(),
+                    [1, 3],
+                ),
+                device,
+            );
+            let positions_in_view =
+                positions.to_owned().matmul(view_rotation) + view_translation;
+            let [x, y, z] = [0, 1, 2].map(|i| {
+                positions_in_view
+                    .to_owned()
+                    .slice([0..point_count, i..i + 1])
+            });
+
+            // D = Pv.z
+            //
+            // D = |Pv| for the equirectangular camera model
+
+            let depths = if view.camera_model.is_equirectangular() {
+                positions_in_view.powf_scalar(2.0).sum_dim(1).sqrt()
+            } else {
+                z
+            };
+            let mut is_visible = depths
+                .to_owned()
+                .greater_equal_elem(options.depth_near)
+                .float()
+                * depths.to_owned().lower_elem(options.depth_far).float();
+
+            // Checking if the normalized position is within the image with a margin
+            //
+            // Pn[2] = [Pv.x, Pv.y] for the orthographic camera model
+            // The image is the whole sphere for the equirectangular camera model
+
+            if !view.camera_model.is_equirectangular() {
+                let (x, y) = if view.camera_model.is_orthographic() {
+                    (x, y)
+                } else {
+                    (x / depths.to_owned(), y / depths.to_owned())
+                };
+                let bound_x_max =
+                    (image_size_x * (1.0 + MARGIN) - principal_point_x) / focal_length_x;
+                let bound_x_min =
+                    -(principal_point_x + image_size_x * MARGIN) / focal_length_x;
+                let bound_y_max =
+                    (image_size_y * (1.0 + MARGIN) - principal_point_y) / focal_length_y;
+                let bound_y_min =
+                    -(principal_point_y + image_size_y * MARGIN) / focal_length_y;
+                is_visible = is_visible
+                    * x.to_owned().greater_equal_elem(bound_x_min).float()
+                    * x.lower_equal_elem(bound_x_max).float()
+                    * y.to_owned().greater_equal_elem(bound_y_min).float()
+                    * y.lower_equal_elem(bound_y_max).float();
+            }
+
+            // ν[P, 1] = F / D
+            //
+            // ν = F for the orthographic camera model
+
+            let focal_length = focal_length_x.max(focal_length_y);
+            let sampling_rates_in_view = if view.camera_model.is_orthographic() {
+                Tensor::full([point_count, 1], focal_length, device)
+            } else {
+                depths.recip() * focal_length
+            };
+            sampling_rates = sampling_rates.max_pair(sampling_rates_in_view * is_visible);
+        }
+
+        // F_3d[P, 1] = 1 / ν^2
+        //
+        // The points outside all the views take the minimum sampling rate.
+
+        let is_invisible = sampling_rates.to_owned().equal_elem(0.0);
+        let sampling_rate_min = sampling_rates
+            .to_owned()
+            .mask_fill(is_invisible.to_owned(), f32::INFINITY)
+            .min()
+            .into_scalar()
+            .elem::<f32>();
+        let filters_3d = sampling_rates
+            .mask_fill(is_invisible, sampling_rate_min)
+            .powf_scalar(-2.0);
+
+        #[cfg(all(debug_assertions, not(test)))]
+        log::debug!(
+            target: "gausplat::renderer::gaussian_3d::scene",
+            "update_filters_3d",
+        );
+
+        self.set_inner_filters_3d(filters_3d)
+    }
+}
+
 /// Attribute getters
 impl<B: Backend> Gaussian3dScene<B> {
+    /// Check if the shape of [`Gaussian3dScene::filters_3d`] is `[P, 1]`.
+    pub(crate) fn check_filters_3d(&self) -> Result<(), Error> {
+        let filters_3d = match &self.filters_3d {
+            Some(filters_3d) => filters_3d,
+            None => return Ok(()),
+        };
+
+        let dims = filters_3d.dims();
+        let dims_target = [self.point_count(), 1];
+        if dims != dims_target {
+            return Err(Error::MismatchedTensorShape(
+                dims.into(),
+                dims_target.into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The inner values of [`Gaussian3dScene::filters_3d`], or zeros if they are absent.
+    ///
+    /// The zero 3D filters leave the points unsmoothed.
+    pub(crate) fn get_inner_filters_3d(&self) -> Tensor<B, 2> {
+        match &self.filters_3d {
+            Some(filters_3d) => filters_3d.to_owned(),
+            None => Tensor::zeros([self.point_count(), 1], &self.device()),
+        }
+    }
+
     /// The device.
     #[inline]
     pub fn device(&self) -> B::Device {
@@ -0,0 +1,656 @@
+//! 3DGS test fixtures.
+
+pub use super::*;
+
+use burn::tensor::BasicOps;
+
+/// The distorted camera models.
+pub const CAMERA_MODELS_DISTORTED: [render::CameraModel; 2] = [
+    render::CameraModel::OpenCv {
+        k1: -0.2,
+        k2: 0.05,
+        k3: 0.0,
+        k4: 0.0,
+        p1: 0.01,
+        p2: -0.01,
+    },
+    render::CameraModel::Fisheye {
+        k1: 0.1,
+        k2: -0.05,
+        k3: 0.01,
+        k4: 0.0,
+    },
+];
+
+/// A view of the scene `sixstars`.
+pub const VIEW: render::View = render::View {
+    camera_model: render::CameraModel::Pinhole,
+    field_of_view_x: 1.39,
+    field_of_view_y: 0.88,
+    image_height: 600,
+    image_width: 900,
+    principal_point_offset_x: 0.0,
+    principal_point_offset_y: 0.0,
+    view_id: 0,
+    view_position: [1.86, 0.45, 2.92],
+    view_transform: [
+        [-0.99, 0.08, -0.10, 0.0],
+        [0.06, 0.99, 0.05, 0.000],
+        [0.10, 0.05, -0.99, 0.00],
+        [1.47, -0.69, 3.08, 1.00],
+    ],
+};
+
+/// A view at the origin looking along the z-axis.
+pub const VIEW_IDENTITY: render::View = render::View {
+    camera_model: render::CameraModel::Pinhole,
+    field_of_view_x: 1.0,
+    field_of_view_y: 1.0,
+    image_height: 32,
+    image_width: 32,
+    principal_point_offset_x: 0.0,
+    principal_point_offset_y: 0.0,
+    view_id: 0,
+    view_position: [0.0, 0.0, 0.0],
+    view_transform: [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ],
+};
+
+/// A view like [`VIEW_IDENTITY`] with an irregular image size
+/// and an offset principal point.
+pub const VIEW_RANDOM: render::View = render::View {
+    field_of_view_x: 1.2,
+    field_of_view_y: 0.9,
+    image_height: 48,
+    image_width: 64,
+    principal_point_offset_x: 1.5,
+    principal_point_offset_y: -0.5,
+    ..VIEW_IDENTITY
+};
+
+/// The scene `sixstars` decoded from the example file.
+pub fn scene_sixstars<B: Backend>(device: &B::Device) -> Gaussian3dScene<B> {
+    let source = include_bytes!("../../../examples/data/3dgs-ply/sixstars.3dgs.ply");
+    Gaussian3dScene::decode_polygon(&mut source.as_slice(), device).unwrap()
+}
+
+/// A scene of the given points.
+///
+/// Each point is `(color_rgb, position, opacity, scaling)`,
+/// where the scaling is isotropic.
+/// The parameters require gradients on autodiff backends.
+pub fn scene_from_points<B: Backend>(
+    points: &[([f32; 3], [f64; 3], f32, f32)],
+    device: &B::Device,
+) -> Gaussian3dScene<B> {
+    let point_count = points.len();
+    let mut scene = Gaussian3dScene::<B>::from_points(
+        points
+            .iter()
+            .map(|&(color_rgb, position, _, _)| Point {
+                color_rgb,
+                position,
+            })
+            .collect(),
+        device,
+    );
+
+    let opacities = Tensor::<B, 2>::from_data(
+        TensorData::new(
+            points.iter().map(|&(_, _, opacity, _)| opacity).collect(),
+            [point_count, 1],
+        ),
+        device,
+    );
+    let scalings = Tensor::<B, 2>::from_data(
+        TensorData::new(
+            points.iter().map(|&(_, _, _, scaling)| scaling).collect(),
+            [point_count, 1],
+        ),
+        device,
+    )
+    .repeat_dim(1, 3);
+    scene
+        .set_inner_opacities(
+            Gaussian3dScene::make_inner_opacities(opacities).set_require_grad(true),
+        )
+        .set_inner_scalings(
+            Gaussian3dScene::make_inner_scalings(scalings).set_require_grad(true),
+        );
+    scene
+}
+
+/// A scene of pseudo-random points in front of [`VIEW_IDENTITY`].
+///
+/// It has all the properties, including the features and the labels.
+pub fn scene_random<B: Backend>(
+    point_count: usize,
+    device: &B::Device,
+) -> Gaussian3dScene<B> {
+    // Sampling deterministically in `[min, max)`
+    let mut index = 0_u32;
+    let mut sample = |count: usize, min: f32, max: f32| {
+        (0..count)
+            .map(|_| {
+                index += 1;
+                let value = ((index as f64 * 12.9898).sin() * 43758.5453).fract().abs();
+                min + (max - min) * value as f32
+            })
+            .collect::<Vec<_>>()
+    };
+    let tensor = |values: Vec<f32>, shape: [usize; 2]| {
+        Tensor::<B, 2>::from_data(TensorData::new(values, shape), device)
+            .set_require_grad(true)
+    };
+
+    let depths = sample(point_count, 3.0, 8.0);
+    let offsets = sample(point_count * 2, -0.5, 0.5);
+    let positions = depths
+        .iter()
+        .zip(offsets.chunks(2))
+        .flat_map(|(&depth, offset)| [offset[0] * depth, offset[1] * depth, depth])
+        .collect();
+    let labels = (0..point_count as i32).map(|index| index % 5 - 1).collect();
+
+    let mut scene = Gaussian3dScene::<B>::default();
+    scene
+        .set_inner_colors_sh(tensor(
+            sample(point_count * SH_COUNT_MAX * 3, -0.5, 0.5),
+            [point_count, SH_COUNT_MAX * 3],
+        ))
+        .set_inner_features(tensor(sample(point_count * 3, -1.0, 1.0), [point_count, 3]))
+        .set_inner_labels(Tensor::from_data(
+            TensorData::new::<i32, _>(labels, [point_count]).convert::<B::IntElem>(),
+            device,
+        ))
+        .set_inner_opacities(tensor(sample(point_count, -1.0, 3.0), [point_count, 1]))
+        .set_inner_positions(tensor(positions, [point_count, 3]))
+        .set_inner_rotations(tensor(sample(point_count * 4, -1.0, 1.0), [point_count, 4]))
+        .set_inner_scalings(tensor(
+            sample(point_count * 3, -3.5, -1.5),
+            [point_count, 3],
+        ));
+    scene
+}
+
+/// A scene of large, rotated and anisotropic points in front of [`VIEW_IDENTITY`].
+///
+/// The points cover the whole image with the alphas above the threshold,
+/// so the render is smooth for the finite differences.
+pub fn scene_smooth<B: Backend>(device: &B::Device) -> Gaussian3dScene<B> {
+    let mut scene = scene_from_points::<B>(
+        &[
+            ([1.0, 0.5, 0.0], [0.3, -0.2, 4.0], 0.5, 1.0),
+            ([0.0, 0.5, 1.0], [-0.4, 0.3, 5.0], 0.6, 1.0),
+            ([0.5, 1.0, 0.2], [0.2, 0.4, 6.0], 0.7, 1.0),
+        ],
+        device,
+    );
+    scene
+        .set_inner_rotations(
+            Tensor::from_data(
+                TensorData::from([
+                    [0.1_f32, 0.2, -0.1, 0.95],
+                    [-0.2, 0.1, 0.3, 0.9],
+                    [0.3, -0.1, 0.2, 0.9],
+                ]),
+                device,
+            )
+            .set_require_grad(true),
+        )
+        .set_inner_scalings(
+            Gaussian3dScene::make_inner_scalings(Tensor::from_data(
+                TensorData::from([[1.5_f32, 2.0, 1.4], [2.4, 1.8, 2.0], [2.2, 2.8, 2.1]]),
+                device,
+            ))
+            .set_require_grad(true),
+        );
+    scene
+}
+
+/// The scene copied to another backend.
+///
+/// The learnable parameters require gradients on autodiff backends.
+fn scene_to<BI: Backend, BO: Backend>(
+    scene: &Gaussian3dScene<BI>,
+    device: &BO::Device,
+) -> Gaussian3dScene<BO> {
+    let tensor =
+        |tensor: Tensor<BI, 2>| Tensor::<BO, 2>::from_data(tensor.into_data(), device);
+
+    let mut output = Gaussian3dScene::<BO>::default();
+    output
+        .set_inner_colors_sh(tensor(scene.colors_sh.val()).set_require_grad(true))
+        .set_inner_opacities(tensor(scene.opacities.val()).set_require_grad(true))
+        .set_inner_positions(tensor(scene.positions.val()).set_require_grad(true))
+        .set_inner_rotations(tensor(scene.rotations.val()).set_require_grad(true))
+        .set_inner_scalings(tensor(scene.scalings.val()).set_require_grad(true));
+    if let Some(features) = &scene.features {
+        output.set_inner_features(tensor(features.val()).set_require_grad(true));
+    }
+    if let Some(filters_3d) = &scene.filters_3d {
+        output.set_inner_filters_3d(tensor(filters_3d.to_owned()));
+    }
+    if let Some(labels) = &scene.labels {
+        output.set_inner_labels(Tensor::from_data(
+            labels.val().into_data().convert::<BO::IntElem>(),
+            device,
+        ));
+    }
+    output
+}
+
+/// The values of the tensor.
+pub fn to_vec<B: Backend, const D: usize, K: BasicOps<B>>(
+    tensor: Tensor<B, D, K>
+) -> Vec<f64> {
+    tensor.into_data().convert::<f64>().to_vec::<f64>().unwrap()
+}
+
+/// Assert that each value is close to the target relatively.
+///
+/// The values much smaller than the largest target
+/// are compared relative to 1% of it instead.
+/// The `message` names the values on failure.
+pub fn assert_relative_eq(
+    output: &[f64],
+    target: &[f64],
+    tolerance: f64,
+    message: &str,
+) {
+    assert_eq!(output.len(), target.len(), "{message}");
+    let target_max = target.iter().fold(0.0_f64, |max, t| max.max(t.abs()));
+    for (index, (o, t)) in output.iter().zip(target).enumerate() {
+        let scale = t.abs().max(target_max * 1e-2);
+        assert!(
+            (o - t).abs() <= scale * tolerance,
+            "{message}[{index}]: {o}, {t}\noutput: {output:?}\ntarget: {target:?}",
+        );
+    }
+}
+
+/// Assert that the renders of the scene on NdArray and Wgpu are close,
+/// including the outputs and the gradients.
+pub fn assert_render_ndarray_and_wgpu(
+    scene: &Gaussian3dScene<NdArray>,
+    view: &render::View,
+    options: &Gaussian3dRenderOptions,
+) {
+    let target = render_values(
+        &scene_to::<_, Autodiff<NdArray>>(scene, &Default::default()),
+        view,
+        options,
+    );
+    let output = render_values(
+        &scene_to::<_, Autodiff<Wgpu>>(scene, &Default::default()),
+        view,
+        options,
+    );
+
+    assert_eq!(
+        output.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        target.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+    );
+    for ((name, output), (_, target)) in output.iter().zip(&target) {
+        // The gradients are accumulated in different orders.
+        let tolerance = match *name {
+            "contributor_indices_2d" | "labels_2d" | "radii" => 0.0,
+            name if name.ends_with("_grad") => 1e-2,
+            _ => 1e-3,
+        };
+        let message = format!("{name} of {:?} with {options:?}", view.camera_model);
+        assert_relative_eq(output, target, tolerance, &message);
+    }
+}
+
+/// Assert that the gradients of the render match the central finite differences.
+///
+/// The gradients of the positions, the rotations, the scalings,
+/// the focal lengths and the view transform are checked per component.
+/// The focal lengths are skipped for the equirectangular camera model,
+/// since they are fixed by the image size.
+pub fn assert_render_grads_finite_difference<B: Backend>(
+    scene: &Gaussian3dScene<Autodiff<B>>,
+    view: &render::View,
+    options: &Gaussian3dRenderOptions,
+    tolerance: f64,
+) where
+    Gaussian3dScene<Autodiff<B>>: Gaussian3dRenderer<B>,
+{
+    const EPSILON: f64 = 1e-2;
+
+    let device = &scene.device();
+    let loss = |scene: &Gaussian3dScene<Autodiff<B>>, view: &render::View| {
+        render_loss_value(&scene.render(view, options).unwrap())
+    };
+    let finite_differences =
+        |count: usize, loss_with_delta: &dyn Fn(usize, f64) -> f64| {
+            (0..count)
+                .map(|index| {
+                    (loss_with_delta(index, EPSILON) - loss_with_delta(index, -EPSILON))
+                        / (2.0 * EPSILON)
+                })
+                .collect::<Vec<_>>()
+        };
+    let camera_model = view.camera_model;
+
+    let output = scene.render(view, options).unwrap();
+    let mut grads = render_loss(&output).backward();
+
+    let properties: [(
+        &str,
+        &Param<Tensor<Autodiff<B>, 2>>,
+        fn(&mut Gaussian3dScene<Autodiff<B>>, Tensor<Autodiff<B>, 2>),
+    ); 3] = [
+        ("positions", &scene.positions, |scene, values| {
+            scene.set_inner_positions(values);
+        }),
+        ("rotations", &scene.rotations, |scene, values| {
+            scene.set_inner_rotations(values);
+        }),
+        ("scalings", &scene.scalings, |scene, values| {
+            scene.set_inner_scalings(values);
+        }),
+    ];
+    for (name, property, set_inner) in properties {
+        let dims = property.dims();
+        let values = to_vec(property.val());
+        let target = finite_differences(values.len(), &|index, delta| {
+            let mut values = values.to_owned();
+            values[index] += delta;
+            let values = values.into_iter().map(|value| value as f32).collect();
+            let mut scene = scene.to_owned();
+            set_inner(
+                &mut scene,
+                Tensor::from_data(TensorData::new(values, dims), device),
+            );
+            loss(&scene, view)
+        });
+        let output = to_vec(property.grad_remove(&mut grads).unwrap());
+        let message = format!("{name}_grad of {camera_model:?}");
+        assert_relative_eq(&output, &target, tolerance, &message);
+    }
+
+    if !camera_model.is_equirectangular() {
+        let target = finite_differences(2, &|axis, delta| {
+            let mut focal_lengths = [view.focal_length_x(), view.focal_length_y()];
+            focal_lengths[axis] += delta;
+            let mut view = *view;
+            view.set_intrinsics(
+                focal_lengths[0],
+                focal_lengths[1],
+                view.principal_point_x(),
+                view.principal_point_y(),
+            );
+            loss(scene, &view)
+        });
+        let output = to_vec(
+            output
+                .focal_lengths_grad_ref
+                .grad_remove(&mut grads)
+                .unwrap(),
+        );
+        let message = format!("focal_lengths_grad of {camera_model:?}");
+        assert_relative_eq(&output, &target, tolerance, &message);
+    }
+
+    let target = finite_differences(6, &|index, delta| {
+        let mut transform_delta = [0.0; 6];
+        transform_delta[index] = delta;
+        let mut view = *view;
+        view.update_transform(&transform_delta);
+        loss(scene, &view)
+    });
+    let output = to_vec(
+        output
+            .view_transform_grad_ref
+            .grad_remove(&mut grads)
+            .unwrap(),
+    );
+    let message = format!("view_transform_grad of {camera_model:?}");
+    assert_relative_eq(&output, &target, tolerance, &message);
+}
+
+/// The views to check the gradients with [`assert_render_grads_finite_difference`].
+pub fn views_finite_difference() -> Vec<render::View> {
+    let mut views = CAMERA_MODELS_DISTORTED
+        .map(|camera_model| render::View {
+            camera_model,
+            ..VIEW_IDENTITY
+        })
+        .to_vec();
+    views.push(render::View {
+        camera_model: render::CameraModel::Orthographic,
+        field_of_view_x: 2.0,
+        field_of_view_y: 2.0,
+        ..VIEW_IDENTITY
+    });
+    views.push(render::View {
+        field_of_view_x: 1.1,
+        field_of_view_y: 0.9,
+        principal_point_offset_x: 1.5,
+        principal_point_offset_y: -1.0,
+        ..VIEW_IDENTITY
+    });
+    let mut view = VIEW_IDENTITY;
+    view.update_transform(&[0.1, -0.05, 0.2, 0.05, -0.04, 0.03]);
+    views.push(view);
+    views
+}
+
+/// Assert that the norm of 2D positions absolute gradient
+/// is the sum of the absolute gradients of each pixel.
+///
+/// The point is isotropic and on the optical axis, so its conic is diagonal,
+/// and the gradient of each pixel along an axis has the sign of its offset.
+/// Weighting the pixels by the signs of the offsets along an axis
+/// sums the absolute gradients along it and cancels the other axis out.
+pub fn assert_positions_2d_grad_abs<B: Backend>(device: &B::Device)
+where
+    Gaussian3dScene<Autodiff<B>>: Gaussian3dRenderer<B>,
+{
+    let scene = scene_from_points::<Autodiff<B>>(
+        &[([1.0, 0.5, 0.0], [0.0, 0.0, 4.0], 0.6, 0.3)],
+        device,
+    );
+    let [image_height, image_width] =
+        [VIEW_IDENTITY.image_height, VIEW_IDENTITY.image_width].map(|s| s as usize);
+    let grad_norms = |weight: fn(f32, f32) -> f32, options: Gaussian3dRenderOptions| {
+        let weights = (0..image_height)
+            .flat_map(|y| (0..image_width).map(move |x| weight(x as f32, y as f32)))
+            .collect::<Vec<_>>();
+        let weights = Tensor::<Autodiff<B>, 2>::from_data(
+            TensorData::new(weights, [image_height, image_width]),
+            device,
+        );
+        let output = scene.render(&VIEW_IDENTITY, &options).unwrap();
+        let mut grads = (output.alphas_2d.to_owned() * weights).sum().backward();
+        [
+            output
+                .positions_2d_grad_abs_norm_ref
+                .grad_remove(&mut grads),
+            output.positions_2d_grad_norm_ref.grad_remove(&mut grads),
+        ]
+        .map(|grad| grad.map(|grad| to_vec(grad)[0]))
+    };
+
+    // The centers of the pixels are at the integer coordinates,
+    // so the 2D mean is at the center of the 32x32 image, (15.5, 15.5).
+    let [_, positions_2d_grad_abs_norm_x] =
+        grad_norms(|x, _| (x - 15.5).signum(), Default::default());
+    let [_, positions_2d_grad_abs_norm_y] =
+        grad_norms(|_, y| (y - 15.5).signum(), Default::default());
+    let target = positions_2d_grad_abs_norm_x
+        .unwrap()
+        .hypot(positions_2d_grad_abs_norm_y.unwrap());
+
+    // The weights alternate in sign, so the gradients partly cancel out,
+    // while the absolute gradients of each pixel are the same.
+    let checkerboard = |x: f32, y: f32| if (x + y) % 2.0 == 0.0 { 1.0 } else { -1.0 };
+    let options = Gaussian3dRenderOptions::default().with_positions_2d_grad_abs(true);
+    let [output, positions_2d_grad_norm] = grad_norms(checkerboard, options);
+    let output = output.unwrap();
+    assert!(target > 0.0);
+    assert!(output > 2.0 * positions_2d_grad_norm.unwrap());
+    assert_relative_eq(&[output], &[target], 1e-3, "positions_2d_grad_abs_norm");
+
+    let [output, positions_2d_grad_norm] = grad_norms(checkerboard, Default::default());
+    assert!(output.is_none());
+    assert!(positions_2d_grad_norm.is_some());
+}
+
+/// The weights of the render loss for the values of the given dims.
+///
+/// They vary smoothly and rapidly across the pixels and the channels,
+/// so that the loss is sensitive to the displacements in any direction.
+fn render_loss_weights(dims: [usize; 3]) -> Vec<f32> {
+    let [height, width, channel_count] = dims;
+    (0..height)
+        .flat_map(|y| {
+            (0..width).flat_map(move |x| {
+                (0..channel_count).map(move |c| {
+                    (x as f32 * 0.4 + y as f32 * 0.5 + c as f32 * 2.1).sin()
+                })
+            })
+        })
+        .collect()
+}
+
+/// The render loss, which is the weighted sum of
+/// the colors, the alphas, the depths and the features.
+fn render_loss<B: Backend>(
+    output: &Gaussian3dRenderOutputAutodiff<Autodiff<B>>
+) -> Tensor<Autodiff<B>, 1> {
+    let device = &output.colors_rgb_2d.device();
+    let weighted_sum = |values: Tensor<Autodiff<B>, 3>| {
+        let dims = values.dims();
+        let weights = TensorData::new(render_loss_weights(dims), dims);
+        (values * Tensor::from_data(weights, device)).sum()
+    };
+
+    let mut loss = weighted_sum(output.colors_rgb_2d.to_owned())
+        + weighted_sum(output.alphas_2d.to_owned().unsqueeze_dim(2))
+        + weighted_sum(output.depths_2d.to_owned().unsqueeze_dim(2));
+    if let Some(features_2d) = &output.features_2d {
+        loss = loss + weighted_sum(features_2d.to_owned());
+    }
+    loss
+}
+
+/// The value of [`render_loss`] accumulated in `f64` for the finite differences.
+fn render_loss_value<B: Backend>(
+    output: &Gaussian3dRenderOutputAutodiff<Autodiff<B>>
+) -> f64 {
+    let weighted_sum = |values: Tensor<Autodiff<B>, 3>| {
+        let weights = render_loss_weights(values.dims());
+        to_vec(values)
+            .into_iter()
+            .zip(weights)
+            .map(|(value, weight)| value * weight as f64)
+            .sum::<f64>()
+    };
+
+    let mut loss = weighted_sum(output.colors_rgb_2d.to_owned())
+        + weighted_sum(output.alphas_2d.to_owned().unsqueeze_dim(2))
+        + weighted_sum(output.depths_2d.to_owned().unsqueeze_dim(2));
+    if let Some(features_2d) = &output.features_2d {
+        loss += weighted_sum(features_2d.to_owned());
+    }
+    loss
+}
+
+/// The rendered values and the gradients of [`render_loss`] of the scene.
+fn render_values<B: Backend>(
+    scene: &Gaussian3dScene<Autodiff<B>>,
+    view: &render::View,
+    options: &Gaussian3dRenderOptions,
+) -> Vec<(&'static str, Vec<f64>)>
+where
+    Gaussian3dScene<Autodiff<B>>: Gaussian3dRenderer<B>,
+{
+    let output = scene.render(view, options).unwrap();
+
+    let mut values = vec![
+        ("alphas_2d", to_vec(output.alphas_2d.to_owned())),
+        ("colors_rgb_2d", to_vec(output.colors_rgb_2d.to_owned())),
+        ("depths_2d", to_vec(output.depths_2d.to_owned())),
+        (
+            "depths_2d_median",
+            to_vec(output.depths_2d_median.to_owned()),
+        ),
+        ("radii", to_vec(output.radii.to_owned())),
+    ];
+    values.extend(
+        [
+            (
+                "contributor_weights_2d",
+                output.contributor_weights_2d.to_owned(),
+            ),
+            ("features_2d", output.features_2d.to_owned()),
+            ("label_coverages_2d", output.label_coverages_2d.to_owned()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, to_vec(value?)))),
+    );
+    values.extend(
+        output
+            .contributor_indices_2d
+            .to_owned()
+            .map(|value| ("contributor_indices_2d", to_vec(value))),
+    );
+    values.extend(
+        output
+            .labels_2d
+            .to_owned()
+            .map(|value| ("labels_2d", to_vec(value))),
+    );
+
+    let mut grads = render_loss(&output).backward();
+    values.extend(
+        [
+            ("colors_sh_grad", scene.colors_sh.grad_remove(&mut grads)),
+            (
+                "features_grad",
+                scene
+                    .features
+                    .as_ref()
+                    .and_then(|features| features.grad_remove(&mut grads)),
+            ),
+            ("opacities_grad", scene.opacities.grad_remove(&mut grads)),
+            ("positions_grad", scene.positions.grad_remove(&mut grads)),
+            ("rotations_grad", scene.rotations.grad_remove(&mut grads)),
+            ("scalings_grad", scene.scalings.grad_remove(&mut grads)),
+        ]
+        .into_iter()
+        .filter_map(|(name, grad)| Some((name, to_vec(grad?)))),
+    );
+    values.extend(
+        [
+            (
+                "focal_lengths_grad",
+                output.focal_lengths_grad_ref.grad_remove(&mut grads),
+            ),
+            (
+                "positions_2d_grad_abs_norm_grad",
+                output
+                    .positions_2d_grad_abs_norm_ref
+                    .grad_remove(&mut grads),
+            ),
+            (
+                "positions_2d_grad_norm_grad",
+                output.positions_2d_grad_norm_ref.grad_remove(&mut grads),
+            ),
+            (
+                "view_transform_grad",
+                output.view_transform_grad_ref.grad_remove(&mut grads),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, grad)| Some((name, to_vec(grad?)))),
+    );
+    values
+}